@@ -46,8 +46,9 @@ impl TryFrom<VerificationRequest> for solidity::multi_part::VerificationRequest
         Ok(Self {
             deployed_bytecode,
             creation_bytecode,
-            compiler_version,
+            compiler_version: Some(compiler_version),
             content: value.content.try_into()?,
+            settings_search: None,
             chain_id: Default::default(),
         })
     }
@@ -0,0 +1,229 @@
+mod rpc;
+mod store;
+mod types;
+
+use crate::{
+    proto::{solidity_verifier_server::SolidityVerifier, verify_response, Source},
+    services::SolidityVerifierService,
+    settings::EtherscanSettings,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use rpc::RpcClients;
+use serde_json::json;
+use std::sync::Arc;
+use store::{JobStatus, NewJobError, Store};
+use tokio::sync::Semaphore;
+use tonic::Request;
+use types::{ApiResponse, CodeFormat, Params, VerifiedContract, VerifySourceCode};
+
+/// Standard json inputs may be much larger than the default payload limit
+const MAX_BODY_SIZE: usize = 50 * 1024 * 1024;
+
+/// Etherscan-compatible api used by Hardhat and Foundry verification plugins.
+/// Supports `verifysourcecode`, `checkverifystatus` and `getsourcecode` actions
+/// of `contract` module for Solidity contracts.
+pub struct EtherscanApi {
+    solidity: Arc<SolidityVerifierService>,
+    rpc: RpcClients,
+    default_chain_id: Option<String>,
+    store: Store,
+    /// Limits the number of verifications running in background
+    pending: Arc<Semaphore>,
+}
+
+impl EtherscanApi {
+    pub fn new(settings: EtherscanSettings, solidity: Arc<SolidityVerifierService>) -> Self {
+        let default_chain_id = match settings.default_chain_id {
+            Some(chain_id) => Some(chain_id),
+            // the only configured chain is used if requests do not specify one
+            None if settings.rpc_urls.len() == 1 => settings.rpc_urls.keys().next().cloned(),
+            None => None,
+        };
+        Self {
+            solidity,
+            rpc: RpcClients::new(settings.rpc_urls),
+            default_chain_id,
+            store: Store::default(),
+            pending: Arc::new(Semaphore::new(settings.max_pending_verifications.get())),
+        }
+    }
+
+    fn chain_id(&self, params: &Params) -> Result<String, ApiResponse> {
+        let chain_id = params
+            .get("chainid")
+            .or(self.default_chain_id.as_deref())
+            .ok_or_else(|| ApiResponse::not_ok("Missing or invalid parameter chainid"))?;
+        if !self.rpc.is_supported(chain_id) {
+            return Err(ApiResponse::not_ok(format!(
+                "Chain {chain_id} is not supported"
+            )));
+        }
+        Ok(chain_id.to_string())
+    }
+
+    async fn handle(self: Arc<Self>, params: Params) -> ApiResponse {
+        let result = match (params.get("module"), params.get("action")) {
+            (Some("contract"), Some("verifysourcecode")) => self.verify_source_code(&params).await,
+            (Some("contract"), Some("checkverifystatus")) => self.check_verify_status(&params),
+            (Some("contract"), Some("getsourcecode")) => self.get_source_code(&params),
+            _ => Err(ApiResponse::not_ok(
+                "Error! Missing Or invalid Module name / Action name",
+            )),
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    /// Checks the contract is deployed and starts its verification in background.
+    /// Returns guid of the verification job
+    async fn verify_source_code(
+        self: Arc<Self>,
+        params: &Params,
+    ) -> Result<ApiResponse, ApiResponse> {
+        let chain_id = self.chain_id(params)?;
+        let request = VerifySourceCode::from_params(params).map_err(ApiResponse::not_ok)?;
+        if self
+            .store
+            .contract(&chain_id, &request.contract_address)
+            .is_some()
+        {
+            return Err(ApiResponse::not_ok("Contract source code already verified"));
+        }
+        let permit = self.pending.clone().try_acquire_owned().map_err(|_| {
+            ApiResponse::not_ok("Too many pending verifications, please try again later")
+        })?;
+
+        let bytecode = self
+            .rpc
+            .get_code(&chain_id, &request.contract_address)
+            .await
+            .map_err(|err| {
+                tracing::error!(chain_id = %chain_id, "failed to get contract code: {err:#}");
+                ApiResponse::not_ok("Unable to get contract code")
+            })?;
+        if bytecode.trim_start_matches("0x").is_empty() {
+            return Err(ApiResponse::not_ok(format!(
+                "Unable to locate ContractCode at {}",
+                request.contract_address
+            )));
+        }
+
+        // the check above is repeated atomically with the creation of the job
+        let guid = self
+            .store
+            .new_job(&chain_id, &request.contract_address)
+            .map_err(|err| match err {
+                NewJobError::AlreadyVerified => {
+                    ApiResponse::not_ok("Contract source code already verified")
+                }
+                NewJobError::AlreadyPending => {
+                    ApiResponse::not_ok("Contract source code verification is already pending")
+                }
+            })?;
+        let api = self.clone();
+        let job = guid.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let contract = match api.verify(&chain_id, &request, bytecode).await {
+                Ok(source) => Some(VerifiedContract {
+                    source,
+                    constructor_arguments: request.constructor_arguments.clone(),
+                    license_type: request.license_type.clone(),
+                }),
+                Err(reason) => {
+                    tracing::info!(
+                        guid = %job,
+                        chain_id = %chain_id,
+                        "verification failed: {reason}"
+                    );
+                    None
+                }
+            };
+            api.store
+                .finish_job(&job, &chain_id, &request.contract_address, contract);
+        });
+        Ok(ApiResponse::ok(guid))
+    }
+
+    async fn verify(
+        &self,
+        chain_id: &str,
+        request: &VerifySourceCode,
+        bytecode: String,
+    ) -> Result<Source, String> {
+        let response = match request.code_format {
+            CodeFormat::SingleFile => {
+                let request = request.multi_part_request(chain_id, bytecode);
+                self.solidity.verify_multi_part(Request::new(request)).await
+            }
+            CodeFormat::StandardJsonInput => {
+                let request = request.standard_json_request(chain_id, bytecode);
+                self.solidity
+                    .verify_standard_json(Request::new(request))
+                    .await
+            }
+        }
+        .map_err(|status| status.message().to_string())?
+        .into_inner();
+
+        if response.status() != verify_response::Status::Success {
+            return Err(response.message);
+        }
+        let source = response
+            .source
+            .ok_or_else(|| "verification succeeded without source".to_string())?;
+        match request.contract_name() {
+            Some(name) if name != source.contract_name => Err(format!(
+                "deployed bytecode matches contract {} instead of {}",
+                source.contract_name, name
+            )),
+            _ => Ok(source),
+        }
+    }
+
+    fn check_verify_status(&self, params: &Params) -> Result<ApiResponse, ApiResponse> {
+        let guid = params
+            .get("guid")
+            .ok_or_else(|| ApiResponse::not_ok("Missing or invalid parameter guid"))?;
+        let response = match self.store.job_status(guid) {
+            Some(JobStatus::Pending) => ApiResponse::not_ok("Pending in queue"),
+            Some(JobStatus::Verified) => ApiResponse::ok("Pass - Verified"),
+            Some(JobStatus::Failed) => ApiResponse::not_ok("Fail - Unable to verify"),
+            None => ApiResponse::not_ok("Unknown UID"),
+        };
+        Ok(response)
+    }
+
+    fn get_source_code(&self, params: &Params) -> Result<ApiResponse, ApiResponse> {
+        let chain_id = self.chain_id(params)?;
+        let address = params
+            .get("address")
+            .ok_or_else(|| ApiResponse::not_ok("Missing or invalid parameter address"))?;
+        let item = match self.store.contract(&chain_id, address) {
+            Some(contract) => contract.source_code_item(),
+            None => VerifiedContract::not_verified_item(),
+        };
+        Ok(ApiResponse::ok(json!([item])))
+    }
+}
+
+async fn handle(
+    api: web::Data<EtherscanApi>,
+    request: HttpRequest,
+    body: web::Bytes,
+) -> HttpResponse {
+    let params = Params::parse(request.query_string(), &body);
+    let response = api.into_inner().handle(params).await;
+    HttpResponse::Ok().json(response)
+}
+
+/// Both GET and POST requests are accepted, parameters are read
+/// from the query string and from urlencoded body
+pub fn route_etherscan(config: &mut web::ServiceConfig, api: Arc<EtherscanApi>) {
+    config.service(
+        web::resource("/api")
+            .app_data(web::Data::from(api))
+            .app_data(web::PayloadConfig::new(MAX_BODY_SIZE))
+            .route(web::get().to(handle))
+            .route(web::post().to(handle)),
+    );
+}
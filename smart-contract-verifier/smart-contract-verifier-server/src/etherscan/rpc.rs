@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<String>,
+    error: Option<RpcError>,
+}
+
+/// JSON-RPC clients of the nodes of supported chains
+#[derive(Debug, Clone)]
+pub struct RpcClients {
+    client: reqwest::Client,
+    rpc_urls: BTreeMap<String, Url>,
+}
+
+impl RpcClients {
+    pub fn new(rpc_urls: BTreeMap<String, Url>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            rpc_urls,
+        }
+    }
+
+    pub fn is_supported(&self, chain_id: &str) -> bool {
+        self.rpc_urls.contains_key(chain_id)
+    }
+
+    /// Deployed bytecode of the address at the latest block. Is `0x` if there is no contract
+    pub async fn get_code(&self, chain_id: &str, address: &str) -> anyhow::Result<String> {
+        let url = self
+            .rpc_urls
+            .get(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("chain {chain_id} is not supported"))?;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getCode",
+            "params": [address, "latest"],
+        });
+        let response: RpcResponse = self
+            .client
+            .post(url.clone())
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(anyhow::anyhow!("eth_getCode failed: {}", error.message)),
+            (Some(code), None) => Ok(code),
+            (None, None) => Err(anyhow::anyhow!("eth_getCode returned no result")),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use super::types::VerifiedContract;
+use std::{
+    collections::{
+        hash_map::{DefaultHasher, RandomState},
+        HashMap, HashSet, VecDeque,
+    },
+    hash::{BuildHasher, Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+/// Maximal number of kept verification jobs. The oldest jobs are removed first
+const MAX_JOBS: usize = 10000;
+/// Maximal number of kept verified contracts. The oldest contracts are removed first
+const MAX_CONTRACTS: usize = 10000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobStatus {
+    Pending,
+    Verified,
+    /// The reason of the failure is logged, Etherscan responds without it
+    Failed,
+}
+
+/// Reason the verification of a contract can't be started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewJobError {
+    AlreadyVerified,
+    AlreadyPending,
+}
+
+type ContractKey = (String, String);
+
+#[derive(Debug, Default)]
+struct State {
+    statuses: HashMap<String, JobStatus>,
+    order: VecDeque<String>,
+    /// Keyed by chain id and lowercase address
+    contracts: HashMap<ContractKey, VerifiedContract>,
+    contracts_order: VecDeque<ContractKey>,
+    /// Contracts of pending jobs, so that a contract is verified by one job at a time
+    pending: HashSet<ContractKey>,
+}
+
+/// In-memory storage of verification jobs and contracts verified through the api.
+/// Both are capped, the oldest ones are removed first
+#[derive(Debug)]
+pub struct Store {
+    state: Mutex<State>,
+    hasher: RandomState,
+    counter: AtomicU64,
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Self {
+            state: Default::default(),
+            hasher: RandomState::new(),
+            counter: AtomicU64::new(0),
+        }
+    }
+}
+
+fn contract_key(chain_id: &str, address: &str) -> ContractKey {
+    (chain_id.to_string(), address.to_lowercase())
+}
+
+impl Store {
+    /// Unpredictable identifier of 50 hex characters, like Etherscan guids
+    fn new_guid(&self) -> String {
+        let counter = self.counter.fetch_add(1, Ordering::Relaxed);
+        let now = SystemTime::now();
+        (0u8..4)
+            .map(|part| {
+                let mut hasher: DefaultHasher = self.hasher.build_hasher();
+                (counter, now, part).hash(&mut hasher);
+                format!("{:016x}", hasher.finish())
+            })
+            .collect::<String>()[..50]
+            .to_string()
+    }
+
+    /// Creates a pending job of the contract and returns its guid.
+    /// Fails if the contract is already verified or is being verified
+    pub fn new_job(&self, chain_id: &str, address: &str) -> Result<String, NewJobError> {
+        let key = contract_key(chain_id, address);
+        let mut state = self.state.lock().expect("poisoned lock");
+        if state.contracts.contains_key(&key) {
+            return Err(NewJobError::AlreadyVerified);
+        }
+        if !state.pending.insert(key) {
+            return Err(NewJobError::AlreadyPending);
+        }
+        let guid = self.new_guid();
+        if state.order.len() >= MAX_JOBS {
+            if let Some(oldest) = state.order.pop_front() {
+                state.statuses.remove(&oldest);
+            }
+        }
+        state.order.push_back(guid.clone());
+        state.statuses.insert(guid.clone(), JobStatus::Pending);
+        Ok(guid)
+    }
+
+    /// Finishes the job of the contract, the contract is saved if it was verified
+    pub fn finish_job(
+        &self,
+        guid: &str,
+        chain_id: &str,
+        address: &str,
+        contract: Option<VerifiedContract>,
+    ) {
+        let key = contract_key(chain_id, address);
+        let mut state = self.state.lock().expect("poisoned lock");
+        state.pending.remove(&key);
+        let status = match contract {
+            Some(contract) => {
+                if state.contracts_order.len() >= MAX_CONTRACTS {
+                    if let Some(oldest) = state.contracts_order.pop_front() {
+                        state.contracts.remove(&oldest);
+                    }
+                }
+                state.contracts_order.push_back(key.clone());
+                state.contracts.insert(key, contract);
+                JobStatus::Verified
+            }
+            None => JobStatus::Failed,
+        };
+        if let Some(job) = state.statuses.get_mut(guid) {
+            *job = status;
+        }
+    }
+
+    pub fn job_status(&self, guid: &str) -> Option<JobStatus> {
+        self.state
+            .lock()
+            .expect("poisoned lock")
+            .statuses
+            .get(guid)
+            .cloned()
+    }
+
+    pub fn contract(&self, chain_id: &str, address: &str) -> Option<VerifiedContract> {
+        self.state
+            .lock()
+            .expect("poisoned lock")
+            .contracts
+            .get(&contract_key(chain_id, address))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn contract() -> VerifiedContract {
+        VerifiedContract {
+            source: Default::default(),
+            constructor_arguments: None,
+            license_type: None,
+        }
+    }
+
+    #[test]
+    fn one_job_per_contract() {
+        let store = Store::default();
+        let guid = store.new_job("1", "0xBEEF").expect("new job");
+        assert_eq!(Some(JobStatus::Pending), store.job_status(&guid));
+        assert_eq!(
+            Err(NewJobError::AlreadyPending),
+            store.new_job("1", "0xbeef")
+        );
+        assert!(store.new_job("2", "0xbeef").is_ok());
+
+        store.finish_job(&guid, "1", "0xBEEF", Some(contract()));
+        assert_eq!(Some(JobStatus::Verified), store.job_status(&guid));
+        assert_eq!(Some(contract()), store.contract("1", "0xbeef"));
+        assert_eq!(
+            Err(NewJobError::AlreadyVerified),
+            store.new_job("1", "0xbeef")
+        );
+
+        let guid = store.new_job("1", "0xdead").expect("new job");
+        store.finish_job(&guid, "1", "0xdead", None);
+        assert_eq!(Some(JobStatus::Failed), store.job_status(&guid));
+        assert_eq!(None, store.contract("1", "0xdead"));
+        assert!(store.new_job("1", "0xdead").is_ok());
+    }
+}
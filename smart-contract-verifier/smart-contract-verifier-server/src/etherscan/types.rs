@@ -0,0 +1,416 @@
+use crate::proto::{
+    BytecodeType, Source, VerificationMetadata, VerifySolidityMultiPartRequest,
+    VerifySolidityStandardJsonRequest,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Maximal number of libraries which may be passed as
+/// `libraryname{i}` and `libraryaddress{i}` parameters
+const MAX_LIBRARIES: usize = 10;
+
+/// Parameters of a request, taken from both query string and urlencoded body.
+/// Body parameters take precedence over query ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(HashMap<String, String>);
+
+impl Params {
+    pub fn parse(query: &str, body: &[u8]) -> Self {
+        let params = url::form_urlencoded::parse(query.as_bytes())
+            .chain(url::form_urlencoded::parse(body))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        Self(params)
+    }
+
+    /// Empty values are treated as absent
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .get(key)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn required(&self, key: &str) -> Result<&str, String> {
+        self.get(key)
+            .ok_or_else(|| format!("Missing or invalid parameter {key}"))
+    }
+}
+
+/// Response body of every action.
+/// `status` is "1" for successful requests and "0" otherwise
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiResponse {
+    pub status: String,
+    pub message: String,
+    pub result: Value,
+}
+
+impl ApiResponse {
+    pub fn ok(result: impl Into<Value>) -> Self {
+        Self {
+            status: "1".to_string(),
+            message: "OK".to_string(),
+            result: result.into(),
+        }
+    }
+
+    pub fn not_ok(result: impl Into<Value>) -> Self {
+        Self {
+            status: "0".to_string(),
+            message: "NOTOK".to_string(),
+            result: result.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeFormat {
+    SingleFile,
+    StandardJsonInput,
+}
+
+/// Parameters of `verifysourcecode` action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifySourceCode {
+    pub chain_id: Option<String>,
+    pub contract_address: String,
+    pub code_format: CodeFormat,
+    pub source_code: String,
+    /// Either `Name` or `path/to/File.sol:Name`
+    pub contract_name: Option<String>,
+    pub compiler_version: String,
+    pub optimization_runs: Option<i32>,
+    pub evm_version: Option<String>,
+    pub constructor_arguments: Option<String>,
+    pub license_type: Option<String>,
+    pub libraries: BTreeMap<String, String>,
+}
+
+impl VerifySourceCode {
+    pub fn from_params(params: &Params) -> Result<Self, String> {
+        let code_format = match params.get("codeformat").unwrap_or("solidity-single-file") {
+            "solidity-single-file" => CodeFormat::SingleFile,
+            "solidity-standard-json-input" => CodeFormat::StandardJsonInput,
+            format => return Err(format!("Unsupported codeformat {format}")),
+        };
+        let optimization_runs = match params.get("optimizationUsed") {
+            Some("1") => {
+                let runs = params.get("runs").unwrap_or("200");
+                Some(
+                    runs.parse()
+                        .map_err(|_| format!("Invalid runs value {runs}"))?,
+                )
+            }
+            _ => None,
+        };
+        let libraries = (1..=MAX_LIBRARIES)
+            .filter_map(|i| {
+                let name = params.get(&format!("libraryname{i}"))?;
+                let address = params.get(&format!("libraryaddress{i}"))?;
+                Some((name.to_string(), address.to_string()))
+            })
+            .collect();
+
+        Ok(Self {
+            chain_id: params.get("chainid").map(str::to_string),
+            contract_address: params.required("contractaddress")?.to_string(),
+            code_format,
+            source_code: params.required("sourceCode")?.to_string(),
+            contract_name: params.get("contractname").map(str::to_string),
+            compiler_version: params.required("compilerversion")?.to_string(),
+            optimization_runs,
+            evm_version: params
+                .get("evmversion")
+                .filter(|version| !version.eq_ignore_ascii_case("default"))
+                .map(str::to_string),
+            // sic, the parameter is misspelled in Etherscan API
+            constructor_arguments: params
+                .get("constructorArguements")
+                .or_else(|| params.get("constructorArguments"))
+                .map(str::to_string),
+            license_type: params.get("licenseType").map(str::to_string),
+            libraries,
+        })
+    }
+
+    /// Name of the contract without the path of its file
+    pub fn contract_name(&self) -> Option<&str> {
+        self.contract_name
+            .as_deref()
+            .map(|name| name.rsplit(':').next().unwrap_or(name))
+    }
+
+    /// Path of the file from `contractname`, or a file named after the contract
+    fn single_file_name(&self) -> String {
+        match self.contract_name.as_deref() {
+            Some(name) => match name.rsplit_once(':') {
+                Some((path, _)) => path.to_string(),
+                None => format!("{name}.sol"),
+            },
+            None => "contract.sol".to_string(),
+        }
+    }
+
+    fn metadata(&self, chain_id: &str) -> Option<VerificationMetadata> {
+        Some(VerificationMetadata {
+            chain_id: chain_id.to_string(),
+            contract_address: self.contract_address.clone(),
+        })
+    }
+
+    pub fn multi_part_request(
+        &self,
+        chain_id: &str,
+        deployed_bytecode: String,
+    ) -> VerifySolidityMultiPartRequest {
+        VerifySolidityMultiPartRequest {
+            bytecode: deployed_bytecode,
+            bytecode_type: BytecodeType::DeployedBytecode.into(),
+            compiler_version: Some(self.compiler_version.clone()),
+            evm_version: self.evm_version.clone(),
+            optimization_runs: self.optimization_runs,
+            source_files: BTreeMap::from([(self.single_file_name(), self.source_code.clone())]),
+            libraries: self.libraries.clone(),
+            metadata: self.metadata(chain_id),
+            search_compiler_settings: None,
+        }
+    }
+
+    pub fn standard_json_request(
+        &self,
+        chain_id: &str,
+        deployed_bytecode: String,
+    ) -> VerifySolidityStandardJsonRequest {
+        VerifySolidityStandardJsonRequest {
+            bytecode: deployed_bytecode,
+            bytecode_type: BytecodeType::DeployedBytecode.into(),
+            compiler_version: self.compiler_version.clone(),
+            input: self.source_code.clone(),
+            metadata: self.metadata(chain_id),
+        }
+    }
+}
+
+/// Contract verified through the api
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedContract {
+    pub source: Source,
+    pub constructor_arguments: Option<String>,
+    pub license_type: Option<String>,
+}
+
+impl VerifiedContract {
+    /// Single source file is returned as is, multiple files are returned
+    /// as standard json input wrapped into double braces as Etherscan does
+    fn source_code(&self, settings: &Value) -> String {
+        if self.source.source_files.len() == 1 {
+            if let Some(content) = self.source.source_files.values().next() {
+                return content.clone();
+            }
+        }
+        let sources: BTreeMap<_, _> = self
+            .source
+            .source_files
+            .iter()
+            .map(|(path, content)| (path, json!({ "content": content })))
+            .collect();
+        let input = json!({
+            "language": "Solidity",
+            "sources": sources,
+            "settings": settings,
+        });
+        format!("{{{input}}}")
+    }
+
+    /// Item of `getsourcecode` result
+    pub fn source_code_item(&self) -> Value {
+        let settings: Value =
+            serde_json::from_str(&self.source.compiler_settings).unwrap_or_default();
+        let optimizer = &settings["optimizer"];
+        let optimization_used = optimizer["enabled"].as_bool().unwrap_or_default();
+        let runs = optimizer["runs"].as_u64().unwrap_or(200);
+        let libraries: Vec<String> = settings["libraries"]
+            .as_object()
+            .into_iter()
+            .flat_map(|files| files.values())
+            .filter_map(Value::as_object)
+            .flat_map(|libraries| libraries.iter())
+            .map(|(name, address)| format!("{}:{}", name, address.as_str().unwrap_or_default()))
+            .collect();
+        let constructor_arguments = self
+            .source
+            .constructor_arguments
+            .as_deref()
+            .or(self.constructor_arguments.as_deref())
+            .unwrap_or_default()
+            .trim_start_matches("0x");
+
+        json!({
+            "SourceCode": self.source_code(&settings),
+            "ABI": self.source.abi.clone().unwrap_or_default(),
+            "ContractName": self.source.contract_name,
+            "CompilerVersion": self.source.compiler_version,
+            "OptimizationUsed": if optimization_used { "1" } else { "0" },
+            "Runs": runs.to_string(),
+            "ConstructorArguments": constructor_arguments,
+            "EVMVersion": settings["evmVersion"].as_str().unwrap_or("Default"),
+            "Library": libraries.join(";"),
+            "LicenseType": self.license_type.clone().unwrap_or_default(),
+            "Proxy": "0",
+            "Implementation": "",
+            "SwarmSource": "",
+        })
+    }
+
+    /// Item of `getsourcecode` result for contracts which are not verified
+    pub fn not_verified_item() -> Value {
+        json!({
+            "SourceCode": "",
+            "ABI": "Contract source code not verified",
+            "ContractName": "",
+            "CompilerVersion": "",
+            "OptimizationUsed": "",
+            "Runs": "",
+            "ConstructorArguments": "",
+            "EVMVersion": "",
+            "Library": "",
+            "LicenseType": "",
+            "Proxy": "0",
+            "Implementation": "",
+            "SwarmSource": "",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::source::{MatchType, SourceType};
+    use pretty_assertions::assert_eq;
+
+    fn params(query: &str, body: &str) -> Params {
+        Params::parse(query, body.as_bytes())
+    }
+
+    #[test]
+    fn parse_params() {
+        let params = params(
+            "module=contract&action=verifysourcecode&chainid=1",
+            "chainid=5&contractname=src%2FFoo.sol%3AFoo&runs=",
+        );
+        assert_eq!(Some("contract"), params.get("module"));
+        assert_eq!(Some("5"), params.get("chainid"));
+        assert_eq!(Some("src/Foo.sol:Foo"), params.get("contractname"));
+        assert_eq!(None, params.get("runs"));
+        assert_eq!(None, params.get("apikey"));
+    }
+
+    #[test]
+    fn verify_source_code_from_params() {
+        let params = params(
+            "module=contract&action=verifysourcecode",
+            "contractaddress=0xcafe&sourceCode=contract%20Foo%20%7B%7D&codeformat=solidity-single-file\
+            &contractname=Foo&compilerversion=v0.8.17%2Bcommit.8df45f5f&optimizationUsed=1&runs=1000\
+            &evmversion=london&constructorArguements=0x1234&licenseType=3\
+            &libraryname1=Lib&libraryaddress1=0xbeef&libraryname2=&libraryaddress2=0xdead",
+        );
+        let request = VerifySourceCode::from_params(&params).unwrap();
+        let expected = VerifySourceCode {
+            chain_id: None,
+            contract_address: "0xcafe".into(),
+            code_format: CodeFormat::SingleFile,
+            source_code: "contract Foo {}".into(),
+            contract_name: Some("Foo".into()),
+            compiler_version: "v0.8.17+commit.8df45f5f".into(),
+            optimization_runs: Some(1000),
+            evm_version: Some("london".into()),
+            constructor_arguments: Some("0x1234".into()),
+            license_type: Some("3".into()),
+            libraries: BTreeMap::from([("Lib".into(), "0xbeef".into())]),
+        };
+        assert_eq!(expected, request);
+
+        let multi_part = request.multi_part_request("1", "0x6080".into());
+        assert_eq!(
+            BTreeMap::from([("Foo.sol".into(), "contract Foo {}".into())]),
+            multi_part.source_files
+        );
+        assert_eq!(Some(1000), multi_part.optimization_runs);
+        assert_eq!(
+            Some(VerificationMetadata {
+                chain_id: "1".into(),
+                contract_address: "0xcafe".into()
+            }),
+            multi_part.metadata
+        );
+
+        let params = params(
+            "",
+            "contractaddress=0xcafe&sourceCode=%7B%7D&codeformat=solidity-standard-json-input\
+            &contractname=src%2FFoo.sol%3AFoo&compilerversion=v0.8.17%2Bcommit.8df45f5f&optimizationUsed=0&evmversion=default",
+        );
+        let request = VerifySourceCode::from_params(&params).unwrap();
+        assert_eq!(CodeFormat::StandardJsonInput, request.code_format);
+        assert_eq!(None, request.optimization_runs);
+        assert_eq!(None, request.evm_version);
+        assert_eq!(Some("Foo"), request.contract_name());
+        let standard_json = request.standard_json_request("1", "0x6080".into());
+        assert_eq!("{}", standard_json.input);
+        assert_eq!(
+            BytecodeType::DeployedBytecode,
+            standard_json.bytecode_type()
+        );
+    }
+
+    #[test]
+    fn invalid_verify_source_code_params() {
+        let missing_address = params("", "sourceCode=a&compilerversion=v0.8.17%2Bcommit.8df45f5f");
+        assert_eq!(
+            Err("Missing or invalid parameter contractaddress".to_string()),
+            VerifySourceCode::from_params(&missing_address)
+        );
+        let vyper = params(
+            "",
+            "contractaddress=0xcafe&sourceCode=a&compilerversion=0.3.7&codeformat=vyper-json",
+        );
+        assert_eq!(
+            Err("Unsupported codeformat vyper-json".to_string()),
+            VerifySourceCode::from_params(&vyper)
+        );
+    }
+
+    #[test]
+    fn source_code_item() {
+        let contract = VerifiedContract {
+            source: Source {
+                file_name: "src/Foo.sol".into(),
+                contract_name: "Foo".into(),
+                compiler_version: "v0.8.17+commit.8df45f5f".into(),
+                compiler_settings: r#"{"optimizer":{"enabled":true,"runs":1000},"evmVersion":"london","libraries":{"src/Foo.sol":{"Lib":"0xbeef"}}}"#.into(),
+                source_type: SourceType::Solidity.into(),
+                source_files: BTreeMap::from([
+                    ("src/Foo.sol".into(), "contract Foo {}".into()),
+                    ("src/Lib.sol".into(), "library Lib {}".into()),
+                ]),
+                abi: Some("[]".into()),
+                constructor_arguments: None,
+                match_type: MatchType::Full.into(),
+            },
+            constructor_arguments: Some("0x1234".into()),
+            license_type: Some("3".into()),
+        };
+        let item = contract.source_code_item();
+        assert_eq!("Foo", item["ContractName"]);
+        assert_eq!("1", item["OptimizationUsed"]);
+        assert_eq!("1000", item["Runs"]);
+        assert_eq!("london", item["EVMVersion"]);
+        assert_eq!("Lib:0xbeef", item["Library"]);
+        assert_eq!("1234", item["ConstructorArguments"]);
+        let source_code = item["SourceCode"].as_str().unwrap();
+        assert!(source_code.starts_with("{{") && source_code.ends_with("}}"));
+        let input: Value = serde_json::from_str(&source_code[1..source_code.len() - 1]).unwrap();
+        assert_eq!("library Lib {}", input["sources"]["src/Lib.sol"]["content"]);
+    }
+}
@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "verification_jobs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    /// Serialized [`super::JobRequest`]
+    #[sea_orm(column_type = "Text")]
+    pub request: String,
+    pub priority: i32,
+    pub status: JobStatus,
+    pub attempts: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    /// Serialized `VerifyResponse` of finished jobs
+    #[sea_orm(column_type = "Text", nullable)]
+    pub response: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    /// Pending jobs are not taken by workers before that time
+    pub run_after: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+pub enum JobStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "running")]
+    Running,
+    #[sea_orm(string_value = "finished")]
+    Finished,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+mod entity;
+mod queue;
+mod worker;
+
+pub use entity::{JobStatus, Model as Job};
+pub use queue::JobQueue;
+pub use worker::spawn_workers;
+
+use crate::proto::{
+    VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest, VerifyVyperMultiPartRequest,
+    VerifyVyperStandardJsonRequest,
+};
+use serde::{Deserialize, Serialize};
+
+/// Request of a verification job, saved into the queue as json
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "request", rename_all = "snake_case")]
+pub enum JobRequest {
+    SolidityMultiPart(VerifySolidityMultiPartRequest),
+    SolidityStandardJson(VerifySolidityStandardJsonRequest),
+    VyperMultiPart(VerifyVyperMultiPartRequest),
+    VyperStandardJson(VerifyVyperStandardJsonRequest),
+}
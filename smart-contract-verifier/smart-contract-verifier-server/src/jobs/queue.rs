@@ -0,0 +1,269 @@
+use super::{
+    entity::{self, JobStatus},
+    JobRequest,
+};
+use crate::{proto::VerifyResponse, settings::JobsSettings};
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, Index},
+    ActiveValue::Set,
+    ColumnTrait, ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Schema,
+};
+use tokio::sync::Notify;
+
+/// Persistent queue of verification jobs.
+///
+/// Jobs are taken by conditional updates, so several instances of the service
+/// may share the same database.
+pub struct JobQueue {
+    db: DatabaseConnection,
+    max_attempts: i32,
+    retry_delay: Duration,
+    job_timeout: Duration,
+    finished_job_ttl: Duration,
+    /// Wakes up idle workers on submission of a new job
+    submitted: Notify,
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let mut table = Schema::new(backend).create_table_from_entity(entity::Entity);
+    table.if_not_exists();
+    db.execute(backend.build(&table)).await?;
+    let index = Index::create()
+        .if_not_exists()
+        .name("verification_jobs_status_priority_index")
+        .table(entity::Entity)
+        .col(entity::Column::Status)
+        .col(entity::Column::Priority)
+        .col(entity::Column::RunAfter)
+        .to_owned();
+    db.execute(backend.build(&index)).await?;
+    Ok(())
+}
+
+impl JobQueue {
+    /// Connects to the database and creates the table of jobs if it does not exist
+    pub async fn connect(settings: &JobsSettings) -> anyhow::Result<Self> {
+        let database_url = settings
+            .database_url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("verification jobs require `database_url`"))?;
+        let mut options = ConnectOptions::new(database_url);
+        options.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+        let db = Database::connect(options).await?;
+        create_tables(&db).await?;
+        Ok(Self {
+            db,
+            max_attempts: settings.max_attempts.get() as i32,
+            retry_delay: Duration::seconds(settings.retry_delay as i64),
+            job_timeout: Duration::seconds(settings.job_timeout as i64),
+            finished_job_ttl: Duration::seconds(settings.finished_job_ttl as i64),
+            submitted: Notify::new(),
+        })
+    }
+
+    pub async fn submit(
+        &self,
+        request: &JobRequest,
+        priority: i32,
+    ) -> anyhow::Result<entity::Model> {
+        let now = now();
+        let job = entity::Model {
+            id: uuid::Uuid::new_v4().to_string(),
+            request: serde_json::to_string(request)?,
+            priority,
+            status: JobStatus::Pending,
+            attempts: 0,
+            error: None,
+            response: None,
+            created_at: now,
+            updated_at: now,
+            run_after: now,
+        };
+        entity::Entity::insert(entity::ActiveModel {
+            id: Set(job.id.clone()),
+            request: Set(job.request.clone()),
+            priority: Set(job.priority),
+            status: Set(job.status),
+            attempts: Set(job.attempts),
+            error: Set(None),
+            response: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            run_after: Set(now),
+        })
+        .exec(&self.db)
+        .await?;
+        self.submitted.notify_one();
+        Ok(job)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<entity::Model>, DbErr> {
+        entity::Entity::find_by_id(id.to_owned())
+            .one(&self.db)
+            .await
+    }
+
+    /// Resolves when a new job is submitted
+    pub async fn wait_submitted(&self) {
+        self.submitted.notified().await
+    }
+
+    /// Takes the pending job with the highest priority and marks it as running.
+    /// Jobs of the same priority are taken in order of submission
+    pub async fn take(&self) -> Result<Option<entity::Model>, DbErr> {
+        loop {
+            let now = now();
+            let candidate = entity::Entity::find()
+                .filter(entity::Column::Status.eq(JobStatus::Pending))
+                .filter(entity::Column::RunAfter.lte(now))
+                .order_by_desc(entity::Column::Priority)
+                .order_by_asc(entity::Column::CreatedAt)
+                .one(&self.db)
+                .await?;
+            let job = match candidate {
+                Some(job) => job,
+                None => return Ok(None),
+            };
+            let attempts = job.attempts + 1;
+            // the job may have been taken by another worker since it was read
+            let result = entity::Entity::update_many()
+                .col_expr(entity::Column::Status, Expr::value(JobStatus::Running))
+                .col_expr(entity::Column::Attempts, Expr::value(attempts))
+                .col_expr(entity::Column::UpdatedAt, Expr::value(now))
+                .filter(entity::Column::Id.eq(job.id.clone()))
+                .filter(entity::Column::Status.eq(JobStatus::Pending))
+                .filter(entity::Column::Attempts.eq(job.attempts))
+                .exec(&self.db)
+                .await?;
+            if result.rows_affected == 1 {
+                return Ok(Some(entity::Model {
+                    status: JobStatus::Running,
+                    attempts,
+                    updated_at: now,
+                    ..job
+                }));
+            }
+        }
+    }
+
+    /// Updates the job if it is still run by the attempt it was taken with.
+    /// Results of attempts considered timed out are discarded
+    async fn complete(
+        &self,
+        job: &entity::Model,
+        status: JobStatus,
+        error: Option<String>,
+        response: Option<String>,
+        run_after: NaiveDateTime,
+    ) -> Result<(), DbErr> {
+        entity::Entity::update_many()
+            .col_expr(entity::Column::Status, Expr::value(status))
+            .col_expr(entity::Column::Error, Expr::value(error))
+            .col_expr(entity::Column::Response, Expr::value(response))
+            .col_expr(entity::Column::UpdatedAt, Expr::value(now()))
+            .col_expr(entity::Column::RunAfter, Expr::value(run_after))
+            .filter(entity::Column::Id.eq(job.id.clone()))
+            .filter(entity::Column::Status.eq(JobStatus::Running))
+            .filter(entity::Column::Attempts.eq(job.attempts))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Period running jobs renew their heartbeats with
+    pub fn heartbeat_interval(&self) -> std::time::Duration {
+        (self.job_timeout / 3)
+            .to_std()
+            .unwrap_or_default()
+            .max(std::time::Duration::from_secs(1))
+    }
+
+    /// Renews the heartbeat of the job if it is still run by the attempt it was taken with,
+    /// so that the job is not considered timed out.
+    /// Returns `false` if the attempt was considered timed out
+    pub async fn heartbeat(&self, job: &entity::Model) -> Result<bool, DbErr> {
+        let result = entity::Entity::update_many()
+            .col_expr(entity::Column::UpdatedAt, Expr::value(now()))
+            .filter(entity::Column::Id.eq(job.id.clone()))
+            .filter(entity::Column::Status.eq(JobStatus::Running))
+            .filter(entity::Column::Attempts.eq(job.attempts))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+
+    pub async fn finish(
+        &self,
+        job: &entity::Model,
+        response: &VerifyResponse,
+    ) -> anyhow::Result<()> {
+        let response = serde_json::to_string(response)?;
+        self.complete(
+            job,
+            JobStatus::Finished,
+            None,
+            Some(response),
+            job.run_after,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Schedules the next attempt of the job if `retry` is set and attempts are not exhausted.
+    /// The delay before the attempt is doubled with every attempt
+    pub async fn fail(&self, job: &entity::Model, error: String, retry: bool) -> Result<(), DbErr> {
+        if retry && job.attempts < self.max_attempts {
+            let factor = 1i32 << (job.attempts - 1).clamp(0, 16);
+            let run_after = now() + self.retry_delay * factor;
+            self.complete(job, JobStatus::Pending, Some(error), None, run_after)
+                .await
+        } else {
+            self.complete(job, JobStatus::Failed, Some(error), None, job.run_after)
+                .await
+        }
+    }
+
+    /// Returns running jobs without heartbeats for longer than the timeout back to the queue,
+    /// e.g. if the instance running them was stopped. Jobs without attempts left are failed
+    pub async fn recover_timed_out(&self) -> Result<(), DbErr> {
+        let now = now();
+        let heartbeat_before = now - self.job_timeout;
+        let error = Some("verification timed out".to_string());
+        entity::Entity::update_many()
+            .col_expr(entity::Column::Status, Expr::value(JobStatus::Pending))
+            .col_expr(entity::Column::Error, Expr::value(error.clone()))
+            .col_expr(entity::Column::UpdatedAt, Expr::value(now))
+            .col_expr(entity::Column::RunAfter, Expr::value(now))
+            .filter(entity::Column::Status.eq(JobStatus::Running))
+            .filter(entity::Column::UpdatedAt.lt(heartbeat_before))
+            .filter(entity::Column::Attempts.lt(self.max_attempts))
+            .exec(&self.db)
+            .await?;
+        entity::Entity::update_many()
+            .col_expr(entity::Column::Status, Expr::value(JobStatus::Failed))
+            .col_expr(entity::Column::Error, Expr::value(error))
+            .col_expr(entity::Column::UpdatedAt, Expr::value(now))
+            .filter(entity::Column::Status.eq(JobStatus::Running))
+            .filter(entity::Column::UpdatedAt.lt(heartbeat_before))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes finished and failed jobs completed longer than the ttl ago
+    pub async fn remove_expired(&self) -> Result<u64, DbErr> {
+        let result = entity::Entity::delete_many()
+            .filter(entity::Column::Status.is_in([JobStatus::Finished, JobStatus::Failed]))
+            .filter(entity::Column::UpdatedAt.lt(now() - self.finished_job_ttl))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,165 @@
+use super::{Job, JobQueue, JobRequest};
+use crate::{
+    proto::{
+        solidity_verifier_server::SolidityVerifier, vyper_verifier_server::VyperVerifier,
+        VerifyResponse,
+    },
+    services::{SolidityVerifierService, VyperVerifierService},
+};
+use std::{sync::Arc, time::Duration};
+use tonic::{Code, Request, Status};
+
+struct Worker {
+    queue: Arc<JobQueue>,
+    solidity: Option<Arc<SolidityVerifierService>>,
+    vyper: Option<Arc<VyperVerifierService>>,
+    poll_interval: Duration,
+}
+
+/// Internal errors may be caused by temporary problems (e.g. failed download
+/// of the compiler), while other errors would be the same on every attempt
+fn is_retryable(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::Internal | Code::Unavailable | Code::Unknown
+    )
+}
+
+impl Worker {
+    async fn verify(&self, request: JobRequest) -> Result<VerifyResponse, Status> {
+        let response = match request {
+            JobRequest::SolidityMultiPart(request) => {
+                self.solidity()?
+                    .verify_multi_part(Request::new(request))
+                    .await?
+            }
+            JobRequest::SolidityStandardJson(request) => {
+                self.solidity()?
+                    .verify_standard_json(Request::new(request))
+                    .await?
+            }
+            JobRequest::VyperMultiPart(request) => {
+                self.vyper()?
+                    .verify_multi_part(Request::new(request))
+                    .await?
+            }
+            JobRequest::VyperStandardJson(request) => {
+                self.vyper()?
+                    .verify_standard_json(Request::new(request))
+                    .await?
+            }
+        };
+        Ok(response.into_inner())
+    }
+
+    fn solidity(&self) -> Result<&SolidityVerifierService, Status> {
+        self.solidity
+            .as_deref()
+            .ok_or_else(|| Status::failed_precondition("solidity verifier is disabled"))
+    }
+
+    fn vyper(&self) -> Result<&VyperVerifierService, Status> {
+        self.vyper
+            .as_deref()
+            .ok_or_else(|| Status::failed_precondition("vyper verifier is disabled"))
+    }
+
+    async fn process(&self, job: Job) -> anyhow::Result<()> {
+        let request: JobRequest = match serde_json::from_str(&job.request) {
+            Ok(request) => request,
+            Err(err) => {
+                let error = format!("invalid job request: {err}");
+                return Ok(self.queue.fail(&job, error, false).await?);
+            }
+        };
+        tracing::debug!(job_id = %job.id, attempt = job.attempts, "verification started");
+        let verification = self.verify(request);
+        tokio::pin!(verification);
+        let mut heartbeat = tokio::time::interval(self.queue.heartbeat_interval());
+        // the first tick completes immediately, the job has just been taken
+        heartbeat.tick().await;
+        let result = loop {
+            tokio::select! {
+                result = &mut verification => break result,
+                _ = heartbeat.tick() => match self.queue.heartbeat(&job).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::warn!(
+                            job_id = %job.id,
+                            attempt = job.attempts,
+                            "verification timed out and was taken over"
+                        );
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        tracing::error!(job_id = %job.id, "failed to renew heartbeat: {err:#}")
+                    }
+                },
+            }
+        };
+        match result {
+            Ok(response) => self.queue.finish(&job, &response).await,
+            Err(status) => {
+                tracing::warn!(
+                    job_id = %job.id,
+                    attempt = job.attempts,
+                    "verification failed: {status}"
+                );
+                let retry = is_retryable(&status);
+                Ok(self
+                    .queue
+                    .fail(&job, status.message().to_string(), retry)
+                    .await?)
+            }
+        }
+    }
+
+    async fn run(self) {
+        loop {
+            match self.queue.take().await {
+                Ok(Some(job)) => {
+                    let id = job.id.clone();
+                    if let Err(err) = self.process(job).await {
+                        tracing::error!(job_id = %id, "failed to save job result: {err:#}");
+                    }
+                }
+                Ok(None) => {
+                    if let Err(err) = self.queue.recover_timed_out().await {
+                        tracing::error!("failed to recover timed out jobs: {err:#}");
+                    }
+                    if let Err(err) = self.queue.remove_expired().await {
+                        tracing::error!("failed to remove expired jobs: {err:#}");
+                    }
+                    tokio::select! {
+                        _ = self.queue.wait_submitted() => {}
+                        _ = tokio::time::sleep(self.poll_interval) => {}
+                    }
+                }
+                Err(err) => {
+                    tracing::error!("failed to take a job: {err:#}");
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+}
+
+/// Spawns workers taking jobs from the queue.
+/// Compilations of all workers are limited by the common compilers threads semaphore
+pub fn spawn_workers(
+    queue: Arc<JobQueue>,
+    workers: usize,
+    poll_interval: Duration,
+    solidity: Option<Arc<SolidityVerifierService>>,
+    vyper: Option<Arc<VyperVerifierService>>,
+) {
+    for _ in 0..workers {
+        let worker = Worker {
+            queue: queue.clone(),
+            solidity: solidity.clone(),
+            vyper: vyper.clone(),
+            poll_interval,
+        };
+        tokio::spawn(worker.run());
+    }
+}
@@ -1,3 +1,5 @@
+mod etherscan;
+mod jobs;
 mod metrics;
 mod proto;
 mod run;
@@ -7,6 +9,7 @@ mod types;
 
 pub use run::run;
 pub use services::{
-    HealthService, SolidityVerifierService, SourcifyVerifierService, VyperVerifierService,
+    HealthService, SolidityVerifierService, SourcifyVerifierService, VerificationJobsService,
+    VyperVerifierService,
 };
 pub use settings::Settings;
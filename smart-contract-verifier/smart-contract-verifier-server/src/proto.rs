@@ -1,9 +1,12 @@
 pub use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
     health_actix, health_check_response, health_server, solidity_verifier_actix,
     solidity_verifier_server, source, sourcify_verifier_actix, sourcify_verifier_server,
-    verify_response, vyper_verifier_actix, vyper_verifier_server, BytecodeType, HealthCheckRequest,
-    HealthCheckResponse, ListCompilerVersionsRequest, ListCompilerVersionsResponse, Source,
-    VerificationMetadata, VerifyResponse, VerifySolidityMultiPartRequest,
+    verification_job, verification_jobs_actix, verification_jobs_server, verify_response,
+    vyper_verifier_actix, vyper_verifier_server, BytecodeType, GetVerificationJobRequest,
+    HealthCheckRequest, HealthCheckResponse, ListCompilerVersionsRequest,
+    ListCompilerVersionsResponse, Source, SubmitSolidityMultiPartRequest,
+    SubmitSolidityStandardJsonRequest, SubmitVyperMultiPartRequest, SubmitVyperStandardJsonRequest,
+    VerificationJob, VerificationMetadata, VerifyResponse, VerifySolidityMultiPartRequest,
     VerifySolidityStandardJsonRequest, VerifySourcifyRequest, VerifyVyperMultiPartRequest,
     VerifyVyperStandardJsonRequest,
 };
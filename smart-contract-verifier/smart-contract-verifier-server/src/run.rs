@@ -1,18 +1,23 @@
 use crate::{
+    etherscan::{route_etherscan, EtherscanApi},
     proto::{
         health_actix::route_health, health_server::HealthServer,
         solidity_verifier_actix::route_solidity_verifier,
         solidity_verifier_server::SolidityVerifierServer,
         sourcify_verifier_actix::route_sourcify_verifier,
         sourcify_verifier_server::SourcifyVerifierServer,
+        verification_jobs_actix::route_verification_jobs,
+        verification_jobs_server::VerificationJobsServer,
         vyper_verifier_actix::route_vyper_verifier, vyper_verifier_server::VyperVerifierServer,
     },
     services::{
-        HealthService, SolidityVerifierService, SourcifyVerifierService, VyperVerifierService,
+        HealthService, SolidityVerifierService, SourcifyVerifierService, VerificationJobsService,
+        VyperVerifierService,
     },
     settings::Settings,
 };
 use blockscout_service_launcher::LaunchSettings;
+use smart_contract_verifier::CompilationCache;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 
@@ -21,6 +26,8 @@ struct HttpRouter {
     solidity_verifier: Option<Arc<SolidityVerifierService>>,
     vyper_verifier: Option<Arc<VyperVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
+    verification_jobs: Option<Arc<VerificationJobsService>>,
+    etherscan: Option<Arc<EtherscanApi>>,
     health: Arc<HealthService>,
 }
 
@@ -44,6 +51,16 @@ impl blockscout_service_launcher::HttpRouter for HttpRouter {
         } else {
             service_config
         };
+        let service_config = if let Some(jobs) = &self.verification_jobs {
+            service_config.configure(|config| route_verification_jobs(config, jobs.clone()))
+        } else {
+            service_config
+        };
+        let service_config = if let Some(etherscan) = &self.etherscan {
+            service_config.configure(|config| route_etherscan(config, etherscan.clone()))
+        } else {
+            service_config
+        };
 
         let _ = service_config;
     }
@@ -53,6 +70,7 @@ fn grpc_router(
     solidity_verifier: Option<Arc<SolidityVerifierService>>,
     vyper_verifier: Option<Arc<VyperVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
+    verification_jobs: Option<Arc<VerificationJobsService>>,
     health: Arc<HealthService>,
 ) -> tonic::transport::server::Router {
     tonic::transport::Server::builder()
@@ -60,16 +78,26 @@ fn grpc_router(
         .add_optional_service(solidity_verifier.map(SolidityVerifierServer::from_arc))
         .add_optional_service(vyper_verifier.map(VyperVerifierServer::from_arc))
         .add_optional_service(sourcify_verifier.map(SourcifyVerifierServer::from_arc))
+        .add_optional_service(verification_jobs.map(VerificationJobsServer::from_arc))
 }
 
 pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
     let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+    let cache_settings = settings.compilers.compilation_cache;
+    let compilation_cache = match cache_settings.enabled {
+        true => Some(Arc::new(CompilationCache::new(
+            cache_settings.dir,
+            cache_settings.max_size,
+        )?)),
+        false => None,
+    };
 
     let solidity_verifier = match settings.solidity.enabled {
         true => Some(Arc::new(
             SolidityVerifierService::new(
                 settings.solidity,
                 compilers_lock.clone(),
+                compilation_cache.clone(),
                 settings.extensions.solidity,
             )
             .await?,
@@ -81,6 +109,7 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
             VyperVerifierService::new(
                 settings.vyper,
                 compilers_lock.clone(),
+                compilation_cache.clone(),
                 settings.extensions.vyper,
             )
             .await?,
@@ -93,17 +122,38 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
         )),
         false => None,
     };
+    let verification_jobs = match settings.jobs.enabled {
+        true => Some(Arc::new(
+            VerificationJobsService::new(
+                settings.jobs,
+                solidity_verifier.clone(),
+                vyper_verifier.clone(),
+            )
+            .await?,
+        )),
+        false => None,
+    };
+    let etherscan = match (settings.etherscan.enabled, &solidity_verifier) {
+        (true, Some(solidity)) => Some(Arc::new(EtherscanApi::new(
+            settings.etherscan,
+            solidity.clone(),
+        ))),
+        _ => None,
+    };
     let health = Arc::new(HealthService::default());
     let grpc_router = grpc_router(
         solidity_verifier.clone(),
         vyper_verifier.clone(),
         sourcify_verifier.clone(),
+        verification_jobs.clone(),
         health.clone(),
     );
     let http_router = HttpRouter {
         solidity_verifier,
         vyper_verifier,
         sourcify_verifier,
+        verification_jobs,
+        etherscan,
         health,
     };
     let launch_settings = LaunchSettings {
@@ -1,9 +1,11 @@
 mod health;
 mod solidity_verifier;
 mod sourcify_verifier;
+mod verification_jobs;
 mod vyper_verifier;
 
 pub use health::HealthService;
 pub use solidity_verifier::SolidityVerifierService;
 pub use sourcify_verifier::SourcifyVerifierService;
+pub use verification_jobs::VerificationJobsService;
 pub use vyper_verifier::VyperVerifierService;
@@ -13,7 +13,8 @@ use crate::{
 };
 use s3::{creds::Credentials, Bucket, Region};
 use smart_contract_verifier::{
-    solidity, Compilers, Fetcher, ListFetcher, S3Fetcher, SolcValidator, SolidityClient,
+    solidity::{self, SettingsSearch},
+    CompilationCache, Compilers, Fetcher, ListFetcher, S3Fetcher, SolcValidator, SolidityClient,
     SolidityCompiler, VerificationError,
 };
 use std::{str::FromStr, sync::Arc};
@@ -22,16 +23,19 @@ use tonic::{Request, Response, Status};
 
 pub struct SolidityVerifierService {
     client: Arc<SolidityClient>,
+    settings_search: SettingsSearch,
 }
 
 impl SolidityVerifierService {
     pub async fn new(
         settings: SoliditySettings,
         compilers_threads_semaphore: Arc<Semaphore>,
+        compilation_cache: Option<Arc<CompilationCache>>,
         /* Otherwise, results in compilation warning if all extensions are disabled */
         #[allow(unused_variables)] extensions: Extensions,
     ) -> anyhow::Result<Self> {
         let dir = settings.compilers_dir.clone();
+        let settings_search = SettingsSearch::new(settings.settings_search_budget.get());
         let schedule = settings.refresh_versions_schedule;
         let validator = Arc::new(SolcValidator::default());
         let fetcher: Arc<dyn Fetcher> = match settings.fetcher {
@@ -54,11 +58,14 @@ impl SolidityVerifierService {
                 .await?,
             ),
         };
-        let compilers = Compilers::new(
+        let mut compilers = Compilers::new(
             fetcher,
             SolidityCompiler::new(),
             compilers_threads_semaphore,
         );
+        if let Some(compilation_cache) = compilation_cache {
+            compilers = compilers.with_compilation_cache(compilation_cache);
+        }
         compilers.load_from_dir(&dir).await;
 
         /* Otherwise, results in compilation warning if all extensions are disabled */
@@ -74,6 +81,7 @@ impl SolidityVerifierService {
 
         Ok(Self {
             client: Arc::new(client),
+            settings_search,
         })
     }
 }
@@ -86,7 +94,13 @@ impl SolidityVerifier for SolidityVerifierService {
     ) -> Result<Response<VerifyResponse>, Status> {
         let request: VerifySolidityMultiPartRequestWrapper = request.into_inner().into();
         let chain_id = request.metadata.clone().unwrap_or_default().chain_id;
-        let result = solidity::multi_part::verify(self.client.clone(), request.try_into()?).await;
+        let search_compiler_settings = request.search_compiler_settings.unwrap_or_default();
+        let mut verification_request: solidity::multi_part::VerificationRequest =
+            request.try_into()?;
+        if search_compiler_settings {
+            verification_request.settings_search = Some(self.settings_search);
+        }
+        let result = solidity::multi_part::verify(self.client.clone(), verification_request).await;
 
         let response = if let Ok(verification_success) = result {
             VerifyResponseWrapper::ok(verification_success)
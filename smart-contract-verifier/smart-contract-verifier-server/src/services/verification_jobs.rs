@@ -0,0 +1,203 @@
+use crate::{
+    jobs::{spawn_workers, Job, JobQueue, JobRequest, JobStatus},
+    proto::{
+        verification_job, verification_jobs_server::VerificationJobs, GetVerificationJobRequest,
+        SubmitSolidityMultiPartRequest, SubmitSolidityStandardJsonRequest,
+        SubmitVyperMultiPartRequest, SubmitVyperStandardJsonRequest, VerificationJob,
+    },
+    services::{SolidityVerifierService, VyperVerifierService},
+    settings::JobsSettings,
+    types::{
+        StandardJsonParseError, VerifySolidityMultiPartRequestWrapper,
+        VerifySolidityStandardJsonRequestWrapper, VerifyVyperMultiPartRequestWrapper,
+        VerifyVyperStandardJsonRequestWrapper,
+    },
+};
+use smart_contract_verifier::{solidity, vyper};
+use std::{sync::Arc, time::Duration};
+use tonic::{Request, Response, Status};
+
+pub struct VerificationJobsService {
+    queue: Arc<JobQueue>,
+    solidity_enabled: bool,
+    vyper_enabled: bool,
+}
+
+impl VerificationJobsService {
+    /// Connects to the queue database and spawns workers verifying submitted jobs
+    pub async fn new(
+        settings: JobsSettings,
+        solidity_verifier: Option<Arc<SolidityVerifierService>>,
+        vyper_verifier: Option<Arc<VyperVerifierService>>,
+    ) -> anyhow::Result<Self> {
+        let queue = Arc::new(JobQueue::connect(&settings).await?);
+        let service = Self {
+            queue: queue.clone(),
+            solidity_enabled: solidity_verifier.is_some(),
+            vyper_enabled: vyper_verifier.is_some(),
+        };
+        spawn_workers(
+            queue,
+            settings.workers.get(),
+            Duration::from_secs(settings.poll_interval),
+            solidity_verifier,
+            vyper_verifier,
+        );
+        Ok(service)
+    }
+
+    async fn submit(
+        &self,
+        request: JobRequest,
+        priority: Option<i32>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        let job = self
+            .queue
+            .submit(&request, priority.unwrap_or_default())
+            .await
+            .map_err(|err| {
+                tracing::error!("failed to submit verification job: {err:#}");
+                Status::internal("failed to submit verification job")
+            })?;
+        Ok(Response::new(job_to_proto(job)?))
+    }
+
+    fn check_enabled(enabled: bool, language: &str) -> Result<(), Status> {
+        if enabled {
+            Ok(())
+        } else {
+            Err(Status::failed_precondition(format!(
+                "{language} verifier is disabled"
+            )))
+        }
+    }
+}
+
+fn missing_request() -> Status {
+    Status::invalid_argument("request is missing")
+}
+
+/// Invalid contents of standard json inputs are reported as failed verification
+/// by the verifiers, so only bad requests are rejected on submission
+fn check_standard_json(result: Result<(), StandardJsonParseError>) -> Result<(), Status> {
+    match result {
+        Err(err @ StandardJsonParseError::BadRequest(_)) => {
+            Err(Status::invalid_argument(err.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn job_to_proto(job: Job) -> Result<VerificationJob, Status> {
+    let status = match job.status {
+        JobStatus::Pending => verification_job::Status::Pending,
+        JobStatus::Running => verification_job::Status::Running,
+        JobStatus::Finished => verification_job::Status::Finished,
+        JobStatus::Failed => verification_job::Status::Failed,
+    };
+    let response = job
+        .response
+        .map(|response| serde_json::from_str(&response))
+        .transpose()
+        .map_err(|err| {
+            tracing::error!(job_id = %job.id, "invalid job response: {err}");
+            Status::internal("invalid job response")
+        })?;
+    Ok(VerificationJob {
+        id: job.id,
+        status: status.into(),
+        priority: job.priority,
+        attempts: job.attempts,
+        error: job.error,
+        response,
+    })
+}
+
+#[async_trait::async_trait]
+impl VerificationJobs for VerificationJobsService {
+    async fn submit_solidity_multi_part(
+        &self,
+        request: Request<SubmitSolidityMultiPartRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        Self::check_enabled(self.solidity_enabled, "solidity")?;
+        let request = request.into_inner();
+        let verify_request = request.request.ok_or_else(missing_request)?;
+        let _: solidity::multi_part::VerificationRequest =
+            VerifySolidityMultiPartRequestWrapper::from(verify_request.clone()).try_into()?;
+        self.submit(
+            JobRequest::SolidityMultiPart(verify_request),
+            request.priority,
+        )
+        .await
+    }
+
+    async fn submit_solidity_standard_json(
+        &self,
+        request: Request<SubmitSolidityStandardJsonRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        Self::check_enabled(self.solidity_enabled, "solidity")?;
+        let request = request.into_inner();
+        let verify_request = request.request.ok_or_else(missing_request)?;
+        check_standard_json(
+            solidity::standard_json::VerificationRequest::try_from(
+                VerifySolidityStandardJsonRequestWrapper::from(verify_request.clone()),
+            )
+            .map(|_| ()),
+        )?;
+        self.submit(
+            JobRequest::SolidityStandardJson(verify_request),
+            request.priority,
+        )
+        .await
+    }
+
+    async fn submit_vyper_multi_part(
+        &self,
+        request: Request<SubmitVyperMultiPartRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        Self::check_enabled(self.vyper_enabled, "vyper")?;
+        let request = request.into_inner();
+        let verify_request = request.request.ok_or_else(missing_request)?;
+        let _: vyper::multi_part::VerificationRequest =
+            VerifyVyperMultiPartRequestWrapper::from(verify_request.clone()).try_into()?;
+        self.submit(JobRequest::VyperMultiPart(verify_request), request.priority)
+            .await
+    }
+
+    async fn submit_vyper_standard_json(
+        &self,
+        request: Request<SubmitVyperStandardJsonRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        Self::check_enabled(self.vyper_enabled, "vyper")?;
+        let request = request.into_inner();
+        let verify_request = request.request.ok_or_else(missing_request)?;
+        check_standard_json(
+            vyper::standard_json::VerificationRequest::try_from(
+                VerifyVyperStandardJsonRequestWrapper::from(verify_request.clone()),
+            )
+            .map(|_| ()),
+        )?;
+        self.submit(
+            JobRequest::VyperStandardJson(verify_request),
+            request.priority,
+        )
+        .await
+    }
+
+    async fn get_verification_job(
+        &self,
+        request: Request<GetVerificationJobRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        let id = request.into_inner().id;
+        let job = self
+            .queue
+            .get(&id)
+            .await
+            .map_err(|err| {
+                tracing::error!(job_id = %id, "failed to get verification job: {err:#}");
+                Status::internal("failed to get verification job")
+            })?
+            .ok_or_else(|| Status::not_found(format!("verification job {id} not found")))?;
+        Ok(Response::new(job_to_proto(job)?))
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     },
 };
 use smart_contract_verifier::{
-    vyper, Compilers, ListFetcher, VerificationError, VyperClient, VyperCompiler,
+    vyper, CompilationCache, Compilers, ListFetcher, VerificationError, VyperClient, VyperCompiler,
 };
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -26,6 +26,7 @@ impl VyperVerifierService {
     pub async fn new(
         settings: VyperSettings,
         compilers_threads_semaphore: Arc<Semaphore>,
+        compilation_cache: Option<Arc<CompilationCache>>,
         /* Otherwise, results in compilation warning if all extensions are disabled */
         #[allow(unused_variables)] extensions: Extensions,
     ) -> anyhow::Result<Self> {
@@ -45,7 +46,11 @@ impl VyperVerifierService {
             )
             .await?,
         );
-        let compilers = Compilers::new(fetcher, VyperCompiler::new(), compilers_threads_semaphore);
+        let mut compilers =
+            Compilers::new(fetcher, VyperCompiler::new(), compilers_threads_semaphore);
+        if let Some(compilation_cache) = compilation_cache {
+            compilers = compilers.with_compilation_cache(compilation_cache);
+        }
         compilers.load_from_dir(&dir).await;
 
         /* Otherwise, results in compilation warning if all extensions are disabled */
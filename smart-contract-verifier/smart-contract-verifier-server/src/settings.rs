@@ -10,6 +10,7 @@ use smart_contract_verifier::{
     DEFAULT_SOLIDITY_COMPILER_LIST, DEFAULT_SOURCIFY_HOST, DEFAULT_VYPER_COMPILER_LIST,
 };
 use std::{
+    collections::BTreeMap,
     num::{NonZeroU32, NonZeroUsize},
     path::PathBuf,
     str::FromStr,
@@ -42,6 +43,8 @@ pub struct Settings {
     pub tracing: TracingSettings,
     pub compilers: CompilersSettings,
     pub extensions: ExtensionsSettings,
+    pub etherscan: EtherscanSettings,
+    pub jobs: JobsSettings,
 
     // Is required as we deny unknown fields, but allow users provide
     // path to config through PREFIX__CONFIG env variable. If removed,
@@ -59,6 +62,9 @@ pub struct SoliditySettings {
     #[serde_as(as = "DisplayFromStr")]
     pub refresh_versions_schedule: Schedule,
     pub fetcher: FetcherSettings,
+    /// Maximum number of compiler settings combinations checked
+    /// for all compiler versions of a request which enables settings search
+    pub settings_search_budget: NonZeroUsize,
 }
 
 impl Default for SoliditySettings {
@@ -70,6 +76,7 @@ impl Default for SoliditySettings {
             compilers_dir: default_dir,
             refresh_versions_schedule: Schedule::from_str("0 0 * * * * *").unwrap(), // every hour
             fetcher: Default::default(),
+            settings_search_budget: NonZeroUsize::new(50).expect("Is not zero"),
         }
     }
 }
@@ -164,6 +171,7 @@ impl Default for SourcifySettings {
 #[serde(default, deny_unknown_fields)]
 pub struct CompilersSettings {
     pub max_threads: NonZeroUsize,
+    pub compilation_cache: CompilationCacheSettings,
 }
 
 impl Default for CompilersSettings {
@@ -172,7 +180,33 @@ impl Default for CompilersSettings {
             tracing::warn!("cannot get number of CPU cores: {}", e);
             NonZeroUsize::new(8).unwrap()
         });
-        Self { max_threads }
+        Self {
+            max_threads,
+            compilation_cache: Default::default(),
+        }
+    }
+}
+
+/// On-disk cache of compilation outputs shared by solidity and vyper verifiers
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompilationCacheSettings {
+    pub enabled: bool,
+    pub dir: PathBuf,
+    /// Maximum total size of cached outputs in bytes.
+    /// Least recently used outputs are removed when the size is exceeded
+    pub max_size: u64,
+}
+
+impl Default for CompilationCacheSettings {
+    fn default() -> Self {
+        let mut default_dir = std::env::temp_dir();
+        default_dir.push("compilation-cache");
+        Self {
+            enabled: false,
+            dir: default_dir,
+            max_size: 1024 * 1024 * 1024,
+        }
     }
 }
 
@@ -191,6 +225,70 @@ pub struct Extensions {
     pub sig_provider: Option<sig_provider_extension::Config>,
 }
 
+/// Etherscan-compatible api. Uses solidity verifier,
+/// so requires solidity verification to be enabled
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EtherscanSettings {
+    pub enabled: bool,
+    /// JSON-RPC urls of the nodes by chain ids. Used to get deployed bytecodes of contracts
+    pub rpc_urls: BTreeMap<String, Url>,
+    /// Chain used by requests without `chainid` parameter.
+    /// Is not required if only one chain is configured
+    pub default_chain_id: Option<String>,
+    /// Number of verifications running in background,
+    /// new requests are rejected until one of them is finished
+    pub max_pending_verifications: NonZeroUsize,
+}
+
+impl Default for EtherscanSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rpc_urls: Default::default(),
+            default_chain_id: None,
+            max_pending_verifications: NonZeroUsize::new(100).expect("Is not zero"),
+        }
+    }
+}
+
+/// Asynchronous verification jobs
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsSettings {
+    pub enabled: bool,
+    /// Either sqlite or postgres url of the jobs queue database. Is required if jobs are enabled
+    pub database_url: Option<String>,
+    /// Number of jobs verified concurrently
+    pub workers: NonZeroUsize,
+    /// Number of attempts to verify a job failed with an internal error
+    pub max_attempts: NonZeroU32,
+    /// Delay in seconds before the second attempt, doubled for every next one
+    pub retry_delay: u64,
+    /// Period in seconds after which running jobs without heartbeats are considered lost
+    /// and are retried. Heartbeats of running jobs are renewed 3 times per the period
+    pub job_timeout: u64,
+    /// Period in seconds idle workers check the queue for new jobs
+    pub poll_interval: u64,
+    /// Period in seconds finished and failed jobs are kept for
+    pub finished_job_ttl: u64,
+}
+
+impl Default for JobsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            database_url: None,
+            workers: NonZeroUsize::new(4).expect("Is not zero"),
+            max_attempts: NonZeroU32::new(3).expect("Is not zero"),
+            retry_delay: 10,
+            job_timeout: 300,
+            poll_interval: 5,
+            finished_job_ttl: 7 * 24 * 3600,
+        }
+    }
+}
+
 impl Settings {
     pub fn new() -> anyhow::Result<Self> {
         let config_path = std::env::var("SMART_CONTRACT_VERIFIER__CONFIG");
@@ -219,6 +317,37 @@ impl Settings {
             }
         };
 
+        // Validate verification jobs
+        if self.jobs.enabled {
+            if !self.solidity.enabled && !self.vyper.enabled {
+                return Err(anyhow!(
+                    "verification jobs require solidity or vyper verifier to be enabled"
+                ));
+            }
+            if self.jobs.database_url.is_none() {
+                return Err(anyhow!("verification jobs require `database_url`"));
+            }
+        }
+
+        // Validate etherscan api
+        if self.etherscan.enabled {
+            if !self.solidity.enabled {
+                return Err(anyhow!(
+                    "etherscan api requires solidity verifier to be enabled"
+                ));
+            }
+            if self.etherscan.rpc_urls.is_empty() {
+                return Err(anyhow!("etherscan api requires at least one of `rpc_urls`"));
+            }
+            if let Some(chain_id) = &self.etherscan.default_chain_id {
+                if !self.etherscan.rpc_urls.contains_key(chain_id) {
+                    return Err(anyhow!(
+                        "etherscan `default_chain_id` {chain_id} is not in `rpc_urls`"
+                    ));
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -52,9 +52,14 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
             BytecodeType::DeployedBytecode => (None, bytecode),
         };
 
-        let compiler_version = Version::from_str(&request.compiler_version).map_err(|err| {
-            tonic::Status::invalid_argument(format!("Invalid compiler version: {err}"))
-        })?;
+        let compiler_version = request
+            .compiler_version
+            .filter(|version| !version.is_empty())
+            .map(|version| Version::from_str(&version))
+            .transpose()
+            .map_err(|err| {
+                tonic::Status::invalid_argument(format!("Invalid compiler version: {err}"))
+            })?;
 
         let sources: BTreeMap<PathBuf, String> = request
             .source_files
@@ -84,6 +89,7 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
                 optimization_runs: request.optimization_runs.map(|i| i as usize),
                 contract_libraries: Some(request.libraries.into_iter().collect()),
             },
+            settings_search: None,
             chain_id: request.metadata.map(|metadata| metadata.chain_id),
         })
     }
@@ -102,7 +108,7 @@ mod tests {
         let mut request = VerifySolidityMultiPartRequest {
             bytecode: "0x1234".to_string(),
             bytecode_type: BytecodeType::CreationInput.into(),
-            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            compiler_version: Some("v0.8.17+commit.8df45f5f".to_string()),
             source_files: BTreeMap::from([("source_path".into(), "source_content".into())]),
             evm_version: Some("london".to_string()),
             optimization_runs: Some(200),
@@ -111,18 +117,20 @@ mod tests {
                 chain_id: "1".into(),
                 contract_address: "0xcafecafecafecafecafecafecafecafecafecafe".into(),
             }),
+            search_compiler_settings: None,
         };
 
         let mut expected = VerificationRequest {
             creation_bytecode: Some(DisplayBytes::from_str("0x1234").unwrap().0),
             deployed_bytecode: DisplayBytes::from_str("").unwrap().0,
-            compiler_version: Version::from_str("v0.8.17+commit.8df45f5f").unwrap(),
+            compiler_version: Some(Version::from_str("v0.8.17+commit.8df45f5f").unwrap()),
             content: MultiFileContent {
                 sources: BTreeMap::from([("source_path".into(), "source_content".into())]),
                 evm_version: Some(EvmVersion::London),
                 optimization_runs: Some(200),
                 contract_libraries: Some(BTreeMap::from([("Lib".into(), "0xcafe".into())])),
             },
+            settings_search: None,
             chain_id: Some("1".into()),
         };
 
@@ -150,12 +158,13 @@ mod tests {
         let request = VerifySolidityMultiPartRequest {
             bytecode: "".to_string(),
             bytecode_type: BytecodeType::CreationInput.into(),
-            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            compiler_version: Some("v0.8.17+commit.8df45f5f".to_string()),
             source_files: Default::default(),
             evm_version: Some("default".to_string()),
             optimization_runs: None,
             libraries: Default::default(),
             metadata: None,
+            search_compiler_settings: None,
         };
 
         let verification_request: VerificationRequest =
@@ -175,12 +184,13 @@ mod tests {
         let request = VerifySolidityMultiPartRequest {
             bytecode: "".to_string(),
             bytecode_type: BytecodeType::CreationInput.into(),
-            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            compiler_version: Some("v0.8.17+commit.8df45f5f".to_string()),
             source_files: Default::default(),
             evm_version: None,
             optimization_runs: None,
             libraries: Default::default(),
             metadata: None,
+            search_compiler_settings: None,
         };
 
         let verification_request: VerificationRequest =
@@ -199,12 +209,13 @@ mod tests {
         let request = VerifySolidityMultiPartRequest {
             bytecode: "".to_string(),
             bytecode_type: BytecodeType::CreationInput.into(),
-            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            compiler_version: Some("v0.8.17+commit.8df45f5f".to_string()),
             source_files: Default::default(),
             evm_version: None,
             optimization_runs: None,
             libraries: Default::default(),
             metadata: None,
+            search_compiler_settings: None,
         };
 
         let verification_request: VerificationRequest =
@@ -37,6 +37,7 @@ async fn global_service() -> &'static Arc<SolidityVerifierService> {
             let service = SolidityVerifierService::new(
                 settings.solidity,
                 Arc::new(compilers_lock),
+                None,
                 settings.extensions.solidity,
             )
             .await
@@ -95,14 +96,20 @@ async fn test_setup(
             "DEPLOYED_BYTECODE",
         )
     };
+    let (evm_version, optimization_runs) = if input.search_compiler_settings {
+        ("default", None)
+    } else {
+        (input.evm_version, input.optimization_runs)
+    };
     let request = json!({
         "bytecode": bytecode,
         "bytecodeType": bytecode_type,
-        "compilerVersion": input.compiler_version,
+        "compilerVersion": (!input.detect_compiler_version).then_some(input.compiler_version),
         "sourceFiles": BTreeMap::from([(contract_path, input.source_code.as_ref().unwrap())]),
-        "evmVersion": input.evm_version,
+        "evmVersion": evm_version,
         "libraries": input.contract_libraries,
-        "optimizationRuns": input.optimization_runs
+        "optimizationRuns": optimization_runs,
+        "searchCompilerSettings": input.search_compiler_settings
     });
 
     let response = TestRequest::post()
@@ -424,6 +431,40 @@ mod success_tests {
     }
 }
 
+mod compiler_version_detection_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn detects_compiler_version_from_metadata() {
+        let contract_dir = "solidity_0.5.14";
+        let test_input = TestInput::new("A", "v0.5.14+commit.01f1aaa4").detect_compiler_version();
+        test_success(contract_dir, test_input).await;
+    }
+
+    #[tokio::test]
+    async fn detects_compiler_version_from_pragma() {
+        // Contracts compiled with solc < 0.5.9 contain no compiler version in the metadata
+        let contract_dir = "simple_storage";
+        let test_input =
+            TestInput::new("SimpleStorage", "v0.4.24+commit.e67f0147").detect_compiler_version();
+        test_success(contract_dir, test_input).await;
+    }
+}
+
+mod compiler_settings_search_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn finds_optimization_runs() {
+        let contract_dir = "with_immutable_assignment";
+        let test_input = TestInput::new("C", "v0.6.7+commit.b8d736ae")
+            .with_optimization_runs(200)
+            .has_constructor_args()
+            .search_compiler_settings();
+        test_success(contract_dir, test_input).await;
+    }
+}
+
 mod failure_tests {
     use super::*;
 
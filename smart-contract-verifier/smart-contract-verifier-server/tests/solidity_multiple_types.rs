@@ -11,6 +11,11 @@ pub struct TestInput {
     pub has_constructor_args: bool,
     pub is_yul: bool,
     pub ignore_creation_tx_input: bool,
+    /// If true, the compiler version is not sent and should be detected by the verifier
+    pub detect_compiler_version: bool,
+    /// If true, evm version and optimization runs are not sent
+    /// and should be found by the verifier
+    pub search_compiler_settings: bool,
     pub abi: Option<serde_json::Value>,
 
     /// If None, the input would be read from the corresponding file
@@ -32,6 +37,8 @@ impl TestInput {
             has_constructor_args: false,
             is_yul: false,
             ignore_creation_tx_input: false,
+            detect_compiler_version: false,
+            search_compiler_settings: false,
             abi: None,
 
             source_code: None,
@@ -70,6 +77,16 @@ impl TestInput {
         self
     }
 
+    pub fn detect_compiler_version(mut self) -> Self {
+        self.detect_compiler_version = true;
+        self
+    }
+
+    pub fn search_compiler_settings(mut self) -> Self {
+        self.search_compiler_settings = true;
+        self
+    }
+
     pub fn with_source_code(mut self, source_code: String) -> Self {
         self.source_code = Some(source_code);
         self
@@ -36,6 +36,7 @@ async fn global_service() -> &'static Arc<SolidityVerifierService> {
             let service = SolidityVerifierService::new(
                 settings.solidity,
                 Arc::new(compilers_lock),
+                None,
                 settings.extensions.solidity,
             )
             .await
@@ -0,0 +1,132 @@
+use actix_web::{
+    http::StatusCode,
+    test,
+    test::{read_body_json, TestRequest},
+    App,
+};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
+    verification_job, verification_jobs_actix::route_verification_jobs, verify_response,
+    VerificationJob,
+};
+use smart_contract_verifier_server::{Settings, SolidityVerifierService, VerificationJobsService};
+use std::{fs, sync::Arc, time::Duration};
+use tokio::sync::Semaphore;
+
+const CONTRACT_DIR: &str = "tests/contracts/simple_storage";
+
+async fn init_service(test_name: &str) -> Arc<VerificationJobsService> {
+    let mut settings = Settings::default();
+    let db_path = std::env::temp_dir().join(format!(
+        "verification-jobs-{}-{}.db",
+        test_name,
+        std::process::id()
+    ));
+    let _ = fs::remove_file(&db_path);
+    settings.jobs.database_url = Some(format!("sqlite://{}?mode=rwc", db_path.display()));
+    settings.jobs.poll_interval = 1;
+
+    let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+    let solidity = SolidityVerifierService::new(
+        settings.solidity,
+        compilers_lock,
+        None,
+        settings.extensions.solidity,
+    )
+    .await
+    .expect("couldn't initialize solidity service");
+    let service = VerificationJobsService::new(settings.jobs, Some(Arc::new(solidity)), None)
+        .await
+        .expect("couldn't initialize verification jobs service");
+    Arc::new(service)
+}
+
+fn multi_part_request(bytecode: &str) -> serde_json::Value {
+    let source_path = format!("{CONTRACT_DIR}/source.sol");
+    let source = fs::read_to_string(&source_path).expect("Error while reading source");
+    json!({
+        "request": {
+            "bytecode": bytecode,
+            "bytecodeType": "CREATION_INPUT",
+            "compilerVersion": "v0.4.24+commit.e67f0147",
+            "sourceFiles": { source_path: source },
+        },
+        "priority": 5,
+    })
+}
+
+#[tokio::test]
+async fn submit_and_poll() {
+    let service = init_service("submit_and_poll").await;
+    let app =
+        test::init_service(App::new().configure(|config| route_verification_jobs(config, service)))
+            .await;
+
+    let creation_tx_input = fs::read_to_string(format!("{CONTRACT_DIR}/creation_tx_input"))
+        .expect("Error while reading creation_tx_input");
+    let response = TestRequest::post()
+        .uri("/api/v2/verifier/solidity/sources:submit-multi-part")
+        .set_json(multi_part_request(&creation_tx_input))
+        .send_request(&app)
+        .await;
+    assert_eq!(StatusCode::OK, response.status());
+    let mut job: VerificationJob = read_body_json(response).await;
+    assert_eq!(verification_job::Status::Pending, job.status());
+    assert_eq!(5, job.priority);
+
+    for _ in 0..300 {
+        let response = TestRequest::get()
+            .uri(&format!("/api/v2/verifier/jobs/{}", job.id))
+            .send_request(&app)
+            .await;
+        assert_eq!(StatusCode::OK, response.status());
+        job = read_body_json(response).await;
+        if job.status() != verification_job::Status::Pending
+            && job.status() != verification_job::Status::Running
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    assert_eq!(
+        verification_job::Status::Finished,
+        job.status(),
+        "Invalid job status. Job: {job:?}"
+    );
+    assert_eq!(1, job.attempts);
+    let response = job.response.expect("finished job must have a response");
+    assert_eq!(verify_response::Status::Success, response.status());
+    assert_eq!("SimpleStorage", response.source.unwrap().contract_name);
+}
+
+#[tokio::test]
+async fn invalid_requests() {
+    let service = init_service("invalid_requests").await;
+    let app =
+        test::init_service(App::new().configure(|config| route_verification_jobs(config, service)))
+            .await;
+
+    let response = TestRequest::post()
+        .uri("/api/v2/verifier/solidity/sources:submit-multi-part")
+        .set_json(multi_part_request("not a bytecode"))
+        .send_request(&app)
+        .await;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let response = TestRequest::post()
+        .uri("/api/v2/verifier/vyper/sources:submit-multi-part")
+        .set_json(multi_part_request("0x6080"))
+        .send_request(&app)
+        .await;
+    assert!(
+        !response.status().is_success(),
+        "vyper jobs are submitted while vyper verifier is disabled"
+    );
+
+    let response = TestRequest::get()
+        .uri("/api/v2/verifier/jobs/unknown")
+        .send_request(&app)
+        .await;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+}
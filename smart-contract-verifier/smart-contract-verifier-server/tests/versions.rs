@@ -18,6 +18,7 @@ async fn test_versions(uri: &str) {
     let solidity_service = SolidityVerifierService::new(
         settings.solidity,
         compilers_lock.clone(),
+        None,
         settings.extensions.solidity,
     )
     .await
@@ -25,6 +26,7 @@ async fn test_versions(uri: &str) {
     let vyper_service = VyperVerifierService::new(
         settings.vyper,
         compilers_lock.clone(),
+        None,
         settings.extensions.vyper,
     )
     .await
@@ -31,6 +31,7 @@ async fn global_service() -> &'static Arc<VyperVerifierService> {
             let service = VyperVerifierService::new(
                 settings.vyper,
                 Arc::new(compilers_lock),
+                None,
                 settings.extensions.vyper,
             )
             .await
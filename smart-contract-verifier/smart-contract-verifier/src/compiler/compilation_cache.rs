@@ -0,0 +1,386 @@
+use super::version::Version;
+use crate::metrics;
+use ethers_solc::CompilerOutput;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, future::Future, path::PathBuf, sync::Arc};
+
+const OUTPUT_EXTENSION: &str = "json";
+const TEMPORARY_EXTENSION: &str = "tmp";
+
+/// On-disk cache of compilation outputs addressed by the hash of the compiler version
+/// and the canonical json representation of the compiler input.
+///
+/// Least recently used outputs are removed when the total size of the cache exceeds the limit.
+/// Concurrent compilations of the same input are coalesced, so that only one of them
+/// runs the compiler, while the others wait for its output.
+pub struct CompilationCache {
+    dir: PathBuf,
+    max_size: u64,
+    entries: parking_lot::Mutex<Entries>,
+    in_flight: parking_lot::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+#[derive(Default)]
+struct Entries {
+    files: HashMap<String, Entry>,
+    total_size: u64,
+    clock: u64,
+}
+
+struct Entry {
+    size: u64,
+    last_used: u64,
+}
+
+impl Entries {
+    fn touch(&mut self, key: &str) -> bool {
+        self.clock += 1;
+        match self.files.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = self.clock;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, key: String, size: u64) {
+        self.clock += 1;
+        let entry = Entry {
+            size,
+            last_used: self.clock,
+        };
+        if let Some(replaced) = self.files.insert(key, entry) {
+            self.total_size -= replaced.size;
+        }
+        self.total_size += size;
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(removed) = self.files.remove(key) {
+            self.total_size -= removed.size;
+        }
+    }
+
+    /// Removes least recently used entries until the total size fits the limit.
+    /// Returns keys of removed entries
+    fn evict(&mut self, max_size: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.total_size > max_size {
+            let key = match self.files.iter().min_by_key(|(_, entry)| entry.last_used) {
+                Some((key, _)) => key.clone(),
+                None => break,
+            };
+            self.remove(&key);
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+/// Removes the coalescing lock of the key when the last compilation waiting for it
+/// finishes (or is cancelled)
+struct InFlightGuard<'a> {
+    in_flight: &'a parking_lot::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    key: &'a str,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock();
+        // The map and the current guard are the only owners of the lock
+        if Arc::strong_count(&self.lock) == 2 {
+            in_flight.remove(self.key);
+        }
+    }
+}
+
+impl CompilationCache {
+    /// Creates the cache in the directory. Outputs saved there by previous runs are reused.
+    pub fn new(dir: PathBuf, max_size: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        let mut files = Vec::new();
+        for dir_entry in std::fs::read_dir(&dir)? {
+            let path = dir_entry?.path();
+            let extension = path.extension().and_then(|extension| extension.to_str());
+            let key = path.file_stem().and_then(|stem| stem.to_str());
+            match (extension, key) {
+                (Some(OUTPUT_EXTENSION), Some(key)) => {
+                    let metadata = std::fs::metadata(&path)?;
+                    files.push((metadata.modified()?, key.to_string(), metadata.len()));
+                }
+                // Left by interrupted writes
+                (Some(TEMPORARY_EXTENSION), _) => std::fs::remove_file(&path)?,
+                _ => {}
+            }
+        }
+        // Outputs modified earlier are considered to be used earlier
+        files.sort();
+
+        let mut entries = Entries::default();
+        for (_, key, size) in files {
+            entries.insert(key, size);
+        }
+        let cache = Self {
+            dir,
+            max_size,
+            entries: parking_lot::Mutex::new(entries),
+            in_flight: Default::default(),
+        };
+        for key in cache.evict() {
+            std::fs::remove_file(cache.path(&key))?;
+        }
+        Ok(cache)
+    }
+
+    /// Returns the cached output of the input compilation,
+    /// or calls `compile` and caches its successful result
+    pub async fn get_or_compile<I, F, Fut, E>(
+        &self,
+        compiler_version: &Version,
+        input: &I,
+        compile: F,
+    ) -> Result<CompilerOutput, E>
+    where
+        I: Serialize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<CompilerOutput, E>>,
+    {
+        let key = match key(compiler_version, input) {
+            Ok(key) => key,
+            Err(err) => {
+                tracing::warn!("cannot calculate compilation cache key: {err}");
+                return compile().await;
+            }
+        };
+
+        let guard = {
+            let mut in_flight = self.in_flight.lock();
+            InFlightGuard {
+                in_flight: &self.in_flight,
+                key: &key,
+                lock: in_flight.entry(key.clone()).or_default().clone(),
+            }
+        };
+        let _lock = guard.lock.lock().await;
+
+        if let Some(output) = self.read(&key).await {
+            metrics::COMPILATION_CACHE_HITS.inc();
+            return Ok(output);
+        }
+        metrics::COMPILATION_CACHE_MISSES.inc();
+
+        let output = compile().await?;
+        if let Err(err) = self.write(&key, &output).await {
+            tracing::warn!(key, "cannot save compilation output into the cache: {err}");
+        }
+        Ok(output)
+    }
+
+    async fn read(&self, key: &str) -> Option<CompilerOutput> {
+        if !self.entries.lock().touch(key) {
+            return None;
+        }
+        let output = tokio::fs::read(self.path(key))
+            .await
+            .and_then(|content| serde_json::from_slice(&content).map_err(std::io::Error::from));
+        match output {
+            Ok(output) => Some(output),
+            Err(err) => {
+                tracing::warn!(key, "cannot read cached compilation output: {err}");
+                self.entries.lock().remove(key);
+                let _ = tokio::fs::remove_file(self.path(key)).await;
+                None
+            }
+        }
+    }
+
+    async fn write(&self, key: &str, output: &CompilerOutput) -> std::io::Result<()> {
+        let content = serde_json::to_vec(output)?;
+        let path = self.path(key);
+        let temporary_path = path.with_extension(TEMPORARY_EXTENSION);
+        tokio::fs::write(&temporary_path, &content).await?;
+        tokio::fs::rename(&temporary_path, &path).await?;
+
+        self.entries
+            .lock()
+            .insert(key.to_string(), content.len() as u64);
+        for key in self.evict() {
+            if let Err(err) = tokio::fs::remove_file(self.path(&key)).await {
+                tracing::warn!(key, "cannot remove evicted compilation output: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    fn evict(&self) -> Vec<String> {
+        let mut entries = self.entries.lock();
+        let evicted = entries.evict(self.max_size);
+        metrics::COMPILATION_CACHE_SIZE.set(entries.total_size as i64);
+        evicted
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.{OUTPUT_EXTENSION}"))
+    }
+}
+
+fn key(compiler_version: &Version, input: &impl Serialize) -> Result<String, serde_json::Error> {
+    let input = sort_keys(serde_json::to_value(input)?);
+    let mut hasher = Sha256::new();
+    hasher.update(compiler_version.to_string());
+    hasher.update([0]);
+    hasher.update(serde_json::to_vec(&input)?);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Makes json representation canonical independently of the order fields were serialized in
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut fields: Vec<_> = map.into_iter().collect();
+            fields.sort_by(|(x, _), (y, _)| x.cmp(y));
+            Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::{
+        str::FromStr,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    fn version() -> Version {
+        Version::from_str("v0.8.17+commit.8df45f5f").unwrap()
+    }
+
+    async fn get_or_compile(
+        cache: &CompilationCache,
+        input: &Value,
+        compilations: &AtomicUsize,
+    ) -> CompilerOutput {
+        cache
+            .get_or_compile(&version(), input, || async {
+                compilations.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok::<_, ()>(CompilerOutput::default())
+            })
+            .await
+            .unwrap()
+    }
+
+    fn output_size() -> u64 {
+        serde_json::to_vec(&CompilerOutput::default())
+            .unwrap()
+            .len() as u64
+    }
+
+    #[test]
+    fn key_is_canonical() {
+        let input = json!({"language": "Solidity", "settings": {"optimizer": {"runs": 200, "enabled": true}}});
+        let reordered = serde_json::from_str::<Value>(
+            r#"{"settings": {"optimizer": {"enabled": true, "runs": 200}}, "language": "Solidity"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            key(&version(), &input).unwrap(),
+            key(&version(), &reordered).unwrap()
+        );
+
+        let another_version = Version::from_str("v0.8.16+commit.07a7930e").unwrap();
+        assert_ne!(
+            key(&version(), &input).unwrap(),
+            key(&another_version, &input).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn caches_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let compilations = AtomicUsize::new(0);
+        let input = json!({"language": "Solidity"});
+
+        let cache = CompilationCache::new(dir.path().to_path_buf(), 1024 * 1024).unwrap();
+        get_or_compile(&cache, &input, &compilations).await;
+        get_or_compile(&cache, &input, &compilations).await;
+        assert_eq!(1, compilations.load(Ordering::SeqCst));
+
+        // outputs saved by previous runs are reused
+        let cache = CompilationCache::new(dir.path().to_path_buf(), 1024 * 1024).unwrap();
+        get_or_compile(&cache, &input, &compilations).await;
+        assert_eq!(1, compilations.load(Ordering::SeqCst));
+
+        get_or_compile(&cache, &json!({"language": "Yul"}), &compilations).await;
+        assert_eq!(2, compilations.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn does_not_cache_failed_compilations() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = json!({"language": "Solidity"});
+
+        let cache = CompilationCache::new(dir.path().to_path_buf(), 1024 * 1024).unwrap();
+        let result = cache
+            .get_or_compile(&version(), &input, || async {
+                Err::<CompilerOutput, _>(())
+            })
+            .await;
+        assert!(result.is_err());
+
+        let compilations = AtomicUsize::new(0);
+        get_or_compile(&cache, &input, &compilations).await;
+        assert_eq!(1, compilations.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn coalesces_concurrent_compilations() {
+        let dir = tempfile::tempdir().unwrap();
+        let compilations = AtomicUsize::new(0);
+        let input = json!({"language": "Solidity"});
+
+        let cache = CompilationCache::new(dir.path().to_path_buf(), 1024 * 1024).unwrap();
+        futures::future::join_all((0..5).map(|_| get_or_compile(&cache, &input, &compilations)))
+            .await;
+        assert_eq!(1, compilations.load(Ordering::SeqCst));
+        assert!(cache.in_flight.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let compilations = AtomicUsize::new(0);
+        let inputs: Vec<_> = (0..3).map(|i| json!({ "input": i })).collect();
+
+        let cache = CompilationCache::new(dir.path().to_path_buf(), 2 * output_size()).unwrap();
+        get_or_compile(&cache, &inputs[0], &compilations).await;
+        get_or_compile(&cache, &inputs[1], &compilations).await;
+        // makes the second input the least recently used one
+        get_or_compile(&cache, &inputs[0], &compilations).await;
+        get_or_compile(&cache, &inputs[2], &compilations).await;
+        assert_eq!(3, compilations.load(Ordering::SeqCst));
+        assert_eq!(2, std::fs::read_dir(dir.path()).unwrap().count());
+
+        get_or_compile(&cache, &inputs[0], &compilations).await;
+        get_or_compile(&cache, &inputs[2], &compilations).await;
+        assert_eq!(3, compilations.load(Ordering::SeqCst));
+        get_or_compile(&cache, &inputs[1], &compilations).await;
+        assert_eq!(4, compilations.load(Ordering::SeqCst));
+    }
+}
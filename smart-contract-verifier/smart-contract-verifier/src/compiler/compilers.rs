@@ -1,4 +1,5 @@
 use super::{
+    compilation_cache::CompilationCache,
     download_cache::DownloadCache,
     fetcher::{FetchError, Fetcher},
     version::Version,
@@ -30,7 +31,7 @@ pub enum Error {
 
 #[async_trait::async_trait]
 pub trait EvmCompiler {
-    type CompilerInput;
+    type CompilerInput: serde::Serialize;
 
     async fn compile(
         &self,
@@ -45,6 +46,7 @@ pub struct Compilers<C> {
     fetcher: Arc<dyn Fetcher>,
     evm_compiler: C,
     threads_semaphore: Arc<Semaphore>,
+    compilation_cache: Option<Arc<CompilationCache>>,
 }
 
 impl<C> Compilers<C>
@@ -61,14 +63,45 @@ where
             fetcher,
             evm_compiler,
             threads_semaphore,
+            compilation_cache: None,
         }
     }
+
+    /// Attaches the cache of compilation outputs. Several compilers may share the same cache.
+    pub fn with_compilation_cache(mut self, compilation_cache: Arc<CompilationCache>) -> Self {
+        self.compilation_cache = Some(compilation_cache);
+        self
+    }
+
     #[instrument(name = "download_and_compile", skip(self, input), level = "debug")]
     pub async fn compile(
         &self,
         compiler_version: &Version,
         input: &C::CompilerInput,
         chain_id: Option<&str>,
+    ) -> Result<CompilerOutput, Error> {
+        match &self.compilation_cache {
+            Some(cache) => {
+                cache
+                    .get_or_compile(compiler_version, input, || {
+                        self.compile_uncached(compiler_version, input, chain_id)
+                    })
+                    .await
+            }
+            None => {
+                self.compile_uncached(compiler_version, input, chain_id)
+                    .await
+            }
+        }
+    }
+
+    /// Outputs with compilation errors are returned as [`Error::Compilation`],
+    /// so that they are not cached
+    async fn compile_uncached(
+        &self,
+        compiler_version: &Version,
+        input: &C::CompilerInput,
+        chain_id: Option<&str>,
     ) -> Result<CompilerOutput, Error> {
         let path_result = {
             self.cache
@@ -5,9 +5,11 @@ mod list_fetcher;
 mod s3_fetcher;
 mod versions_fetcher;
 
+mod compilation_cache;
 mod compilers;
 mod download_cache;
 
+pub use compilation_cache::CompilationCache;
 pub use compilers::{Compilers, Error, EvmCompiler};
 pub use fetcher::{Fetcher, FileValidator};
 pub use list_fetcher::ListFetcher;
@@ -23,7 +23,7 @@ pub use consts::{
 pub use middleware::Middleware;
 
 pub use common_types::MatchType;
-pub use compiler::{Compilers, Fetcher, ListFetcher, S3Fetcher, Version};
+pub use compiler::{CompilationCache, Compilers, Fetcher, ListFetcher, S3Fetcher, Version};
 pub use sourcify::Error as SourcifyError;
 pub use verifier::{BytecodePart, Error as VerificationError};
 
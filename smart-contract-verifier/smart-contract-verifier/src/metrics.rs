@@ -1,7 +1,7 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    register_gauge, register_histogram, register_histogram_vec, register_int_counter, Gauge,
-    Histogram, HistogramVec, IntCounter,
+    register_gauge, register_histogram, register_histogram_vec, register_int_counter,
+    register_int_gauge, Gauge, Histogram, HistogramVec, IntCounter, IntGauge,
 };
 
 lazy_static! {
@@ -43,6 +43,21 @@ lazy_static! {
         "number of compilations in queue",
     )
     .unwrap();
+    pub static ref COMPILATION_CACHE_HITS: IntCounter = register_int_counter!(
+        "smart_contract_verifier_compilation_cache_hits",
+        "number of compilation outputs found in CompilationCache",
+    )
+    .unwrap();
+    pub static ref COMPILATION_CACHE_MISSES: IntCounter = register_int_counter!(
+        "smart_contract_verifier_compilation_cache_misses",
+        "number of compilation outputs missing in CompilationCache",
+    )
+    .unwrap();
+    pub static ref COMPILATION_CACHE_SIZE: IntGauge = register_int_gauge!(
+        "smart_contract_verifier_compilation_cache_size_bytes",
+        "total size of compilation outputs stored in CompilationCache",
+    )
+    .unwrap();
 }
 
 pub struct GaugeGuard(&'static Gauge);
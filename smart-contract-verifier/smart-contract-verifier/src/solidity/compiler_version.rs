@@ -0,0 +1,289 @@
+use crate::{compiler::Version, verifier::Error};
+use anyhow::anyhow;
+use bytes::Bytes;
+use semver::VersionReq;
+use solidity_metadata::MetadataHash;
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+
+/// Maximum number of versions allowed by `pragma solidity` ranges to be checked.
+/// Newer versions are checked first
+const MAX_PRAGMA_CANDIDATES: usize = 10;
+
+/// Returns versions of the compiler the contract could be compiled with.
+///
+/// The version is taken from `solc` value of the metadata hash appended to the bytecode.
+/// If the bytecode contains no metadata (e.g., compiled with solc < 0.5.9, or with metadata
+/// disabled), the versions allowed by `pragma solidity` ranges of all sources are returned.
+pub fn detect(
+    all_versions: &[Version],
+    creation_bytecode: Option<&Bytes>,
+    deployed_bytecode: &Bytes,
+    sources: &BTreeMap<PathBuf, String>,
+) -> Result<Vec<Version>, Error> {
+    let bytecode = match creation_bytecode {
+        Some(creation_bytecode) if deployed_bytecode.is_empty() => creation_bytecode,
+        _ => deployed_bytecode,
+    };
+
+    if let Some(solc) = metadata_solc_version(bytecode) {
+        let versions = versions_from_metadata(all_versions, &solc);
+        if versions.is_empty() {
+            return Err(Error::Initialization(anyhow!(
+                "compiler version {solc} specified in the bytecode metadata is not available"
+            )));
+        }
+        return Ok(versions);
+    }
+
+    let requirements: Vec<_> = sources
+        .values()
+        .flat_map(|source| pragma_requirements(source))
+        .collect();
+    if requirements.is_empty() {
+        return Err(Error::Initialization(anyhow!(
+            "compiler version could not be detected: bytecode contains no metadata and sources contain no `pragma solidity`"
+        )));
+    }
+    let mut versions: Vec<_> = all_versions
+        .iter()
+        .filter(|version| version.is_release())
+        .filter(|version| {
+            requirements.iter().all(|alternatives| {
+                alternatives
+                    .iter()
+                    .any(|requirement| requirement.matches(version.version()))
+            })
+        })
+        .cloned()
+        .collect();
+    versions.sort_by(|x, y| x.cmp(y).reverse());
+    versions.truncate(MAX_PRAGMA_CANDIDATES);
+    if versions.is_empty() {
+        return Err(Error::Initialization(anyhow!(
+            "no available compiler versions satisfy `pragma solidity` of the sources"
+        )));
+    }
+    Ok(versions)
+}
+
+/// Solidity appends CBOR encoded metadata hash followed by its 2-byte length to the runtime code.
+/// Creation inputs may contain constructor arguments after the runtime code,
+/// so the metadata is searched starting from the end of the bytecode.
+fn metadata_solc_version(bytecode: &[u8]) -> Option<semver::Version> {
+    (0..bytecode.len().saturating_sub(2)).rev().find_map(|i| {
+        // CBOR maps of up to 15 elements start with 0xa1..=0xaf
+        if !(0xa1..=0xaf).contains(&bytecode[i]) {
+            return None;
+        }
+        let (metadata, length) = MetadataHash::from_cbor(&bytecode[i..]).ok()?;
+        let encoded_length = bytecode.get(i + length..i + length + 2)?;
+        if u16::from_be_bytes([encoded_length[0], encoded_length[1]]) as usize != length {
+            return None;
+        }
+        metadata.solc
+    })
+}
+
+/// Release builds encode only the version number, while prerelease builds
+/// encode the full version string including the commit hash
+fn versions_from_metadata(all_versions: &[Version], solc: &semver::Version) -> Vec<Version> {
+    let mut versions: Vec<_> = match Version::from_str(&solc.to_string()) {
+        Ok(detected) => all_versions
+            .iter()
+            .filter(|version| {
+                version.version() == detected.version()
+                    && version.date() == detected.date()
+                    && (version.commit().starts_with(detected.commit())
+                        || detected.commit().starts_with(version.commit()))
+            })
+            .cloned()
+            .collect(),
+        Err(_) => all_versions
+            .iter()
+            .filter(|version| version.is_release() && version.version() == solc)
+            .cloned()
+            .collect(),
+    };
+    versions.sort_by(|x, y| x.cmp(y).reverse());
+    versions
+}
+
+/// Requirements of every `pragma solidity` directive of the source.
+/// Every directive is represented by the list of its `||` separated alternatives
+fn pragma_requirements(source: &str) -> Vec<Vec<VersionReq>> {
+    source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("pragma solidity"))
+        .filter_map(|pragma| pragma.split(';').next())
+        .filter_map(parse_pragma)
+        .collect()
+}
+
+/// Converts solidity version pragma into semver requirements.
+/// Unlike semver, solidity treats versions without operators as exact ones,
+/// and separates comparators by whitespaces instead of commas
+fn parse_pragma(pragma: &str) -> Option<Vec<VersionReq>> {
+    pragma
+        .split("||")
+        .map(|alternative| {
+            let mut comparators = Vec::new();
+            let mut operator = String::new();
+            for token in alternative.split_whitespace() {
+                if token.chars().all(|c| "<>=^~".contains(c)) {
+                    operator.push_str(token);
+                    continue;
+                }
+                let comparator = format!("{operator}{token}");
+                operator.clear();
+                if comparator.starts_with(|c: char| c.is_ascii_digit()) {
+                    comparators.push(format!("={comparator}"));
+                } else {
+                    comparators.push(comparator);
+                }
+            }
+            VersionReq::parse(&comparators.join(", ")).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn versions(versions: &[&str]) -> Vec<Version> {
+        versions
+            .iter()
+            .map(|version| Version::from_str(version).unwrap())
+            .collect()
+    }
+
+    fn all_versions() -> Vec<Version> {
+        versions(&[
+            "v0.8.18-nightly.2022.11.23+commit.eb2f874e",
+            "v0.8.17+commit.8df45f5f",
+            "v0.8.16+commit.07a7930e",
+            "v0.7.6+commit.7338295f",
+            "v0.5.9+commit.e560f70d",
+            "v0.4.26+commit.4563c3fc",
+            "v0.4.24+commit.e67f0147",
+        ])
+    }
+
+    fn bytecode_with_metadata(solc: &[u8], suffix: &str) -> Bytes {
+        // "ipfs" hash and "solc" version
+        let mut metadata = hex::decode(
+            "a2646970667358221220c0ffee00c0ffee00c0ffee00c0ffee00c0ffee00c0ffee00c0ffee00c0ffee0064736f6c63",
+        )
+        .unwrap();
+        metadata.extend_from_slice(solc);
+        let mut bytecode = hex::decode("6080604052").unwrap();
+        bytecode.extend_from_slice(&metadata);
+        bytecode.extend_from_slice(&(metadata.len() as u16).to_be_bytes());
+        bytecode.extend_from_slice(&hex::decode(suffix).unwrap());
+        bytecode.into()
+    }
+
+    #[test]
+    fn detect_from_metadata() {
+        let sources = BTreeMap::new();
+        let deployed = bytecode_with_metadata(&[0x43, 0x00, 0x08, 0x11], "");
+        assert_eq!(
+            versions(&["v0.8.17+commit.8df45f5f"]),
+            detect(&all_versions(), None, &deployed, &sources).unwrap()
+        );
+
+        // constructor arguments follow metadata in creation inputs
+        let creation = bytecode_with_metadata(
+            &[0x43, 0x00, 0x05, 0x09],
+            "000000000000000000000000000000000000000000000000000000000000a2a1",
+        );
+        assert_eq!(
+            versions(&["v0.5.9+commit.e560f70d"]),
+            detect(&all_versions(), Some(&creation), &Bytes::new(), &sources).unwrap()
+        );
+
+        let nightly = "0.8.18-nightly.2022.11.23+commit.eb2f874e";
+        let mut solc = vec![0x78, nightly.len() as u8];
+        solc.extend_from_slice(nightly.as_bytes());
+        let deployed = bytecode_with_metadata(&solc, "");
+        assert_eq!(
+            versions(&["v0.8.18-nightly.2022.11.23+commit.eb2f874e"]),
+            detect(&all_versions(), None, &deployed, &sources).unwrap()
+        );
+
+        let deployed = bytecode_with_metadata(&[0x43, 0x00, 0x08, 0x13], "");
+        assert!(detect(&all_versions(), None, &deployed, &sources).is_err());
+    }
+
+    #[test]
+    fn detect_from_pragmas() {
+        let deployed = Bytes::from_static(&[0x60, 0x80, 0x60, 0x40, 0x52]);
+        let sources = |contents: &[&str]| -> BTreeMap<PathBuf, String> {
+            contents
+                .iter()
+                .enumerate()
+                .map(|(i, content)| (PathBuf::from(format!("{i}.sol")), content.to_string()))
+                .collect()
+        };
+
+        let detected = detect(
+            &all_versions(),
+            None,
+            &deployed,
+            &sources(&[
+                "pragma solidity ^0.8.0;\ncontract A {}",
+                "pragma solidity >=0.7.0 <0.9.0;",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            versions(&["v0.8.17+commit.8df45f5f", "v0.8.16+commit.07a7930e"]),
+            detected
+        );
+
+        let detected = detect(
+            &all_versions(),
+            None,
+            &deployed,
+            &sources(&[
+                "  pragma solidity 0.4.24;",
+                "pragma solidity >= 0.4.0 || ^0.8.0;",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(versions(&["v0.4.24+commit.e67f0147"]), detected);
+
+        assert!(detect(
+            &all_versions(),
+            None,
+            &deployed,
+            &sources(&["contract A {}"])
+        )
+        .is_err());
+        assert!(detect(
+            &all_versions(),
+            None,
+            &deployed,
+            &sources(&["pragma solidity ^0.6.0;"])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parse_pragmas() {
+        let parse = |pragma: &str| -> Vec<String> {
+            parse_pragma(pragma)
+                .unwrap()
+                .iter()
+                .map(|requirement| requirement.to_string())
+                .collect()
+        };
+        assert_eq!(vec!["^0.8.0"], parse(" ^0.8.0"));
+        assert_eq!(vec!["=0.4.24"], parse(" 0.4.24"));
+        assert_eq!(vec![">=0.4.22, <0.9.0"], parse(" >=0.4.22 <0.9.0"));
+        assert_eq!(vec![">=0.4.22, <0.9.0"], parse(" >= 0.4.22 < 0.9.0"));
+        assert_eq!(vec!["^0.4.24", "^0.5.0"], parse(" ^0.4.24 || ^0.5.0"));
+        assert!(parse_pragma(" experimental").is_none());
+    }
+}
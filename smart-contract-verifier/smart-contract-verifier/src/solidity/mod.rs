@@ -1,5 +1,7 @@
 mod client;
 mod compiler;
+mod compiler_version;
+mod settings_search;
 mod solc_cli;
 mod types;
 mod validator;
@@ -9,5 +11,6 @@ pub mod standard_json;
 
 pub use client::Client;
 pub use compiler::SolidityCompiler;
+pub use settings_search::SettingsSearch;
 pub use types::Success;
 pub use validator::SolcValidator;
@@ -1,23 +1,36 @@
-use super::{client::Client, types::Success};
+use super::{
+    client::Client, compiler_version, settings_search::SettingsSearch, types::Success,
+    SolidityCompiler,
+};
 use crate::{
-    compiler::Version,
+    compiler::{Compilers, Version},
     verifier::{ContractVerifier, Error},
+    MatchType,
 };
 use bytes::Bytes;
 use ethers_solc::{
     artifacts::{BytecodeHash, Libraries, Settings, SettingsMetadata, Source, Sources},
     CompilerInput, EvmVersion,
 };
+use futures::{stream, StreamExt};
 use semver::VersionReq;
 use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
+/// Maximum number of settings combinations of one request verified at the same time
+const MAX_CONCURRENT_CONTENTS: usize = 8;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VerificationRequest {
     pub deployed_bytecode: Bytes,
     pub creation_bytecode: Option<Bytes>,
-    pub compiler_version: Version,
+    /// If absent, the version is detected from the bytecode metadata
+    /// or `pragma solidity` of the sources
+    pub compiler_version: Option<Version>,
 
     pub content: MultiFileContent,
+    /// If present, the contract is also compiled with other common evm versions
+    /// and optimizer runs. Matched settings are returned in the compiler input of the result
+    pub settings_search: Option<SettingsSearch>,
 
     // Required for the metrics. Has no functional meaning.
     // In case if chain_id has not been provided, results in empty string.
@@ -63,19 +76,128 @@ impl From<MultiFileContent> for Vec<CompilerInput> {
 }
 
 pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result<Success, Error> {
-    let compiler_version = request.compiler_version;
+    let (compiler_versions, is_detected) = match request.compiler_version.clone() {
+        Some(compiler_version) => (vec![compiler_version], false),
+        None => {
+            let versions = compiler_version::detect(
+                &client.compilers().all_versions(),
+                request.creation_bytecode.as_ref(),
+                &request.deployed_bytecode,
+                &request.content.sources,
+            )?;
+            (versions, true)
+        }
+    };
+
+    let mut verified = None;
+    let mut last_error = Error::NoMatchingContracts;
+    // the budget is shared by all compiler versions of the request
+    let mut search_budget = request
+        .settings_search
+        .map(|settings_search| settings_search.max_candidates);
+    for compiler_version in compiler_versions {
+        let contents = match search_budget {
+            Some(budget) => {
+                // the provided settings are checked even if the budget is spent
+                let contents =
+                    SettingsSearch::new(budget).candidates(&request.content, &compiler_version);
+                search_budget = Some(budget.saturating_sub(contents.len()));
+                contents
+            }
+            None => vec![request.content.clone()],
+        };
+        let result =
+            verify_contents(client.compilers(), &compiler_version, &request, contents).await;
+
+        // Detected versions are only candidates, so the next one should be tried if the contract
+        // could not be verified with the current version. Partial matches are possible for
+        // several versions, as the metadata hash depends on the compiler version.
+        match result {
+            Ok(success) if is_detected && success.match_type == MatchType::Partial => {
+                verified.get_or_insert(success);
+            }
+            Ok(success) => {
+                verified = Some(success);
+                break;
+            }
+            Err(
+                err @ (Error::NoMatchingContracts
+                | Error::CompilerVersionMismatch(_)
+                | Error::Compilation(_)
+                | Error::VersionNotFound(_)),
+            ) if is_detected => last_error = err,
+            Err(err) => return Err(err),
+        }
+    }
 
+    match verified {
+        Some(success) => {
+            // Allow middlewares to process success and only then return it to the caller
+            if let Some(middleware) = client.middleware() {
+                middleware.call(&success).await;
+            }
+            Ok(success)
+        }
+        None => Err(last_error),
+    }
+}
+
+/// Contents are compiled concurrently, up to [`MAX_CONCURRENT_CONTENTS`] at a time,
+/// and compilations are also limited by the compilers threads semaphore.
+/// Returns the first full match in the order of the contents,
+/// or the first partial match if there are no full ones.
+async fn verify_contents(
+    compilers: &Compilers<SolidityCompiler>,
+    compiler_version: &Version,
+    request: &VerificationRequest,
+    contents: Vec<MultiFileContent>,
+) -> Result<Success, Error> {
+    let mut results = stream::iter(contents)
+        .map(|content| verify_content(compilers, compiler_version, request, content))
+        .buffered(MAX_CONCURRENT_CONTENTS);
+
+    let mut partial_match = None;
+    let mut first_error = None;
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(success) if success.match_type == MatchType::Full => return Ok(success),
+            Ok(success) => {
+                partial_match.get_or_insert(success);
+            }
+            Err(
+                err @ (Error::NoMatchingContracts
+                | Error::CompilerVersionMismatch(_)
+                | Error::Compilation(_)),
+            ) => {
+                first_error.get_or_insert(err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    match partial_match {
+        Some(success) => Ok(success),
+        None => Err(first_error.unwrap_or(Error::NoMatchingContracts)),
+    }
+}
+
+async fn verify_content(
+    compilers: &Compilers<SolidityCompiler>,
+    compiler_version: &Version,
+    request: &VerificationRequest,
+    content: MultiFileContent,
+) -> Result<Success, Error> {
     let verifier = ContractVerifier::new(
-        client.compilers(),
-        &compiler_version,
-        request.creation_bytecode,
-        request.deployed_bytecode,
-        request.chain_id,
+        compilers,
+        compiler_version,
+        request.creation_bytecode.clone(),
+        request.deployed_bytecode.clone(),
+        request.chain_id.clone(),
     )?;
 
-    let compiler_inputs: Vec<CompilerInput> = request.content.into();
+    let compiler_inputs: Vec<CompilerInput> = content.into();
     for mut compiler_input in compiler_inputs {
-        for metadata in settings_metadata(&compiler_version) {
+        for metadata in settings_metadata(compiler_version) {
             compiler_input.settings.metadata = metadata;
             let result = verifier.verify(&compiler_input).await;
 
@@ -84,14 +206,8 @@ pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result
                 continue;
             }
 
-            // If any error, it is uncorrectable and should be returned immediately, otherwise
-            // we allow middlewares to process success and only then return it to the caller
-            let success = Success::from((compiler_input, result?));
-            if let Some(middleware) = client.middleware() {
-                middleware.call(&success).await;
-            }
-
-            return Ok(success);
+            // If any error, it is uncorrectable and should be returned immediately
+            return Ok(Success::from((compiler_input, result?)));
         }
     }
 
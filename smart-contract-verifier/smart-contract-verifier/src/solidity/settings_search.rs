@@ -0,0 +1,175 @@
+use super::multi_part::MultiFileContent;
+use crate::compiler::Version;
+use ethers_solc::EvmVersion;
+use std::str::FromStr;
+
+/// Optimizer runs most commonly used by developers, sorted by their popularity.
+/// `None` stands for the disabled optimizer
+const OPTIMIZATION_RUNS: [Option<usize>; 10] = [
+    None,
+    Some(200),
+    Some(1),
+    Some(999999),
+    Some(10000),
+    Some(1000),
+    Some(100),
+    Some(500),
+    Some(5000),
+    Some(20000),
+];
+
+/// EVM versions with the first compiler versions supporting them, sorted from the newest
+const EVM_VERSIONS: [(&str, semver::Version); 11] = [
+    ("shanghai", semver::Version::new(0, 8, 20)),
+    ("paris", semver::Version::new(0, 8, 18)),
+    ("london", semver::Version::new(0, 8, 7)),
+    ("berlin", semver::Version::new(0, 8, 5)),
+    ("istanbul", semver::Version::new(0, 5, 14)),
+    ("petersburg", semver::Version::new(0, 5, 5)),
+    ("constantinople", semver::Version::new(0, 4, 21)),
+    ("byzantium", semver::Version::new(0, 4, 21)),
+    ("spuriousDragon", semver::Version::new(0, 4, 21)),
+    ("tangerineWhistle", semver::Version::new(0, 4, 21)),
+    ("homestead", semver::Version::new(0, 4, 21)),
+];
+
+/// Search through the most common compiler settings,
+/// used if the contract could not be verified with the settings provided by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettingsSearch {
+    /// Maximum number of settings combinations checked for the request across
+    /// all compiler versions. The provided settings are checked for every version anyway
+    pub max_candidates: usize,
+}
+
+impl SettingsSearch {
+    pub fn new(max_candidates: usize) -> Self {
+        Self { max_candidates }
+    }
+
+    /// Returns the content with provided settings followed by the contents with
+    /// the other plausible evm versions and optimizer runs for the compiler version.
+    pub(crate) fn candidates(
+        &self,
+        content: &MultiFileContent,
+        compiler_version: &Version,
+    ) -> Vec<MultiFileContent> {
+        let runs = with_first(content.optimization_runs, OPTIMIZATION_RUNS);
+        let evm_versions = with_first(
+            content.evm_version,
+            std::iter::once(None).chain(evm_versions(compiler_version).map(Some)),
+        );
+
+        runs.iter()
+            .flat_map(|optimization_runs| {
+                evm_versions.iter().map(|evm_version| MultiFileContent {
+                    sources: content.sources.clone(),
+                    evm_version: *evm_version,
+                    optimization_runs: *optimization_runs,
+                    contract_libraries: content.contract_libraries.clone(),
+                })
+            })
+            .take(self.max_candidates.max(1))
+            .collect()
+    }
+}
+
+/// EVM versions supported by the compiler, sorted from the newest.
+/// Compilers before v0.4.21 do not allow to specify the EVM version at all
+fn evm_versions(compiler_version: &Version) -> impl Iterator<Item = EvmVersion> + '_ {
+    EVM_VERSIONS
+        .into_iter()
+        .filter(|(_, supported_since)| compiler_version.version() >= supported_since)
+        .filter_map(|(name, _)| EvmVersion::from_str(name).ok())
+}
+
+fn with_first<T: PartialEq>(first: T, rest: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut values = vec![first];
+    for value in rest {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn content(
+        evm_version: Option<EvmVersion>,
+        optimization_runs: Option<usize>,
+    ) -> MultiFileContent {
+        MultiFileContent {
+            sources: BTreeMap::from([("source.sol".into(), "contract A {}".into())]),
+            evm_version,
+            optimization_runs,
+            contract_libraries: None,
+        }
+    }
+
+    fn settings(contents: &[MultiFileContent]) -> Vec<(Option<EvmVersion>, Option<usize>)> {
+        contents
+            .iter()
+            .map(|content| (content.evm_version, content.optimization_runs))
+            .collect()
+    }
+
+    #[test]
+    fn provided_settings_go_first() {
+        let version = Version::from_str("v0.8.7+commit.e28d00a7").unwrap();
+        let candidates = SettingsSearch::new(5)
+            .candidates(&content(Some(EvmVersion::Berlin), Some(200)), &version);
+        assert_eq!(
+            vec![
+                (Some(EvmVersion::Berlin), Some(200)),
+                (None, Some(200)),
+                (Some(EvmVersion::London), Some(200)),
+                (Some(EvmVersion::Istanbul), Some(200)),
+                (Some(EvmVersion::Petersburg), Some(200)),
+            ],
+            settings(&candidates)
+        );
+    }
+
+    #[test]
+    fn evm_versions_supported_by_compiler() {
+        let version = Version::from_str("v0.5.9+commit.e560f70d").unwrap();
+        let candidates = SettingsSearch::new(100).candidates(&content(None, None), &version);
+        assert_eq!(OPTIMIZATION_RUNS.len() * 7, candidates.len());
+        assert_eq!(
+            vec![
+                (None, None),
+                (Some(EvmVersion::Petersburg), None),
+                (Some(EvmVersion::Constantinople), None),
+                (Some(EvmVersion::Byzantium), None),
+                (Some(EvmVersion::SpuriousDragon), None),
+                (Some(EvmVersion::TangerineWhistle), None),
+                (Some(EvmVersion::Homestead), None),
+                (None, Some(200)),
+            ],
+            settings(&candidates[..8])
+        );
+
+        let version = Version::from_str("v0.4.10+commit.9e8cc01b").unwrap();
+        let candidates = SettingsSearch::new(100).candidates(&content(None, Some(200)), &version);
+        assert_eq!(
+            vec![
+                (None, Some(200)),
+                (None, None),
+                (None, Some(1)),
+                (None, Some(999999)),
+                (None, Some(10000)),
+                (None, Some(1000)),
+                (None, Some(100)),
+                (None, Some(500)),
+                (None, Some(5000)),
+                (None, Some(20000)),
+            ],
+            settings(&candidates)
+        );
+    }
+}
@@ -59,13 +59,14 @@ pub mod solidity {
             Self {
                 deployed_bytecode: source.deployed_bytecode,
                 creation_bytecode: source.creation_bytecode,
-                compiler_version: source.compiler_version,
+                compiler_version: Some(source.compiler_version),
                 content: multi_part::MultiFileContent {
                     sources: source.sources,
                     evm_version: source.evm_version,
                     optimization_runs: source.optimization_runs,
                     contract_libraries: source.contract_libraries,
                 },
+                settings_search: None,
                 chain_id: Default::default(),
             }
         }
@@ -73,6 +74,7 @@ pub mod solidity {
 
     impl From<VerificationRequest> for standard_json::VerificationRequest {
         fn from(source: VerificationRequest) -> Self {
+            let compiler_version = source.compiler_version.clone();
             let multi_part_request = multi_part::VerificationRequest::from(source);
             let input = {
                 let input: Vec<CompilerInput> = multi_part_request.content.into();
@@ -84,7 +86,7 @@ pub mod solidity {
             Self {
                 deployed_bytecode: multi_part_request.deployed_bytecode,
                 creation_bytecode: multi_part_request.creation_bytecode,
-                compiler_version: multi_part_request.compiler_version,
+                compiler_version,
                 content: standard_json::StandardJsonContent { input },
                 chain_id: Default::default(),
             }
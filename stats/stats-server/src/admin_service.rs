@@ -0,0 +1,122 @@
+use crate::{
+    chains::{Chain, Chains},
+    read_service::map_chain_error,
+    update_service::UpdateService,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use stats_proto::blockscout::stats::v1::{
+    stats_admin_service_server::StatsAdminService, CancelUpdateRequest, CancelUpdateResponse,
+    ListUpdateStatusesRequest, TriggerUpdateRequest, TriggerUpdateResponse, UpdateStatus,
+    UpdateStatuses,
+};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tonic::{Request, Response, Status};
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+pub struct AdminService {
+    chains: Arc<Chains>,
+    update_service: Arc<UpdateService>,
+    api_key: String,
+}
+
+impl AdminService {
+    pub fn new(chains: Arc<Chains>, update_service: Arc<UpdateService>, api_key: String) -> Self {
+        Self {
+            chains,
+            update_service,
+            api_key,
+        }
+    }
+
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let api_key = request
+            .metadata()
+            .get(API_KEY_HEADER)
+            .and_then(|key| key.to_str().ok());
+        match api_key {
+            Some(key) if bool::from(key.as_bytes().ct_eq(self.api_key.as_bytes())) => Ok(()),
+            Some(_) => Err(Status::permission_denied("invalid api key")),
+            None => Err(Status::unauthenticated(format!(
+                "{API_KEY_HEADER} header is required"
+            ))),
+        }
+    }
+
+    fn chain(&self, chain_id: Option<&str>) -> Result<&Arc<Chain>, Status> {
+        self.chains.get(chain_id).map_err(map_chain_error)
+    }
+}
+
+fn format_time(time: NaiveDateTime) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+#[async_trait]
+impl StatsAdminService for AdminService {
+    async fn trigger_update(
+        &self,
+        request: Request<TriggerUpdateRequest>,
+    ) -> Result<Response<TriggerUpdateResponse>, Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let chain = self.chain(request.chain_id.as_deref())?;
+        if !self
+            .update_service
+            .trigger_update(chain, &request.name, request.force_full)
+        {
+            return Err(Status::not_found(format!(
+                "chart {} not found",
+                request.name
+            )));
+        }
+        tracing::info!(
+            chain_id = %chain.id,
+            chart = %request.name,
+            force_full = request.force_full,
+            "chart update was triggered through admin api"
+        );
+        Ok(Response::new(TriggerUpdateResponse {}))
+    }
+
+    async fn list_update_statuses(
+        &self,
+        request: Request<ListUpdateStatusesRequest>,
+    ) -> Result<Response<UpdateStatuses>, Status> {
+        self.authorize(&request)?;
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        let statuses = stats::get_update_statuses(&chain.db)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let mut result = Vec::with_capacity(statuses.len());
+        for status in statuses {
+            let batch_progress = stats::get_batch_progress(chain.db.as_ref(), &status.name)
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?
+                .map(|progress| progress.ratio() * 100.0);
+            result.push(UpdateStatus {
+                batch_progress,
+                is_locked: stats::is_update_mutex_locked(&chain.id, &status.name).await,
+                is_running: self.update_service.is_running(chain, &status.name),
+                name: status.name,
+                last_updated_at: status.last_updated_at.map(format_time),
+                last_error: status.last_error,
+                last_error_at: status.last_error_at.map(format_time),
+            });
+        }
+        Ok(Response::new(UpdateStatuses { statuses: result }))
+    }
+
+    async fn cancel_update(
+        &self,
+        request: Request<CancelUpdateRequest>,
+    ) -> Result<Response<CancelUpdateResponse>, Status> {
+        self.authorize(&request)?;
+        let request = request.into_inner();
+        let chain = self.chain(request.chain_id.as_deref())?;
+        let cancelled = self.update_service.cancel_update(chain, &request.name);
+        Ok(Response::new(CancelUpdateResponse { cancelled }))
+    }
+}
@@ -0,0 +1,296 @@
+use crate::{
+    alerts_config::{AlertRule, Config, SinkConfig},
+    charts::Charts,
+};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use stats::{AlertState, Resolution};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+impl Display for AlertStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertStatus::Firing => f.write_str("firing"),
+            AlertStatus::Resolved => f.write_str("resolved"),
+        }
+    }
+}
+
+/// Change of the state of an alert rule
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    pub chain_id: String,
+    pub rule: String,
+    pub chart: String,
+    pub status: AlertStatus,
+    /// Date of the point that changed the state
+    pub date: NaiveDate,
+    pub value: String,
+    pub previous_value: Option<String>,
+}
+
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error>;
+}
+
+pub struct LogSink;
+
+#[async_trait]
+impl AlertSink for LogSink {
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        match event.status {
+            AlertStatus::Firing => tracing::warn!(
+                chain_id = %event.chain_id,
+                rule = %event.rule,
+                chart = %event.chart,
+                date = %event.date,
+                value = %event.value,
+                "alert is firing"
+            ),
+            AlertStatus::Resolved => tracing::info!(
+                chain_id = %event.chain_id,
+                rule = %event.rule,
+                chart = %event.chart,
+                date = %event.date,
+                value = %event.value,
+                "alert is resolved"
+            ),
+        }
+        Ok(())
+    }
+}
+
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl WebhookSink {
+    pub fn new(url: String, headers: BTreeMap<String, String>) -> Result<Self, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            client,
+            url,
+            headers,
+        })
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    async fn send(&self, event: &AlertEvent) -> Result<(), anyhow::Error> {
+        let body = serde_json::to_vec(event)?;
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in self.headers.iter() {
+            request = request.header(name, value);
+        }
+        request.body(body).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+fn new_sink(config: SinkConfig) -> Result<Arc<dyn AlertSink>, anyhow::Error> {
+    let sink: Arc<dyn AlertSink> = match config {
+        SinkConfig::Webhook { url, headers } => Arc::new(WebhookSink::new(url, headers)?),
+        SinkConfig::Log => Arc::new(LogSink),
+    };
+    Ok(sink)
+}
+
+fn parse_value(chart: &str, value: &str) -> Result<f64, anyhow::Error> {
+    f64::from_str(value)
+        .map_err(|err| anyhow::anyhow!("invalid value '{}' of chart {}: {}", value, chart, err))
+}
+
+/// Alert rules of a chain, which are evaluated after every successful update of their charts
+#[derive(Default)]
+pub struct Alerts {
+    rules: Vec<AlertRule>,
+    sinks: HashMap<String, Arc<dyn AlertSink>>,
+}
+
+impl Alerts {
+    pub fn new(config: Config, charts: &Charts) -> Result<Self, anyhow::Error> {
+        let sinks = config
+            .sinks
+            .into_iter()
+            .map(|(name, sink)| Ok((name, new_sink(sink)?)))
+            .collect::<Result<HashMap<_, _>, anyhow::Error>>()?;
+
+        let mut ids = HashSet::new();
+        for rule in config.rules.iter() {
+            if !ids.insert(rule.id.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "encountered same alert rule id twice: {}",
+                    rule.id
+                ));
+            }
+            if !charts.counters_filter.contains(&rule.chart)
+                && !charts.lines_filter.contains(&rule.chart)
+            {
+                return Err(anyhow::anyhow!(
+                    "alert rule {} refers to unknown chart {}",
+                    rule.id,
+                    rule.chart
+                ));
+            }
+            if rule.sinks.is_empty() {
+                return Err(anyhow::anyhow!("alert rule {} has no sinks", rule.id));
+            }
+            if let Some(sink) = rule.sinks.iter().find(|sink| !sinks.contains_key(*sink)) {
+                return Err(anyhow::anyhow!(
+                    "alert rule {} refers to unknown sink {}",
+                    rule.id,
+                    sink
+                ));
+            }
+        }
+        Ok(Self {
+            rules: config.rules,
+            sinks,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub async fn evaluate(
+        &self,
+        chain_id: &str,
+        db: &DatabaseConnection,
+        charts: &Charts,
+        chart_name: &str,
+    ) {
+        for rule in self.rules.iter().filter(|rule| rule.chart == chart_name) {
+            if let Err(err) = self.evaluate_rule(chain_id, db, charts, rule).await {
+                tracing::error!(
+                    chain_id = chain_id,
+                    rule = %rule.id,
+                    "failed to evaluate alert rule: {}",
+                    err
+                );
+            }
+        }
+    }
+
+    /// Sends an alert to every sink of the rule, for which the rule changed its state
+    /// since the last alert delivered to the sink. The state is saved for every sink
+    /// that received the alert, so only failed sinks get it after the next update of the chart
+    async fn evaluate_rule(
+        &self,
+        chain_id: &str,
+        db: &DatabaseConnection,
+        charts: &Charts,
+        rule: &AlertRule,
+    ) -> Result<(), anyhow::Error> {
+        let drop_last_point = charts
+            .settings
+            .get(&rule.chart)
+            .map(|settings| settings.drop_last_point)
+            .unwrap_or_default();
+        let limit = 1 + u64::from(rule.condition.needs_previous()) + u64::from(drop_last_point);
+        let mut points = stats::get_last_chart_data(db, &rule.chart, limit).await?;
+        if drop_last_point {
+            // last point can be partially updated
+            if let Some(last) = points.last() {
+                let start = last
+                    .date
+                    .and_hms_opt(0, 0, 0)
+                    .expect("midnight always exists");
+                if Resolution::Day.is_partial(start, charts.timezone(&rule.chart)) {
+                    points.pop();
+                }
+            }
+        }
+        let last = match points.pop() {
+            Some(last) => last,
+            None => return Ok(()),
+        };
+        let previous = points.pop();
+
+        let value = parse_value(&rule.chart, &last.value)?;
+        let previous_value = previous
+            .as_ref()
+            .map(|point| parse_value(&rule.chart, &point.value))
+            .transpose()?;
+        let is_firing = match rule.condition.is_met(value, previous_value) {
+            Some(is_firing) => is_firing,
+            None => return Ok(()),
+        };
+
+        let event = AlertEvent {
+            chain_id: chain_id.to_owned(),
+            rule: rule.id.clone(),
+            chart: rule.chart.clone(),
+            status: if is_firing {
+                AlertStatus::Firing
+            } else {
+                AlertStatus::Resolved
+            },
+            date: last.date,
+            value: last.value,
+            previous_value: previous.map(|point| point.value),
+        };
+        // state of the whole rule was saved before states of sinks
+        let rule_state = stats::get_alert_state(db, &rule.id).await?;
+        for name in rule.sinks.iter() {
+            let state_id = sink_state_id(&rule.id, name);
+            let was_firing = stats::get_alert_state(db, &state_id)
+                .await?
+                .or_else(|| rule_state.clone())
+                .map(|state| state.is_firing)
+                .unwrap_or_default();
+            if is_firing == was_firing {
+                continue;
+            }
+            match self.sinks[name].send(&event).await {
+                Ok(()) => {
+                    let state = AlertState {
+                        is_firing,
+                        date: event.date,
+                        changed_at: Utc::now().naive_utc(),
+                    };
+                    stats::save_alert_state(db, &state_id, &state).await?;
+                }
+                Err(err) => tracing::error!(
+                    chain_id = chain_id,
+                    rule = %rule.id,
+                    sink = %name,
+                    "failed to deliver {} alert: {}",
+                    event.status,
+                    err
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn sink_state_id(rule: &str, sink: &str) -> String {
+    format!("{rule}:{sink}")
+}
@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Destinations of alerts by name
+    #[serde(default)]
+    pub sinks: BTreeMap<String, SinkConfig>,
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SinkConfig {
+    /// Alert is sent as json in body of POST request
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Alert is written to logs of the service
+    Log,
+}
+
+/// Condition on the last point of the chart
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "condition", rename_all = "snake_case")]
+pub enum Condition {
+    Above {
+        threshold: f64,
+    },
+    Below {
+        threshold: f64,
+    },
+    /// Value decreased by at least `percent` compared to the previous point
+    DropPercent {
+        percent: f64,
+    },
+    /// Value increased by at least `percent` compared to the previous point
+    RisePercent {
+        percent: f64,
+    },
+}
+
+impl Condition {
+    /// Whether the condition compares the last point with the previous one
+    pub fn needs_previous(&self) -> bool {
+        matches!(
+            self,
+            Condition::DropPercent { .. } | Condition::RisePercent { .. }
+        )
+    }
+
+    /// `None` if the previous value is required but unknown or zero
+    pub fn is_met(&self, value: f64, previous: Option<f64>) -> Option<bool> {
+        let change_percent = || {
+            previous
+                .filter(|previous| *previous != 0.0)
+                .map(|previous| (value - previous) / previous.abs() * 100.0)
+        };
+        match *self {
+            Condition::Above { threshold } => Some(value > threshold),
+            Condition::Below { threshold } => Some(value < threshold),
+            Condition::DropPercent { percent } => change_percent().map(|change| -change >= percent),
+            Condition::RisePercent { percent } => change_percent().map(|change| change >= percent),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRule {
+    pub id: String,
+    /// Id of enabled counter or line chart
+    pub chart: String,
+    #[serde(flatten)]
+    pub condition: Condition,
+    /// Names of sinks from `sinks`
+    pub sinks: Vec<String>,
+}
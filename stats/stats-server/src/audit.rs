@@ -0,0 +1,271 @@
+use crate::{
+    chains::{Chains, DEFAULT_CHAIN_ID},
+    charts::Charts,
+    server::read_charts,
+    settings::Settings,
+};
+use chrono::{Duration, NaiveDate};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use stats::{
+    audit::{audit_chart, repair_chart, sample_dates, Mismatch},
+    ArcChart,
+};
+use std::{collections::HashSet, str::FromStr};
+
+const DEFAULT_SAMPLE_SIZE: usize = 30;
+
+pub const USAGE: &str = "usage: stats-server audit [--chain <id>] [--charts <name,...>] \
+    [--sample <size>] [--dates <YYYY-MM-DD,...>] [--repair]";
+
+/// Arguments of `audit` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditArgs {
+    /// Default is the only configured chain or the default one
+    pub chain_id: Option<String>,
+    /// Default is all enabled charts
+    pub charts: Option<Vec<String>>,
+    /// Amount of evenly spaced dates from the first point to yesterday
+    pub sample: usize,
+    /// Dates to check instead of the sample
+    pub dates: Option<Vec<NaiveDate>>,
+    /// Replace mismatched points with recalculated values
+    pub repair: bool,
+}
+
+impl AuditArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, anyhow::Error> {
+        let mut result = Self {
+            chain_id: None,
+            charts: None,
+            sample: DEFAULT_SAMPLE_SIZE,
+            dates: None,
+            repair: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("missing value of {}\n{}", arg, USAGE))
+            };
+            match arg.as_str() {
+                "--chain" => result.chain_id = Some(value()?),
+                "--charts" => {
+                    result.charts = Some(value()?.split(',').map(str::to_owned).collect())
+                }
+                "--sample" => result.sample = value()?.parse()?,
+                "--dates" => {
+                    let dates = value()?
+                        .split(',')
+                        .map(NaiveDate::from_str)
+                        .collect::<Result<_, _>>()?;
+                    result.dates = Some(dates)
+                }
+                "--repair" => result.repair = true,
+                _ => return Err(anyhow::anyhow!("unknown argument {}\n{}", arg, USAGE)),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Result of the audit of one chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChartAudit {
+    /// The chart is not calculated from blockscout
+    Skipped,
+    Failed(String),
+    Checked {
+        dates: usize,
+        mismatches: Vec<Mismatch>,
+        /// First mismatched date if mismatches were repaired
+        repaired_from: Option<NaiveDate>,
+    },
+}
+
+impl ChartAudit {
+    /// Whether saved points are equal to recalculated ones or were repaired
+    pub fn is_consistent(&self) -> bool {
+        match self {
+            ChartAudit::Skipped => true,
+            ChartAudit::Failed(_) => false,
+            ChartAudit::Checked {
+                mismatches,
+                repaired_from,
+                ..
+            } => mismatches.is_empty() || repaired_from.is_some(),
+        }
+    }
+}
+
+/// Results of audited charts in order of the audit
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    pub charts: Vec<(String, ChartAudit)>,
+}
+
+impl AuditReport {
+    pub fn is_consistent(&self) -> bool {
+        self.charts.iter().all(|(_, audit)| audit.is_consistent())
+    }
+}
+
+fn format_value(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("none")
+}
+
+impl std::fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, audit) in self.charts.iter() {
+            match audit {
+                ChartAudit::Skipped => {
+                    writeln!(f, "{name}: skipped, is not calculated from blockscout")?
+                }
+                ChartAudit::Failed(err) => writeln!(f, "{name}: failed to audit: {err}")?,
+                ChartAudit::Checked {
+                    dates,
+                    mismatches,
+                    repaired_from,
+                } => {
+                    writeln!(
+                        f,
+                        "{name}: {} of {dates} dates mismatched",
+                        mismatches.len()
+                    )?;
+                    for mismatch in mismatches.iter() {
+                        writeln!(
+                            f,
+                            "  {}: stored {}, recalculated {}",
+                            mismatch.date,
+                            format_value(&mismatch.stored),
+                            format_value(&mismatch.recalculated)
+                        )?;
+                    }
+                    if let Some(date) = repaired_from {
+                        writeln!(
+                            f,
+                            "  repaired, points from {date} are recalculated by the next update"
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Dates of the sample are taken from the first saved point to the last finished day
+async fn audit_dates(
+    db: &DatabaseConnection,
+    charts: &Charts,
+    chart: &ArcChart,
+    args: &AuditArgs,
+) -> Result<Vec<NaiveDate>, anyhow::Error> {
+    if let Some(dates) = &args.dates {
+        return Ok(dates.clone());
+    }
+    let first = stats::get_chart_data(db, chart.name(), None, None)
+        .await?
+        .first()
+        .map(|point| point.date);
+    let yesterday = charts.timezone(chart.name()).today() - Duration::days(1);
+    Ok(first
+        .map(|first| sample_dates(first, yesterday, args.sample))
+        .unwrap_or_default())
+}
+
+/// Next updates of the chart, charts calculated from it and charts it is calculated from
+/// recalculate their points starting from the date
+async fn invalidate(
+    db: &DatabaseConnection,
+    charts: &Charts,
+    chart: &ArcChart,
+    date: NaiveDate,
+) -> Result<(), anyhow::Error> {
+    let mut invalidated = HashSet::new();
+    let affected = std::iter::once(chart.clone()).chain(chart.dependencies());
+    for affected in affected {
+        for chart in charts.graph.with_dependents(affected.name()) {
+            if invalidated.insert(chart.name().to_owned()) {
+                stats::reorg::invalidate_from(db, chart.name(), date).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finds mismatched points of the chart and repairs them if requested
+async fn audit_one(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    charts: &Charts,
+    chart: &ArcChart,
+    args: &AuditArgs,
+) -> Result<ChartAudit, anyhow::Error> {
+    let dates = audit_dates(db, charts, chart, args).await?;
+    let mismatches: Vec<Mismatch> =
+        match audit_chart(db, blockscout, chart.as_ref(), &dates).await? {
+            Some(mismatches) => mismatches,
+            None => return Ok(ChartAudit::Skipped),
+        };
+    let repaired_from = match mismatches.first() {
+        Some(mismatch) if args.repair => {
+            repair_chart(db, blockscout, chart.as_ref(), &mismatches).await?;
+            invalidate(db, charts, chart, mismatch.date).await?;
+            Some(mismatch.date)
+        }
+        _ => None,
+    };
+    Ok(ChartAudit::Checked {
+        dates: dates.len(),
+        mismatches,
+        repaired_from,
+    })
+}
+
+/// Recalculates dates of the charts from blockscout and compares them with saved points
+pub async fn audit(settings: Settings, args: AuditArgs) -> Result<AuditReport, anyhow::Error> {
+    let chains = Chains::settings(settings.default_chain(), &settings.chains)?;
+    let chain_id = match &args.chain_id {
+        Some(id) => id.as_str(),
+        None if chains.len() == 1 => chains[0].0.as_str(),
+        None => DEFAULT_CHAIN_ID,
+    };
+    let (_, chain_settings) = chains
+        .iter()
+        .find(|(id, _)| id == chain_id)
+        .ok_or_else(|| anyhow::anyhow!("chain {} not found", chain_id))?;
+    let charts = read_charts(chain_settings, &settings)?;
+
+    let mut opt = ConnectOptions::new(chain_settings.db_url.clone());
+    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    let db = Database::connect(opt).await?;
+    let mut opt = ConnectOptions::new(chain_settings.blockscout_db_url.clone());
+    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    let blockscout = Database::connect(opt).await?;
+
+    let audited: Vec<ArcChart> = match &args.charts {
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                charts
+                    .charts
+                    .iter()
+                    .find(|chart| chart.name() == name)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("chart {} is not enabled", name))
+            })
+            .collect::<Result<_, _>>()?,
+        None => charts.charts.clone(),
+    };
+
+    let mut report = AuditReport::default();
+    for chart in audited.iter() {
+        let timezone = charts.timezone(chart.name());
+        let audit = audit_one(&db, &blockscout, &charts, chart, &args);
+        let result =
+            stats::chain::scope(chain_id.to_owned(), stats::timezone::scope(timezone, audit)).await;
+        let result = result.unwrap_or_else(|err| ChartAudit::Failed(err.to_string()));
+        report.charts.push((chart.name().to_owned(), result));
+    }
+    Ok(report)
+}
@@ -0,0 +1,105 @@
+use crate::{
+    alerts::Alerts, charts::Charts, counters_watch::CountersWatch, read_cache::ReadCache,
+    settings::ChainSettings,
+};
+use sea_orm::DatabaseConnection;
+use std::{collections::BTreeMap, sync::Arc};
+use thiserror::Error;
+
+/// Id of the chain configured with top-level settings
+pub const DEFAULT_CHAIN_ID: &str = "default";
+
+#[derive(Error, Debug)]
+pub enum ChainError {
+    #[error("chain_id is required")]
+    Required,
+    #[error("chain {0} not found")]
+    NotFound(String),
+}
+
+/// Blockscout instance served by the deployment.
+/// Charts of every chain are stored in a separate stats database
+pub struct Chain {
+    pub id: String,
+    pub db: Arc<DatabaseConnection>,
+    pub blockscout: Arc<DatabaseConnection>,
+    pub charts: Arc<Charts>,
+    pub counters_watch: Arc<CountersWatch>,
+    pub alerts: Alerts,
+    pub read_cache: ReadCache,
+}
+
+impl Chain {
+    pub fn new(
+        id: String,
+        db: Arc<DatabaseConnection>,
+        blockscout: Arc<DatabaseConnection>,
+        charts: Arc<Charts>,
+        alerts: Alerts,
+        read_cache: ReadCache,
+    ) -> Self {
+        let counters_watch = Arc::new(CountersWatch::new(db.clone(), charts.clone()));
+        Self {
+            id,
+            db,
+            blockscout,
+            charts,
+            counters_watch,
+            alerts,
+            read_cache,
+        }
+    }
+}
+
+pub struct Chains {
+    chains: BTreeMap<String, Arc<Chain>>,
+}
+
+impl Chains {
+    pub fn new(chains: impl IntoIterator<Item = Chain>) -> Self {
+        let chains = chains
+            .into_iter()
+            .map(|chain| (chain.id.clone(), Arc::new(chain)))
+            .collect();
+        Self { chains }
+    }
+
+    /// Chains from `STATS__CHAINS` together with the default one, if it is configured
+    pub fn settings(
+        default: Option<ChainSettings>,
+        chains: &BTreeMap<String, ChainSettings>,
+    ) -> Result<Vec<(String, ChainSettings)>, anyhow::Error> {
+        if chains.contains_key(DEFAULT_CHAIN_ID) && default.is_some() {
+            return Err(anyhow::anyhow!(
+                "chain id '{DEFAULT_CHAIN_ID}' is reserved for the chain configured with top-level settings"
+            ));
+        }
+        let settings: Vec<_> = default
+            .map(|settings| (DEFAULT_CHAIN_ID.to_owned(), settings))
+            .into_iter()
+            .chain(chains.clone())
+            .collect();
+        if settings.is_empty() {
+            return Err(anyhow::anyhow!("no chains are configured"));
+        }
+        Ok(settings)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Chain>> {
+        self.chains.values()
+    }
+
+    /// Empty `chain_id` means the default chain
+    pub fn get(&self, chain_id: Option<&str>) -> Result<&Arc<Chain>, ChainError> {
+        match chain_id.filter(|id| !id.is_empty()) {
+            Some(id) => self
+                .chains
+                .get(id)
+                .ok_or_else(|| ChainError::NotFound(id.to_owned())),
+            None => self
+                .chains
+                .get(DEFAULT_CHAIN_ID)
+                .ok_or(ChainError::Required),
+        }
+    }
+}
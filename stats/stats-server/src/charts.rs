@@ -1,19 +1,33 @@
-use crate::charts_config::{ChartSettings, Config};
-use stats::{cache::Cache, counters, entity::sea_orm_active_enums::ChartType, lines, Chart};
+use crate::{
+    charts_config::{ChartSettings, Config},
+    dependency_graph::DependencyGraph,
+};
+use stats::{
+    cache::Cache, counters, entity::sea_orm_active_enums::ChartType, leaderboards, lines,
+    txns_rollup::TxnsRollup, Chart, ExpressionChart, MissingPrice, SqlChart, Timezone,
+    TokenChartKind, TokenLine,
+};
 use std::{
     collections::{HashMap, HashSet},
     hash::Hash,
     sync::Arc,
 };
 
-pub type ArcChart = Arc<dyn Chart + Send + Sync + 'static>;
+pub use stats::ArcChart;
 
 pub struct Charts {
     pub config: Config,
     pub charts: Vec<ArcChart>,
     pub counters_filter: HashSet<String>,
     pub lines_filter: HashSet<String>,
+    pub leaderboards_filter: HashSet<String>,
+    /// Names of charts of configured tokens
+    pub tokens_filter: HashSet<String>,
     pub settings: HashMap<String, ChartSettings>,
+    /// Enabled charts with their dependencies
+    pub graph: DependencyGraph,
+    /// Timezones of enabled charts and their dependencies
+    pub timezones: HashMap<String, Timezone>,
 }
 
 fn new_hashset_check_duplicates<T: Hash + Eq, I: IntoIterator<Item = T>>(
@@ -33,26 +47,94 @@ struct ValidatedConfig {
     charts: Vec<ArcChart>,
     counters_filter: HashSet<String>,
     lines_filter: HashSet<String>,
+    leaderboards_filter: HashSet<String>,
+    tokens_filter: HashSet<String>,
 }
 
 impl Charts {
-    pub fn new(config: Config) -> Result<Self, anyhow::Error> {
+    pub fn new(
+        config: Config,
+        default_timezone: Timezone,
+        missing_price: MissingPrice,
+    ) -> Result<Self, anyhow::Error> {
         let ValidatedConfig {
             charts,
             counters_filter,
             lines_filter,
-        } = Self::validate_config(&config)?;
+            leaderboards_filter,
+            tokens_filter,
+        } = Self::validate_config(&config, missing_price)?;
         let settings = Self::new_settings(&config);
+        Self::validate_resolutions(&charts, &settings)?;
+        let graph = DependencyGraph::new(&charts)?;
+        let timezones = Self::new_timezones(&graph, &settings, default_timezone)?;
         Ok(Self {
             config,
             charts,
             counters_filter,
             lines_filter,
+            leaderboards_filter,
+            tokens_filter,
             settings,
+            graph,
+            timezones,
         })
     }
 
-    fn validate_config(config: &Config) -> Result<ValidatedConfig, anyhow::Error> {
+    /// Timezone of the chart, that is used to bucket its points by dates
+    pub fn timezone(&self, name: &str) -> Timezone {
+        self.timezones.get(name).copied().unwrap_or_default()
+    }
+
+    /// Dependencies use the default timezone unless it's set in their own settings,
+    /// so charts of different timezones can depend only on charts without timezone
+    fn new_timezones(
+        graph: &DependencyGraph,
+        settings: &HashMap<String, ChartSettings>,
+        default_timezone: Timezone,
+    ) -> Result<HashMap<String, Timezone>, anyhow::Error> {
+        let timezones: HashMap<_, _> = graph
+            .iter()
+            .map(|chart| {
+                let timezone = settings
+                    .get(chart.name())
+                    .and_then(|settings| settings.timezone)
+                    .unwrap_or(default_timezone);
+                (chart.name().to_owned(), timezone)
+            })
+            .collect();
+        for chart in graph.iter() {
+            let timezone = timezones[chart.name()];
+            for dependency in chart.dependencies() {
+                let dependency_timezone = timezones[dependency.name()];
+                if dependency.uses_timezone() && dependency_timezone != timezone {
+                    return Err(anyhow::anyhow!(
+                        "chart {} has timezone {}, but its dependency {} has timezone {}",
+                        chart.name(),
+                        timezone,
+                        dependency.name(),
+                        dependency_timezone
+                    ));
+                }
+                if dependency.name() == TxnsRollup::default().name()
+                    && !TxnsRollup::supports_timezone(timezone)
+                {
+                    return Err(anyhow::anyhow!(
+                        "chart {} has timezone {}, but charts of {} support only whole hour offsets",
+                        chart.name(),
+                        timezone,
+                        dependency.name()
+                    ));
+                }
+            }
+        }
+        Ok(timezones)
+    }
+
+    fn validate_config(
+        config: &Config,
+        missing_price: MissingPrice,
+    ) -> Result<ValidatedConfig, anyhow::Error> {
         let counters_filter = config.counters.iter().map(|counter| counter.id.clone());
         let counters_filter = new_hashset_check_duplicates(counters_filter)
             .map_err(|id| anyhow::anyhow!("encountered same id twice: {}", id))?;
@@ -65,30 +147,106 @@ impl Charts {
         let lines_filter = new_hashset_check_duplicates(lines_filter)
             .map_err(|id| anyhow::anyhow!("encountered same id twice: {}", id))?;
 
+        let leaderboards_filter = config
+            .leaderboards
+            .iter()
+            .map(|leaderboard| leaderboard.id.clone());
+        let leaderboards_filter = new_hashset_check_duplicates(leaderboards_filter)
+            .map_err(|id| anyhow::anyhow!("encountered same id twice: {}", id))?;
+
         let mut counters_unknown = counters_filter.clone();
         let mut lines_unknown = lines_filter.clone();
-        let charts = Self::all_charts()
-            .into_iter()
+        let mut leaderboards_unknown = leaderboards_filter.clone();
+        let all_charts = Self::all_charts(missing_price);
+        let mut charts: Vec<_> = all_charts
+            .iter()
             .filter(|chart| match chart.chart_type() {
                 ChartType::Counter => counters_unknown.remove(chart.name()),
                 ChartType::Line => lines_unknown.remove(chart.name()),
+                ChartType::Leaderboard => leaderboards_unknown.remove(chart.name()),
+                // internal charts are updated only as dependencies of served charts
+                ChartType::Internal => false,
             })
+            .cloned()
             .collect();
 
-        if !counters_unknown.is_empty() || !lines_unknown.is_empty() {
-            return Err(anyhow::anyhow!(
-                "found unknown chart ids: {:?}",
-                counters_unknown.union(&lines_unknown)
-            ));
+        let sql_charts = Self::sql_charts(config)?;
+        for chart in sql_charts.iter() {
+            let is_unknown = match chart.chart_type() {
+                ChartType::Counter => counters_unknown.remove(chart.name()),
+                ChartType::Line => lines_unknown.remove(chart.name()),
+                ChartType::Leaderboard => leaderboards_unknown.remove(chart.name()),
+                // internal charts are updated only as dependencies of served charts
+                ChartType::Internal => false,
+            };
+            if !is_unknown {
+                return Err(anyhow::anyhow!(
+                    "sql chart {} has the same id as built-in chart",
+                    chart.name()
+                ));
+            }
+            charts.push(chart.clone());
+        }
+
+        let known: Vec<_> = all_charts.into_iter().chain(sql_charts).collect();
+        for chart in Self::expression_charts(config, known)? {
+            if !lines_unknown.remove(chart.name()) {
+                return Err(anyhow::anyhow!(
+                    "expression chart {} has the same id as built-in chart",
+                    chart.name()
+                ));
+            }
+            charts.push(chart);
+        }
+
+        let unknown: Vec<_> = counters_unknown
+            .iter()
+            .chain(lines_unknown.iter())
+            .chain(leaderboards_unknown.iter())
+            .collect();
+        if !unknown.is_empty() {
+            return Err(anyhow::anyhow!("found unknown chart ids: {:?}", unknown));
         }
 
+        let token_charts = Self::token_charts(config)?;
+        let tokens_filter = token_charts
+            .iter()
+            .map(|chart| chart.name().to_owned())
+            .collect();
+        charts.extend(token_charts);
+
         Ok(ValidatedConfig {
             charts,
             counters_filter,
             lines_filter,
+            leaderboards_filter,
+            tokens_filter,
         })
     }
 
+    fn validate_resolutions(
+        charts: &[ArcChart],
+        settings: &HashMap<String, ChartSettings>,
+    ) -> Result<(), anyhow::Error> {
+        for chart in charts {
+            let resolutions = settings
+                .get(chart.name())
+                .map(|settings| settings.resolutions.as_slice())
+                .unwrap_or_default();
+            if let Some(unsupported) = resolutions
+                .iter()
+                .find(|resolution| !chart.resolutions().contains(resolution))
+            {
+                return Err(anyhow::anyhow!(
+                    "chart {} doesn't support {} resolution",
+                    chart.name(),
+                    unsupported
+                ));
+            }
+        }
+        Ok(())
+    }
+
     // assumes that config is valid
     fn new_settings(config: &Config) -> HashMap<String, ChartSettings> {
         config
@@ -101,12 +259,147 @@ impl Charts {
                     .iter()
                     .map(|chart| (chart.id.clone(), chart.settings.clone()))
             }))
+            .chain(
+                config
+                    .leaderboards
+                    .iter()
+                    .map(|leaderboard| (leaderboard.id.clone(), leaderboard.settings.clone())),
+            )
+            .collect()
+    }
+
+    fn sql_charts(config: &Config) -> Result<Vec<ArcChart>, anyhow::Error> {
+        let counters = config
+            .counters
+            .iter()
+            .map(|counter| (ChartType::Counter, &counter.id, &counter.settings));
+        let lines = config.lines.sections.iter().flat_map(|section| {
+            section
+                .charts
+                .iter()
+                .map(|chart| (ChartType::Line, &chart.id, &chart.settings))
+        });
+        let leaderboards = config.leaderboards.iter().map(|leaderboard| {
+            (
+                ChartType::Leaderboard,
+                &leaderboard.id,
+                &leaderboard.settings,
+            )
+        });
+        counters
+            .chain(lines)
+            .chain(leaderboards)
+            .filter_map(|(chart_type, id, settings)| {
+                settings.sql.as_ref().map(|sql| {
+                    SqlChart::new(id.clone(), chart_type, sql)
+                        .map(|chart| Arc::new(chart) as ArcChart)
+                        .map_err(anyhow::Error::from)
+                })
+            })
             .collect()
     }
 
-    fn all_charts() -> Vec<ArcChart> {
+    /// Expressions can refer to built-in, sql and other expression charts,
+    /// so they are resolved until all of them are found or no progress is made
+    fn expression_charts(
+        config: &Config,
+        mut known: Vec<ArcChart>,
+    ) -> Result<Vec<ArcChart>, anyhow::Error> {
+        if let Some(id) = config
+            .counters
+            .iter()
+            .map(|counter| (&counter.id, &counter.settings))
+            .chain(
+                config
+                    .leaderboards
+                    .iter()
+                    .map(|leaderboard| (&leaderboard.id, &leaderboard.settings)),
+            )
+            .find_map(|(id, settings)| settings.expression.is_some().then_some(id))
+        {
+            return Err(anyhow::anyhow!(
+                "chart {} can't have an expression, only line charts can",
+                id
+            ));
+        }
+        let mut pending = Vec::new();
+        for chart in config.lines.sections.iter().flat_map(|s| s.charts.iter()) {
+            if let Some(expression) = chart.settings.expression.as_ref() {
+                if chart.settings.sql.is_some() {
+                    return Err(anyhow::anyhow!(
+                        "chart {} has both sql and expression",
+                        chart.id
+                    ));
+                }
+                pending.push((chart.id.clone(), expression.as_str()));
+            }
+        }
+
+        let mut charts = Vec::new();
+        while !pending.is_empty() {
+            let pending_count = pending.len();
+            let mut last_error = None;
+            let mut unresolved = Vec::new();
+            for (id, expression) in pending {
+                let find = |name: &str| known.iter().find(|chart| chart.name() == name).cloned();
+                match ExpressionChart::new(id.clone(), expression, find) {
+                    Ok(chart) => {
+                        let chart: ArcChart = Arc::new(chart);
+                        known.push(chart.clone());
+                        charts.push(chart);
+                    }
+                    Err(err @ stats::ExpressionError::UnknownChart { .. }) => {
+                        last_error = Some(err);
+                        unresolved.push((id, expression));
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            if unresolved.len() == pending_count {
+                if let Some(err) = last_error {
+                    return Err(err.into());
+                }
+            }
+            pending = unresolved;
+        }
+        Ok(charts)
+    }
+
+    fn token_charts(config: &Config) -> Result<Vec<ArcChart>, anyhow::Error> {
+        let addresses = config
+            .tokens
+            .iter()
+            .map(|address| stats::parse_token_address(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        new_hashset_check_duplicates(addresses.iter()).map_err(|address| {
+            anyhow::anyhow!(
+                "encountered same token twice: {}",
+                TokenLine::new(TokenChartKind::Transfers, address.clone()).name()
+            )
+        })?;
+        Ok(addresses
+            .into_iter()
+            .flat_map(|address| {
+                TokenChartKind::all()
+                    .into_iter()
+                    .map(move |kind| Arc::new(TokenLine::new(kind, address.clone())) as ArcChart)
+            })
+            .collect())
+    }
+
+    fn all_charts(missing_price: MissingPrice) -> Vec<ArcChart> {
         let accounts_cache = Cache::default();
-        let new_txns = Arc::new(lines::NewTxns::default());
+        let txns_rollup = Arc::new(TxnsRollup::default());
+        let new_txns = Arc::new(lines::NewTxns::new(txns_rollup.clone()));
+        let txns_fee = Arc::new(lines::TxnsFee::new(txns_rollup.clone()));
+        let txns_volume = Arc::new(lines::TxnsVolume::new(txns_rollup.clone()));
+        let native_coin_supply = Arc::new(lines::NativeCoinSupply::default());
+        let native_coin_price = Arc::new(lines::NativeCoinPrice::default());
+        let market_cap = Arc::new(lines::MarketCap::new(
+            native_coin_supply.clone(),
+            native_coin_price.clone(),
+            missing_price,
+        ));
         let new_native_coin_transfers = Arc::new(lines::NewNativeCoinTransfers::default());
         let native_coin_holders_growth = Arc::new(lines::NativeCoinHoldersGrowth::default());
 
@@ -121,7 +414,8 @@ impl Charts {
             // tier 1
             Arc::new(lines::AverageBlockRewards::default()),
             Arc::new(counters::TotalTokens::default()),
-            Arc::new(lines::NativeCoinSupply::default()),
+            native_coin_supply,
+            native_coin_price.clone(),
             native_coin_holders_growth.clone(),
             new_txns.clone(),
             Arc::new(lines::NewAccounts::new(accounts_cache.clone())),
@@ -132,17 +426,36 @@ impl Charts {
             Arc::new(lines::GasUsedGrowth::default()),
             Arc::new(lines::AverageBlockSize::default()),
             Arc::new(counters::TotalBlocks::default()),
-            Arc::new(lines::TxnsFee::default()),
+            txns_fee.clone(),
+            txns_volume.clone(),
             Arc::new(lines::AverageGasLimit::default()),
             Arc::new(counters::AverageBlockTime::default()),
             Arc::new(lines::ActiveAccounts::default()),
-            Arc::new(lines::AverageGasPrice::default()),
-            Arc::new(lines::AverageTxnFee::default()),
-            Arc::new(lines::TxnsSuccessRate::default()),
+            Arc::new(lines::AverageGasPrice::new(txns_rollup.clone())),
+            Arc::new(lines::AverageTxnFee::new(txns_rollup.clone())),
+            Arc::new(lines::TxnsSuccessRate::new(txns_rollup)),
+            Arc::new(lines::GasPriceDistribution::default()),
+            Arc::new(lines::TxnFeeDistribution::default()),
+            Arc::new(lines::BlockTimeDistribution::default()),
             Arc::new(counters::CompletedTxns::default()),
             Arc::new(lines::AccountsGrowth::new(accounts_cache.clone())),
             Arc::new(counters::TotalAccounts::new(accounts_cache)),
+            Arc::new(leaderboards::TopAccountsByTxns::default()),
+            Arc::new(leaderboards::TopContractsByGasUsed::default()),
+            Arc::new(leaderboards::TopTokensByTransfers::default()),
             // tier 2
+            market_cap.clone(),
+            Arc::new(lines::TxnsFeeUsd::new(
+                txns_fee,
+                native_coin_price.clone(),
+                missing_price,
+            )),
+            Arc::new(lines::TxnsVolumeUsd::new(
+                txns_volume,
+                native_coin_price.clone(),
+                missing_price,
+            )),
+            Arc::new(counters::LastNativeCoinPrice::new(native_coin_price)),
             Arc::new(counters::LastNewContracts::new(new_contracts)),
             Arc::new(counters::TotalNativeCoinHolders::new(
                 native_coin_holders_growth.clone(),
@@ -159,6 +472,7 @@ impl Charts {
                 new_native_coin_transfers,
             )),
             // tier 3
+            Arc::new(counters::LastMarketCap::new(market_cap)),
             Arc::new(counters::TotalContracts::new(contracts_growth)),
             Arc::new(counters::TotalVerifiedContracts::new(
                 verified_contracts_growth,
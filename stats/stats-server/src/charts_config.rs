@@ -1,6 +1,7 @@
 use cron::Schedule;
 use serde::Deserialize;
 use serde_with::{serde_as, DisplayFromStr};
+use stats::{Resolution, Timezone, LEADERBOARD_PERIODS};
 use stats_proto::blockscout::stats::v1 as proto;
 
 #[serde_as]
@@ -13,6 +14,41 @@ pub struct ChartSettings {
     pub drop_last_point: bool,
     #[serde(default)]
     pub relevant_or_zero: bool,
+    /// Resolutions available through the api
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default = "default_resolutions")]
+    pub resolutions: Vec<Resolution>,
+    /// Query template for charts that are not built-in
+    pub sql: Option<String>,
+    /// Expression over other charts for line charts that are not built-in,
+    /// e.g. `ma7(newTxns)`
+    pub expression: Option<String>,
+    /// Timezone of dates of the chart, overrides timezone of the service
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub timezone: Option<Timezone>,
+}
+
+fn default_resolutions() -> Vec<Resolution> {
+    vec![Resolution::Day]
+}
+
+pub fn resolution_from_proto(resolution: proto::Resolution) -> Resolution {
+    match resolution {
+        proto::Resolution::Day => Resolution::Day,
+        proto::Resolution::Hour => Resolution::Hour,
+        proto::Resolution::Week => Resolution::Week,
+        proto::Resolution::Month => Resolution::Month,
+    }
+}
+
+pub fn resolution_to_proto(resolution: Resolution) -> proto::Resolution {
+    match resolution {
+        Resolution::Day => proto::Resolution::Day,
+        Resolution::Hour => proto::Resolution::Hour,
+        Resolution::Week => proto::Resolution::Week,
+        Resolution::Month => proto::Resolution::Month,
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -39,6 +75,12 @@ impl From<LineChartInfo> for proto::LineChartInfo {
             title: value.title,
             description: value.description,
             units: value.settings.units,
+            resolutions: value
+                .settings
+                .resolutions
+                .into_iter()
+                .map(|resolution| resolution_to_proto(resolution) as i32)
+                .collect(),
         }
     }
 }
@@ -73,8 +115,64 @@ impl From<LineCharts> for proto::LineCharts {
     }
 }
 
+pub fn leaderboard_period_from_proto(period: proto::LeaderboardPeriod) -> u32 {
+    match period {
+        proto::LeaderboardPeriod::OneDay => 1,
+        proto::LeaderboardPeriod::SevenDays => 7,
+        proto::LeaderboardPeriod::ThirtyDays => 30,
+    }
+}
+
+pub fn leaderboard_period_to_proto(period_days: u32) -> Option<proto::LeaderboardPeriod> {
+    match period_days {
+        1 => Some(proto::LeaderboardPeriod::OneDay),
+        7 => Some(proto::LeaderboardPeriod::SevenDays),
+        30 => Some(proto::LeaderboardPeriod::ThirtyDays),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeaderboardInfo {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub settings: ChartSettings,
+}
+
+impl From<LeaderboardInfo> for proto::LeaderboardInfo {
+    fn from(value: LeaderboardInfo) -> Self {
+        Self {
+            id: value.id,
+            title: value.title,
+            description: value.description,
+            units: value.settings.units,
+            periods: LEADERBOARD_PERIODS
+                .iter()
+                .filter_map(|period| leaderboard_period_to_proto(*period))
+                .map(|period| period as i32)
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub counters: Vec<CounterInfo>,
     pub lines: LineCharts,
+    #[serde(default)]
+    pub leaderboards: Vec<LeaderboardInfo>,
+    /// Addresses of tokens with token charts, which are updated on the default schedule
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    #[serde(default)]
+    pub token_settings: TokenChartSettings,
+}
+
+/// Settings shared by charts of all configured tokens
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TokenChartSettings {
+    #[serde(default)]
+    pub drop_last_point: bool,
 }
@@ -0,0 +1,156 @@
+use crate::{
+    chains::{ChainError, Chains},
+    charts::Charts,
+    read_service::read_counters,
+};
+use actix_web::{web, HttpResponse};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use stats::{cache::Cache, ReadError};
+use stats_proto::blockscout::stats::v1::Counter;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::watch;
+
+/// Pushes counters to subscribers whenever they are updated.
+///
+/// [`crate::UpdateService`] reports successfully updated charts with [`CountersWatch::updated`],
+/// every update of a counter increments the version of counters.
+/// Values of the version are read once and shared by all subscribers.
+pub struct CountersWatch {
+    db: Arc<DatabaseConnection>,
+    charts: Arc<Charts>,
+    counters: Cache<Vec<Counter>>,
+    version: watch::Sender<u64>,
+    /// Versions of the last updates of counters
+    updated_in: Mutex<HashMap<String, u64>>,
+}
+
+struct WatchState {
+    watch: Arc<CountersWatch>,
+    receiver: watch::Receiver<u64>,
+    pending: VecDeque<Counter>,
+    /// Version of the counters sent to the subscriber, `None` before the first send
+    sent_version: Option<u64>,
+}
+
+impl CountersWatch {
+    pub fn new(db: Arc<DatabaseConnection>, charts: Arc<Charts>) -> Self {
+        let (version, _) = watch::channel(0);
+        Self {
+            db,
+            charts,
+            counters: Cache::default(),
+            version,
+            updated_in: Default::default(),
+        }
+    }
+
+    /// Notifies subscribers if the chart is a counter
+    pub fn updated(&self, name: &str) {
+        if !self.charts.counters_filter.contains(name) {
+            return;
+        }
+        let mut updated_in = self.updated_in.lock().expect("poisoned lock");
+        let version = *self.version.borrow() + 1;
+        updated_in.insert(name.to_owned(), version);
+        self.version.send_replace(version);
+    }
+
+    /// Counters of the version, read from the database by the first subscriber asking for it
+    async fn read(&self, version: u64) -> Result<Vec<Counter>, ReadError> {
+        self.counters
+            .get_or_update_version(version, read_counters(&self.db, &self.charts))
+            .await
+    }
+
+    /// Stream of counters that starts with current values of all counters.
+    /// If several updates happen while the subscriber is busy,
+    /// every updated counter is sent once with its latest value.
+    pub fn watch(self: Arc<Self>) -> impl Stream<Item = Result<Counter, ReadError>> + Send {
+        let state = WatchState {
+            receiver: self.version.subscribe(),
+            watch: self,
+            pending: VecDeque::new(),
+            sent_version: None,
+        };
+        futures::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(counter) = state.pending.pop_front() {
+                    return Some((Ok(counter), state));
+                }
+                if state.sent_version.is_some() && state.receiver.changed().await.is_err() {
+                    return None;
+                }
+                let version = *state.receiver.borrow_and_update();
+                let sent_version = state.sent_version.replace(version);
+                let counters = match state.watch.read(version).await {
+                    Ok(counters) => counters,
+                    Err(err) => return Some((Err(err), state)),
+                };
+                let updated = {
+                    let updated_in = state.watch.updated_in.lock().expect("poisoned lock");
+                    counters
+                        .into_iter()
+                        .filter(|counter| match sent_version {
+                            Some(sent_version) => updated_in
+                                .get(&counter.id)
+                                .map_or(false, |updated_in| *updated_in > sent_version),
+                            None => true,
+                        })
+                        .collect::<Vec<_>>()
+                };
+                state.pending.extend(updated);
+            }
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchParams {
+    chain_id: Option<String>,
+}
+
+/// Server-sent events equivalent of `WatchCounters` rpc
+pub fn route_counters_watch(config: &mut web::ServiceConfig, chains: Arc<Chains>) {
+    config
+        .app_data(web::Data::from(chains))
+        .route("/api/v1/counters/watch", web::get().to(watch_counters_sse));
+}
+
+async fn watch_counters_sse(
+    chains: web::Data<Chains>,
+    params: web::Query<WatchParams>,
+) -> HttpResponse {
+    let chain = match chains.get(params.chain_id.as_deref()) {
+        Ok(chain) => chain,
+        Err(err @ ChainError::Required) => return HttpResponse::BadRequest().body(err.to_string()),
+        Err(err @ ChainError::NotFound(_)) => {
+            return HttpResponse::NotFound().body(err.to_string())
+        }
+    };
+    let events = chain
+        .counters_watch
+        .clone()
+        .watch()
+        .map(|counter| Ok::<_, actix_web::Error>(Bytes::from(sse_event(counter))));
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events)
+}
+
+fn sse_event(counter: Result<Counter, ReadError>) -> String {
+    let (event, data) = match counter {
+        Ok(counter) => (
+            "counter",
+            serde_json::to_string(&counter).expect("counter is always serializable"),
+        ),
+        Err(err) => ("error", err.to_string()),
+    };
+    format!("event: {event}\ndata: {data}\n\n")
+}
@@ -0,0 +1,124 @@
+use crate::charts::ArcChart;
+use stats::Chart;
+use std::collections::{HashMap, HashSet};
+
+/// Enabled charts together with charts they depend on.
+///
+/// A chart is updated right after all charts it depends on,
+/// once per tick of every schedule of charts it's reachable from.
+pub struct DependencyGraph {
+    /// Every chart goes after its dependencies
+    order: Vec<ArcChart>,
+    dependents: HashMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    pub fn new(charts: &[ArcChart]) -> Result<Self, anyhow::Error> {
+        let mut graph = Self {
+            order: Vec::new(),
+            dependents: HashMap::new(),
+        };
+        let mut visited = HashSet::new();
+        for chart in charts {
+            graph.visit(chart, &mut visited, &mut Vec::new())?;
+        }
+        Ok(graph)
+    }
+
+    fn visit(
+        &mut self,
+        chart: &ArcChart,
+        visited: &mut HashSet<String>,
+        path: &mut Vec<String>,
+    ) -> Result<(), anyhow::Error> {
+        let name = chart.name().to_owned();
+        if path.contains(&name) {
+            return Err(anyhow::anyhow!(
+                "found cyclic dependency of charts: {} -> {}",
+                path.join(" -> "),
+                name
+            ));
+        }
+        if !visited.insert(name.clone()) {
+            return Ok(());
+        }
+        path.push(name.clone());
+        for dependency in chart.dependencies() {
+            self.dependents
+                .entry(dependency.name().to_owned())
+                .or_default()
+                .push(name.clone());
+            self.visit(&dependency, visited, path)?;
+        }
+        path.pop();
+        self.order.push(chart.clone());
+        Ok(())
+    }
+
+    /// All charts in order of update
+    pub fn iter(&self) -> impl Iterator<Item = &ArcChart> {
+        self.order.iter()
+    }
+
+    /// Groups of charts that don't depend on charts of other groups,
+    /// every group in order of update
+    pub fn components(&self) -> Vec<Vec<ArcChart>> {
+        fn find(parents: &[usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                i = parents[i];
+            }
+            i
+        }
+
+        let mut parents: Vec<usize> = Vec::with_capacity(self.order.len());
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        for (i, chart) in self.order.iter().enumerate() {
+            parents.push(i);
+            // dependencies always go before the chart
+            for dependency in chart.dependencies() {
+                let root = find(&parents, indices[dependency.name()]);
+                parents[root] = i;
+            }
+            indices.insert(chart.name(), i);
+        }
+
+        let mut components: Vec<Vec<ArcChart>> = Vec::new();
+        let mut component_of_root: HashMap<usize, usize> = HashMap::new();
+        for (i, chart) in self.order.iter().enumerate() {
+            let root = find(&parents, i);
+            let component = *component_of_root.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[component].push(chart.clone());
+        }
+        components
+    }
+
+    /// The chart followed by all charts that depend on it, in order of update
+    pub fn with_dependents(&self, name: &str) -> Vec<ArcChart> {
+        self.with_dependents_of([name])
+    }
+
+    /// The charts followed by all charts that depend on any of them, in order of update.
+    /// Every chart is included once
+    pub fn with_dependents_of<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<ArcChart> {
+        let mut queue: Vec<String> = names.into_iter().map(str::to_owned).collect();
+        let mut reached: HashSet<String> = queue.iter().cloned().collect();
+        while let Some(name) = queue.pop() {
+            for dependent in self.dependents.get(&name).into_iter().flatten() {
+                if reached.insert(dependent.clone()) {
+                    queue.push(dependent.clone());
+                }
+            }
+        }
+        self.order
+            .iter()
+            .filter(|chart| reached.contains(chart.name()))
+            .cloned()
+            .collect()
+    }
+}
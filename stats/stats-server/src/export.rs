@@ -0,0 +1,493 @@
+use crate::chains::{Chain, ChainError, Chains};
+use actix_web::{http::header, web, HttpResponse};
+use bytes::Bytes;
+use chrono::{Duration, NaiveDate};
+use futures::{Stream, StreamExt};
+use parquet::{
+    data_type::{ByteArray, ByteArrayType, Int32Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    format::KeyValue,
+    schema::parser::parse_message_type,
+};
+use serde::Deserialize;
+use stats::{ReadError, Resolution, Timezone};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+/// Days of points read from the database and sent at once
+const PAGE_DAYS: i64 = 1000;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("chart {0} not found")]
+    NotFound(String),
+    #[error("{0}")]
+    Chain(#[from] ChainError),
+    #[error("read error: {0}")]
+    Read(#[from] ReadError),
+    #[error("parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+impl From<ExportError> for actix_web::Error {
+    fn from(err: ExportError) -> Self {
+        actix_web::error::ErrorInternalServerError(err.to_string())
+    }
+}
+
+impl ExportError {
+    fn into_response(self) -> HttpResponse {
+        match self {
+            ExportError::InvalidRequest(_) | ExportError::Chain(ChainError::Required) => {
+                HttpResponse::BadRequest().body(self.to_string())
+            }
+            ExportError::NotFound(_)
+            | ExportError::Chain(ChainError::NotFound(_))
+            | ExportError::Read(ReadError::NotFound(_)) => {
+                HttpResponse::NotFound().body(self.to_string())
+            }
+            _ => HttpResponse::InternalServerError().body(self.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// Comma-separated list of line chart ids
+    charts: String,
+    /// Default is first data point
+    from: Option<String>,
+    /// Default is last data point
+    to: Option<String>,
+    #[serde(default)]
+    format: ExportFormat,
+    /// `day` (default), `week` or `month`
+    resolution: Option<String>,
+    /// Default is the chain configured with `STATS__BLOCKSCOUT_DB_URL`
+    chain_id: Option<String>,
+}
+
+/// Line charts joined on date.
+/// Values of chart that has no point at the date are `None`
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ChartsTable {
+    names: Vec<String>,
+    rows: BTreeMap<NaiveDate, Vec<Option<String>>>,
+}
+
+struct ExportedChart {
+    name: String,
+    drop_last_point: bool,
+    timezone: Timezone,
+}
+
+/// Reads the charts page by page, so that the whole table is never kept in memory
+struct TableReader {
+    chain: Arc<Chain>,
+    charts: Vec<ExportedChart>,
+    resolution: Resolution,
+    /// First day of the next page, `None` if all pages are read
+    next: Option<NaiveDate>,
+    to: NaiveDate,
+}
+
+impl TableReader {
+    async fn new(
+        chain: Arc<Chain>,
+        names: &[String],
+        resolution: Resolution,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Self, ExportError> {
+        let mut charts = Vec::new();
+        for name in names {
+            let settings = chain
+                .charts
+                .settings
+                .get(name)
+                .filter(|_| chain.charts.lines_filter.contains(name))
+                .ok_or_else(|| ExportError::NotFound(name.clone()))?;
+            if !settings.resolutions.contains(&resolution) {
+                return Err(ExportError::InvalidRequest(format!(
+                    "chart {name} doesn't have {resolution} resolution"
+                )));
+            }
+            charts.push(ExportedChart {
+                name: name.clone(),
+                drop_last_point: settings.drop_last_point,
+                timezone: chain.charts.timezone(name),
+            });
+        }
+
+        let next = match from {
+            Some(from) => Some(from),
+            None => {
+                let mut start: Option<NaiveDate> = None;
+                for chart in charts.iter() {
+                    let chart_start =
+                        stats::get_chart_start_date(&chain.db, &chart.name, resolution).await?;
+                    start = start.into_iter().chain(chart_start).min();
+                }
+                start
+            }
+        };
+        let to = to.unwrap_or_else(|| {
+            charts
+                .iter()
+                .map(|chart| chart.timezone.today())
+                .max()
+                .unwrap_or_default()
+        });
+        Ok(Self {
+            chain,
+            charts,
+            resolution,
+            next,
+            to,
+        })
+    }
+
+    async fn next_page(&mut self) -> Result<Option<ChartsTable>, ExportError> {
+        let from = match self.next {
+            Some(from) if from <= self.to => from,
+            _ => return Ok(None),
+        };
+        let to = (from + Duration::days(PAGE_DAYS - 1)).min(self.to);
+        self.next = Some(to + Duration::days(1));
+
+        let mut table = ChartsTable {
+            names: self.charts.iter().map(|chart| chart.name.clone()).collect(),
+            rows: BTreeMap::new(),
+        };
+        for (i, chart) in self.charts.iter().enumerate() {
+            let data = stats::get_chart_data_with_resolution(
+                &self.chain.db,
+                &chart.name,
+                self.resolution,
+                Some(from),
+                Some(to),
+            )
+            .await?;
+            for point in data {
+                // only the last point can be partially updated
+                if chart.drop_last_point
+                    && self.resolution.is_partial(point.timespan, chart.timezone)
+                {
+                    continue;
+                }
+                table
+                    .rows
+                    .entry(point.timespan.date())
+                    .or_insert_with(|| vec![None; self.charts.len()])[i] = Some(point.value);
+            }
+        }
+        Ok(Some(table))
+    }
+}
+
+pub struct ChartsExport {
+    chains: Arc<Chains>,
+}
+
+impl ChartsExport {
+    pub fn new(chains: Arc<Chains>) -> Self {
+        Self { chains }
+    }
+
+    fn titles(chain: &Chain, names: &[String]) -> Vec<(String, String)> {
+        chain
+            .charts
+            .config
+            .lines
+            .sections
+            .iter()
+            .flat_map(|section| section.charts.iter())
+            .filter(|info| names.contains(&info.id))
+            .map(|info| (info.id.clone(), info.title.clone()))
+            .collect()
+    }
+}
+
+pub fn route_export(config: &mut web::ServiceConfig, export: Arc<ChartsExport>) {
+    config
+        .app_data(web::Data::from(export))
+        .route("/api/v1/export", web::get().to(export_charts));
+}
+
+async fn export_charts(
+    export: web::Data<ChartsExport>,
+    params: web::Query<ExportParams>,
+) -> HttpResponse {
+    let params = params.into_inner();
+    match export_charts_inner(&export, &params).await {
+        Ok((names, body)) => {
+            let filename = format!("{}.{}", names.join("_"), params.format.extension());
+            HttpResponse::Ok()
+                .content_type(params.format.content_type())
+                .insert_header(header::ContentDisposition::attachment(filename))
+                .streaming(body)
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+async fn export_charts_inner(
+    export: &ChartsExport,
+    params: &ExportParams,
+) -> Result<
+    (
+        Vec<String>,
+        impl Stream<Item = Result<Bytes, actix_web::Error>>,
+    ),
+    ExportError,
+> {
+    let chain = export.chains.get(params.chain_id.as_deref())?.clone();
+    let names = parse_names(&params.charts)?;
+    let from = parse_date(params.from.as_deref())?;
+    let to = parse_date(params.to.as_deref())?;
+    let resolution = parse_resolution(params.resolution.as_deref())?;
+    let writer = match params.format {
+        ExportFormat::Csv => TableWriter::Csv,
+        ExportFormat::Parquet => TableWriter::Parquet(ParquetWriter::new(
+            &names,
+            ChartsExport::titles(&chain, &names),
+        )?),
+    };
+    let reader = TableReader::new(chain, &names, resolution, from, to).await?;
+    let header = match &writer {
+        TableWriter::Csv => Some(csv_header(&names)),
+        TableWriter::Parquet(_) => None,
+    };
+    let pages = futures::stream::unfold(Some((reader, writer)), |state| async move {
+        let (mut reader, mut writer) = state?;
+        let chunk = match reader.next_page().await {
+            Ok(Some(table)) => match writer.write(&table) {
+                Ok(chunk) => return Some((Ok(chunk), Some((reader, writer)))),
+                Err(err) => Err(err),
+            },
+            Ok(None) => writer.finish(),
+            Err(err) => Err(err),
+        };
+        // the response is already started, so an error aborts it
+        Some((chunk.map_err(actix_web::Error::from), None))
+    });
+    let body = futures::stream::iter(header.map(Ok)).chain(pages);
+    Ok((names, body))
+}
+
+fn parse_names(charts: &str) -> Result<Vec<String>, ExportError> {
+    let mut names: Vec<String> = Vec::new();
+    for name in charts
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(ExportError::InvalidRequest(format!(
+                "invalid chart id '{name}'"
+            )));
+        }
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_owned());
+        }
+    }
+    if names.is_empty() {
+        return Err(ExportError::InvalidRequest("no charts requested".into()));
+    }
+    Ok(names)
+}
+
+fn parse_date(date: Option<&str>) -> Result<Option<NaiveDate>, ExportError> {
+    date.map(|date| {
+        NaiveDate::from_str(date)
+            .map_err(|_| ExportError::InvalidRequest(format!("invalid date '{date}'")))
+    })
+    .transpose()
+}
+
+/// Points are identified by dates in exported tables,
+/// so hourly points cannot be exported
+fn parse_resolution(resolution: Option<&str>) -> Result<Resolution, ExportError> {
+    let resolution = resolution
+        .map(Resolution::from_str)
+        .transpose()
+        .map_err(|err| ExportError::InvalidRequest(err.to_string()))?
+        .unwrap_or_default();
+    if resolution == Resolution::Hour {
+        return Err(ExportError::InvalidRequest(
+            "hour resolution cannot be exported".into(),
+        ));
+    }
+    Ok(resolution)
+}
+
+fn csv_header(names: &[String]) -> Bytes {
+    let header = std::iter::once("date".to_owned())
+        .chain(names.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(",");
+    Bytes::from(header + "\n")
+}
+
+/// Converts pages of the table into chunks of the response body
+enum TableWriter {
+    Csv,
+    Parquet(ParquetWriter),
+}
+
+impl TableWriter {
+    fn write(&mut self, table: &ChartsTable) -> Result<Bytes, ExportError> {
+        match self {
+            TableWriter::Csv => Ok(to_csv(table)),
+            TableWriter::Parquet(writer) => writer.write(table),
+        }
+    }
+
+    fn finish(self) -> Result<Bytes, ExportError> {
+        match self {
+            TableWriter::Csv => Ok(Bytes::new()),
+            TableWriter::Parquet(writer) => writer.finish(),
+        }
+    }
+}
+
+fn to_csv(table: &ChartsTable) -> Bytes {
+    let mut chunk = String::new();
+    for (date, values) in table.rows.iter() {
+        chunk.push_str(&date.to_string());
+        for value in values.iter() {
+            chunk.push(',');
+            if let Some(value) = value {
+                chunk.push_str(value);
+            }
+        }
+        chunk.push('\n');
+    }
+    Bytes::from(chunk)
+}
+
+/// Buffer of the parquet file that is drained after every written row group
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Bytes {
+        Bytes::from(std::mem::take(&mut *self.0.lock().expect("poisoned lock")))
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().expect("poisoned lock").write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes every page of the table as a separate row group
+struct ParquetWriter {
+    writer: SerializedFileWriter<SharedBuffer>,
+    buffer: SharedBuffer,
+}
+
+impl ParquetWriter {
+    fn new(names: &[String], titles: Vec<(String, String)>) -> Result<Self, ExportError> {
+        let columns: String = names
+            .iter()
+            .map(|name| format!("OPTIONAL BYTE_ARRAY {name} (UTF8); "))
+            .collect();
+        let schema = parse_message_type(&format!(
+            "message chart_export {{ REQUIRED INT32 date (DATE); {columns}}}"
+        ))?;
+        let metadata = titles
+            .into_iter()
+            .map(|(id, title)| KeyValue::new(format!("title.{id}"), title))
+            .collect();
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(metadata))
+            .build();
+        let buffer = SharedBuffer::default();
+        let writer = SerializedFileWriter::new(buffer.clone(), Arc::new(schema), Arc::new(props))?;
+        Ok(Self { writer, buffer })
+    }
+
+    fn write(&mut self, table: &ChartsTable) -> Result<Bytes, ExportError> {
+        if table.rows.is_empty() {
+            return Ok(self.buffer.take());
+        }
+        let mut row_group = self.writer.next_row_group()?;
+
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("epoch is valid date");
+        let dates: Vec<i32> = table
+            .rows
+            .keys()
+            .map(|date| (*date - epoch).num_days() as i32)
+            .collect();
+        let mut column = row_group
+            .next_column()?
+            .expect("schema contains date column");
+        column
+            .typed::<Int32Type>()
+            .write_batch(&dates, None, None)?;
+        column.close()?;
+
+        for i in 0..table.names.len() {
+            let (values, def_levels): (Vec<_>, Vec<_>) = table
+                .rows
+                .values()
+                .map(|row| match &row[i] {
+                    Some(value) => (Some(ByteArray::from(value.as_str())), 1),
+                    None => (None, 0),
+                })
+                .unzip();
+            let values: Vec<_> = values.into_iter().flatten().collect();
+            let mut column = row_group
+                .next_column()?
+                .expect("schema contains column for every chart");
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(&values, Some(&def_levels), None)?;
+            column.close()?;
+        }
+        row_group.close()?;
+        Ok(self.buffer.take())
+    }
+
+    fn finish(mut self) -> Result<Bytes, ExportError> {
+        self.writer.close()?;
+        Ok(self.buffer.take())
+    }
+}
@@ -1,13 +1,27 @@
+mod admin_service;
+mod alerts;
+mod alerts_config;
+mod audit;
+mod chains;
 mod charts;
 mod charts_config;
+mod counters_watch;
+mod dependency_graph;
+mod export;
 mod health;
+mod line_chart_http;
+mod read_cache;
 mod read_service;
 mod server;
 mod settings;
 mod update_service;
 
+pub use admin_service::AdminService;
+pub use audit::{audit, AuditArgs, AuditReport, ChartAudit};
+pub use chains::{Chain, Chains};
 pub use charts::Charts;
+pub use counters_watch::CountersWatch;
 pub use read_service::ReadService;
 pub use server::stats;
-pub use settings::Settings;
+pub use settings::{ChainSettings, Settings};
 pub use update_service::UpdateService;
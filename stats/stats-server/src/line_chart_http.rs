@@ -0,0 +1,125 @@
+use crate::{
+    chains::Chains,
+    charts_config::resolution_from_proto,
+    read_cache::CachedLineChart,
+    read_service::{map_chain_error, read_line_chart},
+};
+use actix_web::{
+    http::header::{
+        self, CacheControl, CacheDirective, EntityTag, Header, HttpDate, IfModifiedSince,
+        IfNoneMatch,
+    },
+    web, HttpRequest, HttpResponse,
+};
+use serde::Deserialize;
+use stats::Resolution;
+use stats_proto::blockscout::stats::v1 as proto;
+use std::{str::FromStr, sync::Arc, time::SystemTime};
+use tonic::{Code, Status};
+
+#[derive(Debug, Deserialize)]
+struct LineChartParams {
+    from: Option<String>,
+    to: Option<String>,
+    resolution: Option<String>,
+    chain_id: Option<String>,
+}
+
+/// `GetLineChart` with `ETag` and `Last-Modified` headers.
+/// Responds with `304 Not Modified` to conditional requests if the chart was not changed
+pub fn route_line_chart(config: &mut web::ServiceConfig, chains: Arc<Chains>) {
+    config
+        .app_data(web::Data::from(chains))
+        .route("/api/v1/lines/{name}", web::get().to(get_line_chart));
+}
+
+/// Accepts names of proto enum (`DAY`) as well as their numbers
+fn parse_resolution(resolution: &str) -> Option<Resolution> {
+    Resolution::from_str(resolution).ok().or_else(|| {
+        i32::from_str(resolution)
+            .ok()
+            .and_then(proto::Resolution::from_i32)
+            .map(resolution_from_proto)
+    })
+}
+
+fn status_response(status: Status) -> HttpResponse {
+    let mut response = match status.code() {
+        Code::InvalidArgument => HttpResponse::BadRequest(),
+        Code::NotFound => HttpResponse::NotFound(),
+        _ => HttpResponse::InternalServerError(),
+    };
+    response.body(status.message().to_owned())
+}
+
+/// `If-None-Match` takes precedence over `If-Modified-Since`
+fn is_not_modified(request: &HttpRequest, chart: &CachedLineChart, etag: &EntityTag) -> bool {
+    if request.headers().contains_key(header::IF_NONE_MATCH) {
+        return match IfNoneMatch::parse(request) {
+            Ok(IfNoneMatch::Any) => true,
+            Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+            Err(_) => false,
+        };
+    }
+    match IfModifiedSince::parse(request) {
+        Ok(IfModifiedSince(since)) => {
+            SystemTime::from(since) >= SystemTime::from(chart.last_modified)
+        }
+        Err(_) => false,
+    }
+}
+
+async fn get_line_chart(
+    chains: web::Data<Chains>,
+    name: web::Path<String>,
+    params: web::Query<LineChartParams>,
+    request: HttpRequest,
+) -> HttpResponse {
+    let chain = match chains.get(params.chain_id.as_deref()) {
+        Ok(chain) => chain,
+        Err(err) => return status_response(map_chain_error(err)),
+    };
+    let resolution = match params.resolution.as_deref() {
+        Some(resolution) => match parse_resolution(resolution) {
+            Some(resolution) => resolution,
+            None => {
+                return HttpResponse::BadRequest().body(format!("invalid resolution {resolution}"))
+            }
+        },
+        None => Resolution::Day,
+    };
+    let chart = match read_line_chart(
+        chain,
+        &name,
+        params.from.as_deref(),
+        params.to.as_deref(),
+        resolution,
+    )
+    .await
+    {
+        Ok(chart) => chart,
+        Err(status) => return status_response(status),
+    };
+
+    let etag = EntityTag::new_strong(chart.etag.clone());
+    let not_modified = is_not_modified(&request, &chart, &etag);
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    response
+        .insert_header(header::ETag(etag))
+        .insert_header(header::LastModified(HttpDate::from(SystemTime::from(
+            chart.last_modified,
+        ))))
+        // responses may be stored, but have to be revalidated
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]));
+    if not_modified {
+        response.finish()
+    } else {
+        response
+            .content_type("application/json")
+            .body(chart.json.clone())
+    }
+}
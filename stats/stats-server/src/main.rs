@@ -1,4 +1,4 @@
-use stats_server::{stats, Settings};
+use stats_server::{audit, stats, AuditArgs, Settings};
 use tracing::log;
 
 fn log_error(err: anyhow::Error) -> anyhow::Error {
@@ -9,5 +9,18 @@ fn log_error(err: anyhow::Error) -> anyhow::Error {
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let settings = Settings::new().map_err(log_error)?;
-    stats(settings).await.map_err(log_error)
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        None => stats(settings).await.map_err(log_error),
+        Some("audit") => {
+            let args = AuditArgs::parse(args)?;
+            let report = audit(settings, args).await?;
+            print!("{report}");
+            if !report.is_consistent() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Some(command) => Err(anyhow::anyhow!("unknown command {}", command)),
+    }
 }
@@ -0,0 +1,162 @@
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Timelike, Utc};
+use stats::Resolution;
+use stats_proto::blockscout::stats::v1::LineChart;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineChartKey {
+    pub name: String,
+    pub resolution: Resolution,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Start of the current hour of the chart timezone.
+    /// Last points of unfinished periods are dropped depending on the current time
+    pub hour: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct CachedLineChart {
+    pub chart: LineChart,
+    /// Serialized `chart`, body of http responses
+    pub json: Bytes,
+    pub etag: String,
+    /// Time of the last update of the chart,
+    /// or start of the service if the chart was not updated since then
+    pub last_modified: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct ChartVersion {
+    version: u64,
+    modified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct CacheData {
+    entries: HashMap<LineChartKey, Arc<CachedLineChart>>,
+    versions: HashMap<String, ChartVersion>,
+    /// Invalidation of all charts
+    all: ChartVersion,
+}
+
+impl CacheData {
+    fn version(&self, name: &str) -> u64 {
+        let chart = self
+            .versions
+            .get(name)
+            .map(|version| version.version)
+            .unwrap_or_default();
+        chart + self.all.version
+    }
+
+    fn modified_at(&self, name: &str) -> Option<DateTime<Utc>> {
+        let chart = self
+            .versions
+            .get(name)
+            .and_then(|version| version.modified_at);
+        chart.max(self.all.modified_at)
+    }
+}
+
+impl ChartVersion {
+    fn increase(&mut self) {
+        self.version += 1;
+        self.modified_at = Some(truncate_to_seconds(Utc::now()));
+    }
+}
+
+/// Responses of `GetLineChart` kept until the chart is updated.
+///
+/// [`crate::UpdateService`] invalidates entries of a chart after every update of it.
+/// Every chart has a version that is increased on invalidation, so data read
+/// before the update is not cached after it.
+#[derive(Debug)]
+pub struct ReadCache {
+    capacity: usize,
+    started_at: DateTime<Utc>,
+    data: Mutex<CacheData>,
+}
+
+fn truncate_to_seconds(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.with_nanosecond(0)
+        .expect("zero nanoseconds are always valid")
+}
+
+impl ReadCache {
+    /// Cache with zero capacity doesn't keep any entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            started_at: truncate_to_seconds(Utc::now()),
+            data: Default::default(),
+        }
+    }
+
+    pub fn get(&self, key: &LineChartKey) -> Option<Arc<CachedLineChart>> {
+        self.data
+            .lock()
+            .expect("poisoned lock")
+            .entries
+            .get(key)
+            .cloned()
+    }
+
+    /// Version of the chart, has to be taken before reading the chart data
+    pub fn version(&self, name: &str) -> u64 {
+        self.data.lock().expect("poisoned lock").version(name)
+    }
+
+    /// Saves the chart read at `version`.
+    /// The chart is not saved if it was invalidated since then
+    pub fn insert(
+        &self,
+        key: LineChartKey,
+        version: u64,
+        chart: LineChart,
+    ) -> Arc<CachedLineChart> {
+        let json = serde_json::to_vec(&chart).expect("line chart is always serializable");
+        let mut hasher = DefaultHasher::new();
+        json.hash(&mut hasher);
+        let etag = format!("{:016x}", hasher.finish());
+
+        let mut data = self.data.lock().expect("poisoned lock");
+        let cached = Arc::new(CachedLineChart {
+            chart,
+            json: json.into(),
+            etag,
+            last_modified: data.modified_at(&key.name).unwrap_or(self.started_at),
+        });
+        if data.version(&key.name) != version || self.capacity == 0 {
+            return cached;
+        }
+        if data.entries.len() >= self.capacity {
+            // entries of previous hours are never requested again
+            data.entries
+                .retain(|cached_key, _| cached_key.hour >= key.hour);
+        }
+        if data.entries.len() >= self.capacity {
+            data.entries.clear();
+        }
+        data.entries.insert(key, cached.clone());
+        cached
+    }
+
+    /// Removes cached responses of the chart
+    pub fn invalidate(&self, name: &str) {
+        let mut data = self.data.lock().expect("poisoned lock");
+        data.entries.retain(|key, _| key.name != name);
+        data.versions.entry(name.to_owned()).or_default().increase();
+    }
+
+    /// Removes cached responses of all charts
+    pub fn invalidate_all(&self) {
+        let mut data = self.data.lock().expect("poisoned lock");
+        data.entries.clear();
+        data.all.increase();
+    }
+}
@@ -1,25 +1,45 @@
 use async_trait::async_trait;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate};
+use futures::{Stream, StreamExt};
 use sea_orm::{DatabaseConnection, DbErr};
-use stats::ReadError;
+use stats::{Chart, ReadError, Resolution, Timezone, TokenChartKind, TokenLine};
 use stats_proto::blockscout::stats::v1::{
-    stats_service_server::StatsService, Counter, Counters, GetCountersRequest, GetLineChartRequest,
-    GetLineChartsRequest, LineChart, LineCharts, Point,
+    stats_service_server::StatsService, Counter, Counters, GetCounterHistoryRequest,
+    GetCountersRequest, GetLeaderboardRequest, GetLeaderboardsRequest, GetLineChartRequest,
+    GetLineChartsRequest, GetTokenLineChartRequest, Leaderboard, LeaderboardEntry, Leaderboards,
+    LineChart, LineCharts, Point, SeriesValue, WatchCountersRequest,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, pin::Pin, str::FromStr, sync::Arc};
 use tonic::{Request, Response, Status};
 
-use crate::charts::Charts;
+use crate::{
+    chains::{Chain, ChainError, Chains},
+    charts::Charts,
+    charts_config::{leaderboard_period_from_proto, resolution_from_proto},
+    read_cache::{CachedLineChart, LineChartKey},
+};
+
+const DEFAULT_LEADERBOARD_LIMIT: u32 = 10;
 
 #[derive(Clone)]
 pub struct ReadService {
-    db: Arc<DatabaseConnection>,
-    charts: Arc<Charts>,
+    chains: Arc<Chains>,
 }
 
 impl ReadService {
-    pub async fn new(db: Arc<DatabaseConnection>, charts: Arc<Charts>) -> Result<Self, DbErr> {
-        Ok(Self { db, charts })
+    pub async fn new(chains: Arc<Chains>) -> Result<Self, DbErr> {
+        Ok(Self { chains })
+    }
+
+    fn chain(&self, chain_id: Option<&str>) -> Result<&Arc<Chain>, Status> {
+        self.chains.get(chain_id).map_err(map_chain_error)
+    }
+}
+
+pub fn map_chain_error(err: ChainError) -> Status {
+    match &err {
+        ChainError::Required => tonic::Status::invalid_argument(err.to_string()),
+        ChainError::NotFound(_) => tonic::Status::not_found(err.to_string()),
     }
 }
 
@@ -30,79 +50,230 @@ fn map_read_error(err: ReadError) -> Status {
     }
 }
 
+/// Parses date of the chart timezone.
+/// Timestamps with offset (RFC 3339) are converted to the date of the timezone
+fn parse_date(date: &str, timezone: Timezone) -> Option<NaiveDate> {
+    NaiveDate::from_str(date).ok().or_else(|| {
+        DateTime::parse_from_rfc3339(date)
+            .ok()
+            .map(|time| timezone.local_time(time.naive_utc()).date())
+    })
+}
+
+fn format_address(address: &[u8]) -> String {
+    let hex: String = address.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{hex}")
+}
+
+pub async fn read_counters(
+    db: &DatabaseConnection,
+    charts: &Charts,
+) -> Result<Vec<Counter>, ReadError> {
+    let mut data = stats::get_counters(db).await?;
+    let counters = charts
+        .config
+        .counters
+        .iter()
+        .filter_map(|info| {
+            data.remove(&info.id).map(|point| {
+                let point = if info.settings.relevant_or_zero {
+                    point.relevant_or_zero(charts.timezone(&info.id))
+                } else {
+                    point
+                };
+                Counter {
+                    id: info.id.clone(),
+                    value: point.value,
+                    title: info.title.clone(),
+                    units: info.settings.units.clone(),
+                }
+            })
+        })
+        .collect();
+    Ok(counters)
+}
+
+/// Named series of the chart grouped by date in order of [`stats::Chart::series`]
+async fn read_series(
+    db: &DatabaseConnection,
+    name: &str,
+    series: &[&str],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<HashMap<NaiveDate, Vec<SeriesValue>>, ReadError> {
+    let mut result: HashMap<NaiveDate, Vec<SeriesValue>> = HashMap::new();
+    if series.is_empty() {
+        return Ok(result);
+    }
+    let mut data = stats::get_chart_series_data(db, name, from, to).await?;
+    data.sort_by_key(|point| {
+        (
+            point.date,
+            series.iter().position(|name| *name == point.series),
+        )
+    });
+    for point in data {
+        result.entry(point.date).or_default().push(SeriesValue {
+            name: point.series,
+            value: point.value,
+        });
+    }
+    Ok(result)
+}
+
+/// Reads the line chart from the cache of the chain.
+/// The chart is read from the database and cached if it is not cached yet
+pub async fn read_line_chart(
+    chain: &Chain,
+    name: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    resolution: Resolution,
+) -> Result<Arc<CachedLineChart>, Status> {
+    if !chain.charts.lines_filter.contains(name) {
+        return Err(tonic::Status::not_found(format!("chart {name} not found")));
+    }
+    let settings = chain
+        .charts
+        .settings
+        .get(name)
+        .ok_or_else(|| tonic::Status::not_found(format!("chart {name} not found")))?;
+    if !settings.resolutions.contains(&resolution) {
+        return Err(tonic::Status::not_found(format!(
+            "chart {name} doesn't have {resolution} resolution"
+        )));
+    }
+    read_chart_points(chain, name, from, to, resolution, settings.drop_last_point).await
+}
+
+/// Reads points of the chart through the cache of the chain,
+/// the chart must be checked to be enabled with the resolution
+async fn read_chart_points(
+    chain: &Chain,
+    name: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    resolution: Resolution,
+    drop_last_point: bool,
+) -> Result<Arc<CachedLineChart>, Status> {
+    let timezone = chain.charts.timezone(name);
+    let from = from.and_then(|date| parse_date(date, timezone));
+    let to = to.and_then(|date| parse_date(date, timezone));
+    let key = LineChartKey {
+        name: name.to_owned(),
+        resolution,
+        from,
+        to,
+        hour: Resolution::Hour.period_start(timezone.now()),
+    };
+    if let Some(cached) = chain.read_cache.get(&key) {
+        return Ok(cached);
+    }
+    let version = chain.read_cache.version(name);
+
+    let mut data = stats::get_chart_data_with_resolution(&chain.db, name, resolution, from, to)
+        .await
+        .map_err(map_read_error)?;
+
+    if drop_last_point {
+        // remove last data point, because it can be partially updated
+        if let Some(last) = data.last() {
+            if resolution.is_partial(last.timespan, timezone) {
+                data.pop();
+            }
+        }
+    }
+
+    let series: &[&str] = match resolution {
+        Resolution::Day => chain
+            .charts
+            .charts
+            .iter()
+            .find(|chart| chart.name() == name)
+            .map(|chart| chart.series())
+            .unwrap_or_default(),
+        _ => &[],
+    };
+    let mut series = read_series(&chain.db, name, series, from, to)
+        .await
+        .map_err(map_read_error)?;
+
+    let serialized_chart: Vec<_> = data
+        .into_iter()
+        .map(|point| Point {
+            date: resolution.format_timespan(point.timespan),
+            value: point.value,
+            series: series.remove(&point.timespan.date()).unwrap_or_default(),
+        })
+        .collect();
+    let chart = LineChart {
+        chart: serialized_chart,
+    };
+    Ok(chain.read_cache.insert(key, version, chart))
+}
+
 #[async_trait]
 impl StatsService for ReadService {
+    type WatchCountersStream = Pin<Box<dyn Stream<Item = Result<Counter, Status>> + Send>>;
+
     async fn get_counters(
         &self,
-        _request: Request<GetCountersRequest>,
+        request: Request<GetCountersRequest>,
     ) -> Result<Response<Counters>, Status> {
-        let mut data = stats::get_counters(&self.db)
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        let counters = read_counters(&chain.db, &chain.charts)
             .await
             .map_err(map_read_error)?;
-
-        let counters = self
-            .charts
-            .config
-            .counters
-            .iter()
-            .filter_map(|info| {
-                data.remove(&info.id).map(|point| {
-                    let point = if info.settings.relevant_or_zero {
-                        point.relevant_or_zero()
-                    } else {
-                        point
-                    };
-                    Counter {
-                        id: info.id.clone(),
-                        value: point.value,
-                        title: info.title.clone(),
-                        units: info.settings.units.clone(),
-                    }
-                })
-            })
-            .collect();
         let counters = Counters { counters };
         Ok(Response::new(counters))
     }
 
-    async fn get_line_chart(
+    async fn watch_counters(
         &self,
-        request: Request<GetLineChartRequest>,
+        request: Request<WatchCountersRequest>,
+    ) -> Result<Response<Self::WatchCountersStream>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        let stream = chain
+            .counters_watch
+            .clone()
+            .watch()
+            .map(|counter| counter.map_err(map_read_error));
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn get_counter_history(
+        &self,
+        request: Request<GetCounterHistoryRequest>,
     ) -> Result<Response<LineChart>, Status> {
         let request = request.into_inner();
-        if !self.charts.lines_filter.contains(&request.name) {
+        let chain = self.chain(request.chain_id.as_deref())?;
+        if !chain.charts.counters_filter.contains(&request.name) {
             return Err(tonic::Status::not_found(format!(
-                "chart {} not found",
+                "counter {} not found",
                 request.name
             )));
         }
-        let settings =
-            self.charts.settings.get(&request.name).ok_or_else(|| {
-                tonic::Status::not_found(format!("chart {} not found", request.name))
-            })?;
-
-        let from = request
-            .from
-            .and_then(|date| NaiveDate::from_str(&date).ok());
-        let to = request.to.and_then(|date| NaiveDate::from_str(&date).ok());
-        let mut data = stats::get_chart_data(&self.db, &request.name, from, to)
-            .await
-            .map_err(map_read_error)?;
-
-        if settings.drop_last_point {
-            // remove last data point, because it can be partially updated
-            if let Some(last) = data.last() {
-                if last.is_partial() {
-                    data.pop();
-                }
-            }
+        let resolution = resolution_from_proto(request.resolution());
+        if !matches!(resolution, Resolution::Day | Resolution::Hour) {
+            return Err(tonic::Status::invalid_argument(format!(
+                "counter history doesn't have {resolution} resolution"
+            )));
         }
 
+        let timezone = chain.charts.timezone(&request.name);
+        let from = request.from.and_then(|date| parse_date(&date, timezone));
+        let to = request.to.and_then(|date| parse_date(&date, timezone));
+        let data =
+            stats::get_chart_data_with_resolution(&chain.db, &request.name, resolution, from, to)
+                .await
+                .map_err(map_read_error)?;
+
         let serialized_chart: Vec<_> = data
             .into_iter()
             .map(|point| Point {
-                date: point.date.to_string(),
+                date: resolution.format_timespan(point.timespan),
                 value: point.value,
+                series: vec![],
             })
             .collect();
         Ok(Response::new(LineChart {
@@ -110,10 +281,129 @@ impl StatsService for ReadService {
         }))
     }
 
+    async fn get_line_chart(
+        &self,
+        request: Request<GetLineChartRequest>,
+    ) -> Result<Response<LineChart>, Status> {
+        let request = request.into_inner();
+        let chain = self.chain(request.chain_id.as_deref())?;
+        let resolution = resolution_from_proto(request.resolution());
+        let chart = read_line_chart(
+            chain,
+            &request.name,
+            request.from.as_deref(),
+            request.to.as_deref(),
+            resolution,
+        )
+        .await?;
+        Ok(Response::new(chart.chart.clone()))
+    }
+
     async fn get_line_charts(
         &self,
-        _request: tonic::Request<GetLineChartsRequest>,
+        request: tonic::Request<GetLineChartsRequest>,
     ) -> Result<tonic::Response<LineCharts>, tonic::Status> {
-        Ok(Response::new(self.charts.config.lines.clone().into()))
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        Ok(Response::new(chain.charts.config.lines.clone().into()))
+    }
+
+    async fn get_token_line_chart(
+        &self,
+        request: Request<GetTokenLineChartRequest>,
+    ) -> Result<Response<LineChart>, Status> {
+        let request = request.into_inner();
+        let chain = self.chain(request.chain_id.as_deref())?;
+        let kind = TokenChartKind::from_str(&request.name)
+            .map_err(|err| tonic::Status::not_found(err.to_string()))?;
+        let address = stats::parse_token_address(&request.address)
+            .map_err(|err| tonic::Status::invalid_argument(err.to_string()))?;
+        // charts are updated by the scheduler only for configured tokens,
+        // so that requests cannot trigger scans of blockscout
+        let chart = TokenLine::new(kind, address);
+        if !chain.charts.tokens_filter.contains(chart.name()) {
+            return Err(tonic::Status::not_found(format!(
+                "charts of token {} are not enabled",
+                request.address
+            )));
+        }
+
+        let chart = read_chart_points(
+            chain,
+            chart.name(),
+            request.from.as_deref(),
+            request.to.as_deref(),
+            Resolution::Day,
+            chain.charts.config.token_settings.drop_last_point,
+        )
+        .await?;
+        Ok(Response::new(chart.chart.clone()))
+    }
+
+    async fn get_leaderboards(
+        &self,
+        request: Request<GetLeaderboardsRequest>,
+    ) -> Result<Response<Leaderboards>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        let leaderboards = chain
+            .charts
+            .config
+            .leaderboards
+            .iter()
+            .cloned()
+            .map(|info| info.into())
+            .collect();
+        Ok(Response::new(Leaderboards { leaderboards }))
+    }
+
+    async fn get_leaderboard(
+        &self,
+        request: Request<GetLeaderboardRequest>,
+    ) -> Result<Response<Leaderboard>, Status> {
+        let request = request.into_inner();
+        let chain = self.chain(request.chain_id.as_deref())?;
+        if !chain.charts.leaderboards_filter.contains(&request.name) {
+            return Err(tonic::Status::not_found(format!(
+                "leaderboard {} not found",
+                request.name
+            )));
+        }
+        let period_days = leaderboard_period_from_proto(request.period());
+        let timezone = chain.charts.timezone(&request.name);
+        let date =
+            match request.date.as_deref() {
+                Some(date) => Some(parse_date(date, timezone).ok_or_else(|| {
+                    tonic::Status::invalid_argument(format!("invalid date {date}"))
+                })?),
+                None => None,
+            };
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_LEADERBOARD_LIMIT)
+            .min(stats::LEADERBOARD_SIZE);
+
+        let leaderboard =
+            stats::get_leaderboard(&chain.db, &request.name, period_days, date, limit)
+                .await
+                .map_err(map_read_error)?;
+        let leaderboard = match leaderboard {
+            Some(leaderboard) => Leaderboard {
+                date: Some(leaderboard.date.to_string()),
+                entries: leaderboard
+                    .entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, entry)| LeaderboardEntry {
+                        rank: i as u32 + 1,
+                        address: format_address(&entry.address),
+                        value: entry.value,
+                    })
+                    .collect(),
+            },
+            None => Leaderboard {
+                date: None,
+                entries: vec![],
+            },
+        };
+        Ok(Response::new(leaderboard))
     }
 }
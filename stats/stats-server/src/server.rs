@@ -1,12 +1,26 @@
 use crate::{
-    charts::Charts, charts_config, health::HealthService, read_service::ReadService,
-    settings::Settings, update_service::UpdateService,
+    admin_service::AdminService,
+    alerts::Alerts,
+    alerts_config,
+    chains::{Chain, Chains},
+    charts::Charts,
+    charts_config,
+    counters_watch::route_counters_watch,
+    export::{route_export, ChartsExport},
+    health::HealthService,
+    line_chart_http::route_line_chart,
+    read_cache::ReadCache,
+    read_service::ReadService,
+    settings::{ChainSettings, Settings},
+    update_service::UpdateService,
 };
 use blockscout_service_launcher::LaunchSettings;
 use sea_orm::{ConnectOptions, Database};
 use stats_proto::blockscout::stats::v1::{
     health_actix::route_health,
     health_server::HealthServer,
+    stats_admin_service_actix::route_stats_admin_service,
+    stats_admin_service_server::StatsAdminServiceServer,
     stats_service_actix::route_stats_service,
     stats_service_server::{StatsService, StatsServiceServer},
 };
@@ -18,32 +32,59 @@ const SERVICE_NAME: &str = "stats";
 struct HttpRouter<S: StatsService> {
     stats: Arc<S>,
     health: Arc<HealthService>,
+    chains: Arc<Chains>,
+    export: Arc<ChartsExport>,
+    admin: Option<Arc<AdminService>>,
 }
 
 impl<S: StatsService> blockscout_service_launcher::HttpRouter for HttpRouter<S> {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
         service_config
             .configure(|config| route_health(config, self.health.clone()))
+            .configure(|config| route_counters_watch(config, self.chains.clone()))
+            .configure(|config| route_export(config, self.export.clone()))
+            // serves `GetLineChart` with conditional requests support,
+            // so it has to be registered before the generated route
+            .configure(|config| route_line_chart(config, self.chains.clone()))
             .configure(|config| route_stats_service(config, self.stats.clone()));
+        if let Some(admin) = &self.admin {
+            service_config.configure(|config| route_stats_admin_service(config, admin.clone()));
+        }
     }
 }
 
 fn grpc_router<S: StatsService>(
     stats: Arc<S>,
     health: Arc<HealthService>,
+    admin: Option<Arc<AdminService>>,
 ) -> tonic::transport::server::Router {
     tonic::transport::Server::builder()
         .add_service(HealthServer::from_arc(health))
         .add_service(StatsServiceServer::from_arc(stats))
+        .add_optional_service(admin.map(StatsAdminServiceServer::from_arc))
 }
 
-pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
-    blockscout_service_launcher::init_logs(SERVICE_NAME, &settings.tracing, &settings.jaeger)?;
-
-    let charts_config = std::fs::read(settings.charts_config)?;
+pub fn read_charts(
+    chain_settings: &ChainSettings,
+    settings: &Settings,
+) -> Result<Charts, anyhow::Error> {
+    let charts_config_path = chain_settings
+        .charts_config
+        .as_ref()
+        .unwrap_or(&settings.charts_config);
+    let charts_config = std::fs::read(charts_config_path)?;
     let charts_config: charts_config::Config = toml::from_slice(&charts_config)?;
+    Charts::new(charts_config, settings.timezone, settings.missing_price)
+}
+
+async fn init_chain(
+    id: String,
+    chain_settings: ChainSettings,
+    settings: &Settings,
+) -> Result<Chain, anyhow::Error> {
+    let charts = Arc::new(read_charts(&chain_settings, settings)?);
 
-    let mut opt = ConnectOptions::new(settings.db_url.clone());
+    let mut opt = ConnectOptions::new(chain_settings.db_url.clone());
     opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
     blockscout_service_launcher::database::initialize_postgres::<stats::migration::Migrator>(
         opt.clone(),
@@ -53,19 +94,71 @@ pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
     .await?;
     let db = Arc::new(Database::connect(opt).await?);
 
-    let mut opt = ConnectOptions::new(settings.blockscout_db_url.clone());
+    let mut opt = ConnectOptions::new(chain_settings.blockscout_db_url.clone());
     opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
     let blockscout = Arc::new(Database::connect(opt).await?);
 
-    let charts = Arc::new(Charts::new(charts_config)?);
-
     // TODO: may be run this with migrations or have special config
     for chart in charts.charts.iter() {
         chart.create(&db).await?;
     }
+    for chart in charts.graph.iter().filter(|chart| chart.uses_timezone()) {
+        let timezone = charts.timezone(chart.name());
+        if let Some(previous) =
+            stats::reorg::save_chart_timezone(&db, chart.name(), timezone).await?
+        {
+            tracing::warn!(
+                chain_id = %id,
+                chart = chart.name(),
+                previous = %previous,
+                timezone = %timezone,
+                "timezone of chart was changed, the chart will be recalculated"
+            );
+        }
+    }
+
+    let alerts_config_path = chain_settings
+        .alerts_config
+        .as_ref()
+        .or(settings.alerts_config.as_ref());
+    let alerts = match alerts_config_path {
+        Some(path) => {
+            let alerts_config = std::fs::read(path)?;
+            let alerts_config: alerts_config::Config = toml::from_slice(&alerts_config)?;
+            Alerts::new(alerts_config, &charts)?
+        }
+        None => Alerts::default(),
+    };
+
+    tracing::info!(chain_id = %id, "chain is initialized");
+    let read_cache = ReadCache::new(settings.read_cache_size);
+    Ok(Chain::new(id, db, blockscout, charts, alerts, read_cache))
+}
+
+pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
+    blockscout_service_launcher::init_logs(SERVICE_NAME, &settings.tracing, &settings.jaeger)?;
+
+    let mut chains = Vec::new();
+    for (id, chain_settings) in Chains::settings(settings.default_chain(), &settings.chains)? {
+        chains.push(init_chain(id, chain_settings, &settings).await?);
+    }
+    let chains = Arc::new(Chains::new(chains));
+
+    let update_service = Arc::new(
+        UpdateService::new(chains.clone(), settings.counter_history_retention_days).await?,
+    );
+
+    let admin = settings.admin_api_key.clone().map(|api_key| {
+        Arc::new(AdminService::new(
+            chains.clone(),
+            update_service.clone(),
+            api_key,
+        ))
+    });
 
-    let update_service =
-        Arc::new(UpdateService::new(db.clone(), blockscout, charts.clone()).await?);
+    if let Some(schedule) = settings.reorg_check_schedule {
+        tokio::spawn(update_service.clone().run_reorg_checks(schedule));
+    }
 
     tokio::spawn(async move {
         update_service
@@ -77,13 +170,17 @@ pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
             .await;
     });
 
-    let read_service = Arc::new(ReadService::new(db, charts).await?);
+    let export = Arc::new(ChartsExport::new(chains.clone()));
+    let read_service = Arc::new(ReadService::new(chains.clone()).await?);
     let health = Arc::new(HealthService::default());
 
-    let grpc_router = grpc_router(read_service.clone(), health.clone());
+    let grpc_router = grpc_router(read_service.clone(), health.clone(), admin.clone());
     let http_router = HttpRouter {
         stats: read_service,
         health: health.clone(),
+        chains,
+        export,
+        admin,
     };
 
     let launch_settings = LaunchSettings {
@@ -6,7 +6,8 @@ use config::{Config, File};
 use cron::Schedule;
 use serde::{de, Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use stats::{MissingPrice, Timezone};
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf, str::FromStr};
 
 /// Wrapper under [`serde::de::IgnoredAny`] which implements
 /// [`PartialEq`] and [`Eq`] for fields to be ignored.
@@ -35,6 +36,26 @@ pub struct Settings {
     pub force_update_on_start: Option<bool>, // None = no update
     pub concurrent_start_updates: usize,
     pub charts_config: PathBuf,
+    /// Alert rules on chart values. Alerts are disabled if not set
+    pub alerts_config: Option<PathBuf>,
+    /// Admin api is enabled only if the key is set
+    pub admin_api_key: Option<String>,
+    /// Schedule of checks for reorged blocks. Checks are disabled if not set
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub reorg_check_schedule: Option<Schedule>,
+    /// Chains served in addition to the default one, keyed by chain id
+    pub chains: BTreeMap<String, ChainSettings>,
+    /// Timezone of chart dates, can be overridden in settings of a chart
+    #[serde_as(as = "DisplayFromStr")]
+    pub timezone: Timezone,
+    /// Handling of days without price in fiat charts
+    #[serde_as(as = "DisplayFromStr")]
+    pub missing_price: MissingPrice,
+    /// Hourly values of counters older than this amount of days are removed,
+    /// only the last value of every day is kept for them
+    pub counter_history_retention_days: u32,
+    /// Maximal amount of cached line chart responses of every chain. Zero disables the cache
+    pub read_cache_size: usize,
 
     pub server: ServerSettings,
     pub metrics: MetricsSettings,
@@ -67,6 +88,14 @@ impl Default for Settings {
             force_update_on_start: Some(false),
             concurrent_start_updates: 3,
             charts_config: PathBuf::from_str("config/charts.toml").unwrap(),
+            alerts_config: Default::default(),
+            admin_api_key: Default::default(),
+            reorg_check_schedule: Default::default(),
+            chains: Default::default(),
+            timezone: Default::default(),
+            missing_price: Default::default(),
+            counter_history_retention_days: 30,
+            read_cache_size: 1000,
             blockscout_db_url: Default::default(),
             create_database: Default::default(),
             run_migrations: Default::default(),
@@ -78,7 +107,32 @@ impl Default for Settings {
     }
 }
 
+/// Databases and charts of a single chain.
+/// Every chain must have its own stats database
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSettings {
+    pub db_url: String,
+    pub blockscout_db_url: String,
+    /// Default is `charts_config` of the service
+    #[serde(default)]
+    pub charts_config: Option<PathBuf>,
+    /// Default is `alerts_config` of the service
+    #[serde(default)]
+    pub alerts_config: Option<PathBuf>,
+}
+
 impl Settings {
+    /// Chain configured with top-level settings
+    pub fn default_chain(&self) -> Option<ChainSettings> {
+        (!self.blockscout_db_url.is_empty()).then(|| ChainSettings {
+            db_url: self.db_url.clone(),
+            blockscout_db_url: self.blockscout_db_url.clone(),
+            charts_config: None,
+            alerts_config: None,
+        })
+    }
+
     pub fn new() -> anyhow::Result<Self> {
         let config_path = std::env::var("STATS__CONFIG");
 
@@ -1,13 +1,33 @@
-use crate::charts::{ArcChart, Charts};
+use crate::{
+    chains::{Chain, Chains},
+    charts::ArcChart,
+};
 use chrono::Utc;
 use cron::Schedule;
-use sea_orm::{DatabaseConnection, DbErr};
-use std::sync::Arc;
+use futures::future::{AbortHandle, Aborted};
+use sea_orm::DbErr;
+use stats::{entity::sea_orm_active_enums::ChartType, Chart};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::sync::mpsc;
 
+/// Schedules updates of charts of all chains.
+///
+/// Charts are updated on their schedules (see [`scheduled_charts`]),
+/// then charts depending on them are updated in topological order.
 pub struct UpdateService {
-    db: Arc<DatabaseConnection>,
-    blockscout: Arc<DatabaseConnection>,
-    charts: Arc<Charts>,
+    chains: Arc<Chains>,
+    // running updates by chain id, chart name and update id
+    running: Mutex<HashMap<(String, String), HashMap<u64, AbortHandle>>>,
+    next_update_id: AtomicU64,
+    counter_history_retention_days: u32,
+    // updated charts to evaluate alert rules of, by chain id
+    alerts: HashMap<String, mpsc::UnboundedSender<String>>,
 }
 
 fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
@@ -22,16 +42,64 @@ fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
 
 impl UpdateService {
     pub async fn new(
-        db: Arc<DatabaseConnection>,
-        blockscout: Arc<DatabaseConnection>,
-        charts: Arc<Charts>,
+        chains: Arc<Chains>,
+        counter_history_retention_days: u32,
     ) -> Result<Self, DbErr> {
+        let alerts = chains
+            .iter()
+            .filter(|chain| !chain.alerts.is_empty())
+            .map(|chain| (chain.id.clone(), spawn_alerts_evaluation(chain.clone())))
+            .collect();
         Ok(Self {
-            db,
-            blockscout,
-            charts,
+            chains,
+            running: Default::default(),
+            next_update_id: Default::default(),
+            counter_history_retention_days,
+            alerts,
         })
     }
+
+    /// Starts update of the chart and charts depending on it in background.
+    /// Returns `false` if chart is not enabled
+    pub fn trigger_update(
+        self: &Arc<Self>,
+        chain: &Arc<Chain>,
+        name: &str,
+        force_full: bool,
+    ) -> bool {
+        if !chain.charts.charts.iter().any(|chart| chart.name() == name) {
+            return false;
+        }
+        let charts = chain.charts.graph.with_dependents(name);
+        tokio::spawn(
+            self.clone()
+                .update_with_dependents(chain.clone(), charts, force_full),
+        );
+        true
+    }
+
+    /// Cancels all running updates of the chart.
+    /// Returns `false` if there were no running updates
+    pub fn cancel_update(&self, chain: &Chain, name: &str) -> bool {
+        let running = self.running.lock().expect("poisoned lock");
+        match running.get(&(chain.id.clone(), name.to_owned())) {
+            Some(updates) if !updates.is_empty() => {
+                tracing::info!(chain_id = %chain.id, chart = name, "cancelling chart update");
+                updates.values().for_each(AbortHandle::abort);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_running(&self, chain: &Chain, name: &str) -> bool {
+        self.running
+            .lock()
+            .expect("poisoned lock")
+            .get(&(chain.id.clone(), name.to_owned()))
+            .map(|updates| !updates.is_empty())
+            .unwrap_or_default()
+    }
     pub async fn force_async_update_and_run(
         self: Arc<Self>,
         concurrent_tasks: usize,
@@ -40,20 +108,32 @@ impl UpdateService {
     ) {
         let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrent_tasks));
         let tasks = self
-            .charts
-            .charts
+            .chains
             .iter()
-            .map(|chart| {
+            .map(|chain| {
                 let this = self.clone();
-                let chart = chart.clone();
+                let chain = chain.clone();
                 let default_schedule = default_schedule.clone();
-                let sema = semaphore.clone();
+                let semaphore = semaphore.clone();
                 async move {
-                    let _permit = sema.acquire().await.expect("failed to acquire permit");
                     if let Some(force_full) = force_update_on_start {
-                        this.clone().update(chart.clone(), force_full).await
-                    };
-                    this.spawn_chart_updater(chart, &default_schedule);
+                        let updates = chain.charts.graph.components().into_iter().map(|charts| {
+                            let this = this.clone();
+                            let chain = chain.clone();
+                            let sema = semaphore.clone();
+                            async move {
+                                let _permit =
+                                    sema.acquire().await.expect("failed to acquire permit");
+                                this.update_with_dependents(chain, charts, force_full).await
+                            }
+                        });
+                        futures::future::join_all(updates).await;
+                    }
+                    for (schedule, charts) in scheduled_charts(&chain, &default_schedule) {
+                        let this = this.clone();
+                        let chain = chain.clone();
+                        tokio::spawn(async move { this.run_cron(chain, charts, schedule).await });
+                    }
                 }
             })
             .collect::<Vec<_>>();
@@ -61,52 +141,250 @@ impl UpdateService {
         tracing::info!("initial updating is done");
     }
 
-    fn spawn_chart_updater(self: &Arc<Self>, chart: ArcChart, default_schedule: &Schedule) {
-        let settings = self
-            .charts
-            .settings
-            .get(chart.name())
-            .expect("enabled chart must contain settings");
-        let this = self.clone();
-        let chart = chart.clone();
-        let schedule = settings
-            .update_schedule
-            .as_ref()
-            .unwrap_or(default_schedule)
-            .clone();
-        tokio::spawn(async move { this.run_cron(chart, schedule).await });
+    /// Updates charts in the given order.
+    /// Charts which dependencies failed to update are skipped
+    async fn update_with_dependents(
+        self: Arc<Self>,
+        chain: Arc<Chain>,
+        charts: Vec<ArcChart>,
+        force_full: bool,
+    ) {
+        let mut failed = HashSet::new();
+        for chart in charts {
+            let failed_dependency = chart
+                .dependencies()
+                .into_iter()
+                .find(|dependency| failed.contains(dependency.name()));
+            let updated = match failed_dependency {
+                Some(dependency) => {
+                    tracing::warn!(
+                        chain_id = %chain.id,
+                        chart = chart.name(),
+                        dependency = dependency.name(),
+                        "skipping update of chart due to failed update of its dependency"
+                    );
+                    false
+                }
+                None => {
+                    self.clone()
+                        .update(chain.clone(), chart.clone(), force_full)
+                        .await
+                }
+            };
+            if !updated {
+                failed.insert(chart.name().to_owned());
+            }
+        }
     }
 
-    async fn update(self: Arc<Self>, chart: ArcChart, force_full: bool) {
-        tracing::info!(chart = chart.name(), "updating chart");
-        let result = {
+    /// Returns `true` if the chart was successfully updated
+    async fn update(self: Arc<Self>, chain: Arc<Chain>, chart: ArcChart, force_full: bool) -> bool {
+        tracing::info!(chain_id = %chain.id, chart = chart.name(), "updating chart");
+        let timezone = chain.charts.timezone(chart.name());
+        let update = stats::timezone::scope(timezone, async {
             let _timer = stats::metrics::CHART_UPDATE_TIME
-                .with_label_values(&[chart.name()])
+                .with_label_values(&[&chain.id, chart.name()])
                 .start_timer();
             chart
-                .update_with_mutex(&self.db, &self.blockscout, force_full)
+                .update_with_mutex(&chain.db, &chain.blockscout, force_full)
                 .await
+        });
+        let (update, abort_handle) =
+            futures::future::abortable(stats::chain::scope(chain.id.clone(), update));
+        let update_id = self.next_update_id.fetch_add(1, Ordering::Relaxed);
+        let key = (chain.id.clone(), chart.name().to_owned());
+        self.running
+            .lock()
+            .expect("poisoned lock")
+            .entry(key.clone())
+            .or_default()
+            .insert(update_id, abort_handle);
+        let result = update.await;
+        if let Some(updates) = self.running.lock().expect("poisoned lock").get_mut(&key) {
+            updates.remove(&update_id);
+        }
+
+        // failed and cancelled updates may also save some points
+        chain.read_cache.invalidate(chart.name());
+
+        let updated = matches!(result, Ok(Ok(())));
+        let status = match result {
+            Ok(Ok(())) => {
+                tracing::info!(
+                    chain_id = %chain.id,
+                    chart = chart.name(),
+                    "successfully updated chart"
+                );
+                chain.counters_watch.updated(chart.name());
+                stats::set_update_succeeded(&chain.db, chart.name()).await
+            }
+            Ok(Err(err)) => {
+                stats::metrics::UPDATE_ERRORS
+                    .with_label_values(&[&chain.id, chart.name()])
+                    .inc();
+                tracing::error!(
+                    chain_id = %chain.id,
+                    chart = chart.name(),
+                    "error during updating chart: {}",
+                    err
+                );
+                stats::set_update_failed(&chain.db, chart.name(), &err.to_string()).await
+            }
+            Err(Aborted) => {
+                tracing::warn!(
+                    chain_id = %chain.id,
+                    chart = chart.name(),
+                    "chart update was cancelled"
+                );
+                stats::set_update_failed(&chain.db, chart.name(), "update was cancelled").await
+            }
         };
+        if let Err(err) = status {
+            tracing::error!(
+                chain_id = %chain.id,
+                chart = chart.name(),
+                "failed to save update status of chart: {}",
+                err
+            );
+        }
+        if updated && chart.chart_type() == ChartType::Counter {
+            self.save_counter_history(&chain, chart.name()).await;
+        }
+        if updated {
+            if let Some(alerts) = self.alerts.get(&chain.id) {
+                // receiver lives as long as the service
+                let _ = alerts.send(chart.name().to_owned());
+            }
+        }
+        updated
+    }
+
+    /// Saves the current value of the counter to its history
+    /// and removes history points older than the retention period
+    async fn save_counter_history(&self, chain: &Chain, name: &str) {
+        let timezone = chain.charts.timezone(name);
+        let result = async {
+            stats::counter_history::save_counter_snapshot(&chain.db, name, timezone).await?;
+            stats::counter_history::compact_counter_history(
+                &chain.db,
+                name,
+                timezone,
+                self.counter_history_retention_days,
+            )
+            .await
+        }
+        .await;
         if let Err(err) = result {
-            stats::metrics::UPDATE_ERRORS
-                .with_label_values(&[chart.name()])
-                .inc();
-            tracing::error!(chart = chart.name(), "error during updating chart: {}", err);
-        } else {
-            tracing::info!(chart = chart.name(), "successfully updated chart");
+            tracing::error!(
+                chain_id = %chain.id,
+                chart = name,
+                "failed to save history of counter: {}",
+                err
+            );
         }
     }
 
-    async fn run_cron(self: Arc<Self>, chart: ArcChart, schedule: Schedule) {
+    /// Periodically removes chart points of dates with reorged blocks.
+    /// Removed points are recalculated by the next scheduled update of every chart
+    pub async fn run_reorg_checks(self: Arc<Self>, schedule: Schedule) {
+        loop {
+            let sleep_duration = time_till_next_call(&schedule);
+            tracing::info!("scheduled next reorg check in {:?}", sleep_duration);
+            tokio::time::sleep(sleep_duration).await;
+            for chain in self.chains.iter() {
+                let result = stats::reorg::invalidate_reorged_dates(
+                    &chain.db,
+                    &chain.blockscout,
+                    &chain.charts.timezones,
+                )
+                .await;
+                match result {
+                    Ok(dates) if !dates.is_empty() => {
+                        chain.read_cache.invalidate_all();
+                        stats::metrics::REORGED_DATES
+                            .with_label_values(&[&chain.id])
+                            .inc_by(dates.len() as u64);
+                        tracing::warn!(
+                            chain_id = %chain.id,
+                            dates = ?dates,
+                            "invalidated chart points of reorged dates"
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::error!(chain_id = %chain.id, "error during reorg check: {}", err)
+                    }
+                }
+            }
+        }
+    }
+
+    async fn run_cron(
+        self: Arc<Self>,
+        chain: Arc<Chain>,
+        charts: Vec<ArcChart>,
+        schedule: Schedule,
+    ) {
+        let names: Vec<_> = charts.iter().map(|chart| chart.name().to_owned()).collect();
         loop {
             let sleep_duration = time_till_next_call(&schedule);
             tracing::info!(
-                chart = chart.name(),
+                chain_id = %chain.id,
+                charts = ?names,
                 "scheduled next run of chart update in {:?}",
                 sleep_duration
             );
             tokio::time::sleep(sleep_duration).await;
-            self.clone().update(chart.clone(), false).await;
+            self.clone()
+                .update_with_dependents(chain.clone(), charts.clone(), false)
+                .await;
         }
     }
 }
+
+/// Charts to update on every tick of each schedule, in order of update.
+///
+/// Charts without dependencies use the default schedule unless their own is set,
+/// dependents are updated after their dependencies and on their own schedule if it's set.
+/// Charts of the same schedule are updated together, so a chart that is reachable
+/// from several of them is updated once per tick.
+fn scheduled_charts(chain: &Chain, default_schedule: &Schedule) -> Vec<(Schedule, Vec<ArcChart>)> {
+    let mut schedules: BTreeMap<String, (Schedule, Vec<&str>)> = BTreeMap::new();
+    for chart in chain.charts.graph.iter() {
+        // dependency of enabled charts may be disabled itself
+        let schedule = chain
+            .charts
+            .settings
+            .get(chart.name())
+            .and_then(|settings| settings.update_schedule.as_ref());
+        let schedule = match schedule {
+            Some(schedule) => schedule,
+            None if chart.dependencies().is_empty() => default_schedule,
+            None => continue,
+        };
+        schedules
+            .entry(schedule.to_string())
+            .or_insert_with(|| (schedule.clone(), Vec::new()))
+            .1
+            .push(chart.name());
+    }
+    schedules
+        .into_values()
+        .map(|(schedule, names)| (schedule, chain.charts.graph.with_dependents_of(names)))
+        .collect()
+}
+
+/// Evaluates alert rules of the updated charts of the chain one by one,
+/// so slow sinks don't delay chart updates
+fn spawn_alerts_evaluation(chain: Arc<Chain>) -> mpsc::UnboundedSender<String> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        while let Some(chart) = receiver.recv().await {
+            chain
+                .alerts
+                .evaluate(&chain.id, &chain.db, &chain.charts, &chart)
+                .await;
+        }
+    });
+    sender
+}
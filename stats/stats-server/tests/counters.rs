@@ -1,7 +1,7 @@
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
-use stats_proto::blockscout::stats::v1::Counters;
+use stats_proto::blockscout::stats::v1::{Counters, LineChart};
 use stats_server::{stats, Settings};
 use std::{collections::HashSet, path::PathBuf, str::FromStr};
 
@@ -66,9 +66,37 @@ async fn test_counters_ok() {
         "lastNewVerifiedContracts",
         "totalContracts",
         "totalVerifiedContracts",
+        "lastNativeCoinPrice",
+        "lastMarketCap",
     ]
     .into_iter()
     .collect();
 
     assert_eq!(counter_names, expected_counter_names);
+
+    for resolution in ["DAY", "HOUR"] {
+        let resp = client
+            .get(format!(
+                "{base}/api/v1/counters/totalBlocks/history?resolution={resolution}"
+            ))
+            .send()
+            .await
+            .expect("failed to connect to server");
+        assert_eq!(resp.status(), 200);
+        let history: LineChart = resp
+            .json()
+            .await
+            .expect("failed to convert response to json");
+        assert!(
+            !history.chart.is_empty(),
+            "{resolution} history of totalBlocks is empty"
+        );
+    }
+
+    let resp = client
+        .get(format!("{base}/api/v1/counters/unknownCounter/history"))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 404);
 }
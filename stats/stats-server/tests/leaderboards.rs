@@ -0,0 +1,108 @@
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
+use stats_proto::blockscout::stats::v1::Leaderboards;
+use stats_server::{stats, Settings};
+use std::{collections::HashSet, path::PathBuf, str::FromStr};
+
+fn client() -> ClientWithMiddleware {
+    let retry_policy = ExponentialBackoff::builder()
+        .build_with_total_retry_duration(std::time::Duration::from_secs(10));
+    ClientBuilder::new(reqwest::Client::new())
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build()
+}
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_leaderboards_ok() {
+    let db_url = std::env::var("DATABASE_URL").expect("no DATABASE_URL env");
+    let (_stats, blockscout) = init_db_all("test_leaderboards_ok", Some(db_url.clone())).await;
+    let stats_db_url = format!("{db_url}/test_leaderboards_ok",);
+    let blockscout_db_url = format!("{db_url}/test_leaderboards_ok_blockscout",);
+    fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+    let mut settings = Settings::default();
+    settings.charts_config = PathBuf::from_str("../config/charts.toml").unwrap();
+    settings.server.grpc.enabled = false;
+    settings.metrics.enabled = false;
+    settings.jaeger.enabled = false;
+    settings.db_url = stats_db_url;
+    settings.blockscout_db_url = blockscout_db_url;
+
+    let base = format!("http://{}", settings.server.http.addr);
+
+    let _server_handle = {
+        let settings = settings.clone();
+        tokio::spawn(async move { stats(settings).await.unwrap() })
+    };
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let client = client();
+
+    let resp = client
+        .get(format!("{base}/api/v1/leaderboards"))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 200);
+    let leaderboards: Leaderboards = resp
+        .json()
+        .await
+        .expect("failed to convert response to json");
+    let names: HashSet<_> = leaderboards
+        .leaderboards
+        .iter()
+        .map(|l| l.id.as_str())
+        .collect();
+    let expected_names: HashSet<_> = [
+        "topAccountsByTxns",
+        "topContractsByGasUsed",
+        "topTokensByTransfers",
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(names, expected_names);
+
+    for (name, period) in [
+        ("topAccountsByTxns", "THIRTY_DAYS"),
+        ("topTokensByTransfers", "SEVEN_DAYS"),
+    ] {
+        let resp = client
+            .get(format!(
+                "{base}/api/v1/leaderboards/{name}?period={period}&date=2023-02-01&limit=5"
+            ))
+            .send()
+            .await
+            .expect("failed to connect to server");
+        let s = resp.status();
+        assert_eq!(s, 200, "invalid status for leaderboard '{name}': {s:?}");
+
+        let leaderboard: serde_json::Value = resp
+            .json()
+            .await
+            .expect("failed to convert response to json");
+        let leaderboard = leaderboard
+            .as_object()
+            .expect("response has to be json object");
+        assert_eq!(
+            leaderboard.get("date").and_then(|date| date.as_str()),
+            Some("2023-02-01")
+        );
+        let entries = leaderboard
+            .get("entries")
+            .expect("response doesn't have 'entries' field")
+            .as_array()
+            .expect("'entries' field has to be json array");
+        assert!(!entries.is_empty(), "leaderboard '{name}' is empty");
+        assert!(entries.len() <= 5, "leaderboard '{name}' exceeds the limit");
+    }
+
+    let resp = client
+        .get(format!("{base}/api/v1/leaderboards/unknownLeaderboard"))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 404);
+}
@@ -63,6 +63,14 @@ async fn test_lines_ok() {
         "newContracts",
         "verifiedContractsGrowth",
         "contractsGrowth",
+        "gasPriceDistribution",
+        "txnFeeDistribution",
+        "blockTimeDistribution",
+        "txnsVolume",
+        "nativeCoinPrice",
+        "marketCap",
+        "txnsFeeUsd",
+        "txnsVolumeUsd",
     ] {
         let resp = client
             .get(format!("{base}/api/v1/lines/{line_name}"))
@@ -86,4 +94,62 @@ async fn test_lines_ok() {
 
         assert!(!chart.is_empty(), "chart '{line_name}' is empty");
     }
+
+    let resp = client
+        .get(format!("{base}/api/v1/lines/newBlocks"))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 200);
+    let etag = resp
+        .headers()
+        .get(reqwest::header::ETAG)
+        .expect("response doesn't have etag")
+        .clone();
+    assert!(resp.headers().contains_key(reqwest::header::LAST_MODIFIED));
+    let resp = client
+        .get(format!("{base}/api/v1/lines/newBlocks"))
+        .header(reqwest::header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 304);
+    let resp = client
+        .get(format!("{base}/api/v1/lines/newBlocks?resolution=WEEK"))
+        .header(reqwest::header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .get(format!(
+            "{base}/api/v1/export?charts=newTxns,newBlocks&format=csv"
+        ))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 200);
+    let csv = resp.text().await.expect("failed to read csv");
+    let mut rows = csv.lines();
+    assert_eq!(rows.next(), Some("date,newTxns,newBlocks"));
+    assert!(rows.next().is_some(), "csv export is empty");
+
+    let resp = client
+        .get(format!(
+            "{base}/api/v1/export?charts=newTxns&format=parquet"
+        ))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 200);
+    let parquet = resp.bytes().await.expect("failed to read parquet");
+    assert!(parquet.starts_with(b"PAR1"), "invalid parquet file");
+
+    let resp = client
+        .get(format!("{base}/api/v1/export?charts=unknownChart"))
+        .send()
+        .await
+        .expect("failed to connect to server");
+    assert_eq!(resp.status(), 404);
 }
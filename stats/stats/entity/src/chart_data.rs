@@ -1,5 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.4
 
+use super::sea_orm_active_enums::ChartResolution;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -12,6 +13,9 @@ pub struct Model {
     pub value: String,
     pub created_at: DateTime,
     pub min_blockscout_block: Option<i64>,
+    pub resolution: ChartResolution,
+    pub time: Time,
+    pub series: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
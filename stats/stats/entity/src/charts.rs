@@ -12,12 +12,18 @@ pub struct Model {
     pub name: String,
     pub chart_type: ChartType,
     pub created_at: DateTime,
+    pub last_updated_at: Option<DateTime>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::chart_data::Entity")]
     ChartData,
+    #[sea_orm(has_many = "super::leaderboard_data::Entity")]
+    LeaderboardData,
 }
 
 impl Related<super::chart_data::Entity> for Entity {
@@ -26,4 +32,10 @@ impl Related<super::chart_data::Entity> for Entity {
     }
 }
 
+impl Related<super::leaderboard_data::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LeaderboardData.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -4,4 +4,6 @@ pub mod prelude;
 
 pub mod chart_data;
 pub mod charts;
+pub mod kv_storage;
+pub mod leaderboard_data;
 pub mod sea_orm_active_enums;
@@ -1,3 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.4
 
-pub use super::{chart_data::Entity as ChartData, charts::Entity as Charts};
+pub use super::{
+    chart_data::Entity as ChartData, charts::Entity as Charts, kv_storage::Entity as KvStorage,
+    leaderboard_data::Entity as LeaderboardData,
+};
@@ -7,6 +7,23 @@ use sea_orm::entity::prelude::*;
 pub enum ChartType {
     #[sea_orm(string_value = "COUNTER")]
     Counter,
+    #[sea_orm(string_value = "INTERNAL")]
+    Internal,
+    #[sea_orm(string_value = "LEADERBOARD")]
+    Leaderboard,
     #[sea_orm(string_value = "LINE")]
     Line,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "chart_resolution")]
+pub enum ChartResolution {
+    #[sea_orm(string_value = "DAY")]
+    Day,
+    #[sea_orm(string_value = "HOUR")]
+    Hour,
+    #[sea_orm(string_value = "MONTH")]
+    Month,
+    #[sea_orm(string_value = "WEEK")]
+    Week,
+}
@@ -2,13 +2,35 @@ pub use sea_orm_migration::prelude::*;
 use sea_orm_migration::sea_orm::{ConnectionTrait, Statement, TransactionTrait};
 
 mod m20220101_000001_init;
+mod m20230315_000001_chart_resolutions;
+mod m20230320_000001_chart_update_status;
+mod m20230322_000001_kv_storage;
+mod m20230327_000001_chart_series;
+mod m20230329_000001_txns_rollup;
+mod m20230403_000001_leaderboards;
+mod m20230405_000001_recalculate_total_counters;
+mod m20230406_000001_internal_charts;
+mod m20230406_000002_txns_rollup_internal;
+mod m20230410_000001_txns_rollup_value_sum;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_init::Migration)]
+        vec![
+            Box::new(m20220101_000001_init::Migration),
+            Box::new(m20230315_000001_chart_resolutions::Migration),
+            Box::new(m20230320_000001_chart_update_status::Migration),
+            Box::new(m20230322_000001_kv_storage::Migration),
+            Box::new(m20230327_000001_chart_series::Migration),
+            Box::new(m20230329_000001_txns_rollup::Migration),
+            Box::new(m20230403_000001_leaderboards::Migration),
+            Box::new(m20230405_000001_recalculate_total_counters::Migration),
+            Box::new(m20230406_000001_internal_charts::Migration),
+            Box::new(m20230406_000002_txns_rollup_internal::Migration),
+            Box::new(m20230410_000001_txns_rollup_value_sum::Migration),
+        ]
     }
 }
 
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TYPE "chart_resolution" AS ENUM (
+  'HOUR',
+  'DAY',
+  'WEEK',
+  'MONTH'
+);
+
+ALTER TABLE "chart_data"
+  ADD COLUMN "resolution" chart_resolution NOT NULL DEFAULT 'DAY',
+  ADD COLUMN "time" time NOT NULL DEFAULT '00:00:00';
+
+DROP INDEX "chart_data_chart_id_date_idx";
+
+CREATE UNIQUE INDEX "chart_data_chart_id_resolution_date_time_idx"
+  ON "chart_data" ("chart_id", "resolution", "date", "time");
+
+COMMENT ON COLUMN "chart_data"."date" IS 'First day of the period the point belongs to';
+
+COMMENT ON COLUMN "chart_data"."time" IS 'Start time of the period within `date`, is non-zero only for hourly points';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DELETE FROM "chart_data" WHERE "resolution" != 'DAY';
+
+DROP INDEX "chart_data_chart_id_resolution_date_time_idx";
+
+ALTER TABLE "chart_data"
+  DROP COLUMN "resolution",
+  DROP COLUMN "time";
+
+CREATE UNIQUE INDEX "chart_data_chart_id_date_idx"
+  ON "chart_data" ("chart_id", "date");
+
+DROP TYPE "chart_resolution";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
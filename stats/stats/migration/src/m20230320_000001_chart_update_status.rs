@@ -0,0 +1,31 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "charts"
+  ADD COLUMN "last_updated_at" timestamp,
+  ADD COLUMN "last_error" text,
+  ADD COLUMN "last_error_at" timestamp;
+
+COMMENT ON COLUMN "charts"."last_updated_at" IS 'Time when the last successful update finished';
+
+COMMENT ON COLUMN "charts"."last_error" IS 'Error of the last failed or cancelled update';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "charts"
+  DROP COLUMN "last_updated_at",
+  DROP COLUMN "last_error",
+  DROP COLUMN "last_error_at";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
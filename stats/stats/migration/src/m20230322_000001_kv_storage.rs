@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TABLE IF NOT EXISTS "kv_storage" (
+  "key" varchar(256) PRIMARY KEY,
+  "value" text NOT NULL
+);
+
+COMMENT ON TABLE "kv_storage" IS 'Table contains service state, e.g. progress of batch updates';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "kv_storage";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
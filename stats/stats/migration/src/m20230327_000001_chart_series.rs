@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "chart_data"
+  ADD COLUMN "series" varchar(64) NOT NULL DEFAULT '';
+
+DROP INDEX "chart_data_chart_id_resolution_date_time_idx";
+
+CREATE UNIQUE INDEX "chart_data_chart_id_resolution_date_time_series_idx"
+  ON "chart_data" ("chart_id", "resolution", "date", "time", "series");
+
+COMMENT ON COLUMN "chart_data"."series" IS 'Name of the additional series of the point, is empty for the main value';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DELETE FROM "chart_data" WHERE "series" != '';
+
+DROP INDEX "chart_data_chart_id_resolution_date_time_series_idx";
+
+ALTER TABLE "chart_data"
+  DROP COLUMN "series";
+
+CREATE UNIQUE INDEX "chart_data_chart_id_resolution_date_time_idx"
+  ON "chart_data" ("chart_id", "resolution", "date", "time");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
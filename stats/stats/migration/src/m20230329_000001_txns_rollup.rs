@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TABLE IF NOT EXISTS "txns_rollup" (
+  "hour" timestamp PRIMARY KEY,
+  "txns" bigint NOT NULL,
+  "finished_txns" bigint NOT NULL,
+  "succeeded_txns" bigint NOT NULL,
+  "fee_sum" numeric NOT NULL,
+  "fee_count" bigint NOT NULL,
+  "gas_price_sum" numeric NOT NULL,
+  "gas_price_count" bigint NOT NULL,
+  "min_blockscout_block" bigint NOT NULL
+);
+
+COMMENT ON TABLE "txns_rollup" IS 'Aggregates of blockscout transactions per hour, line charts of transactions are calculated from it';
+
+COMMENT ON COLUMN "txns_rollup"."finished_txns" IS 'Transactions that were not dropped or replaced';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "txns_rollup";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TYPE "chart_type" ADD VALUE 'LEADERBOARD';
+
+CREATE TABLE "leaderboard_data" (
+  "id" INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+  "chart_id" int NOT NULL,
+  "date" date NOT NULL,
+  "period_days" int NOT NULL,
+  "rank" int NOT NULL,
+  "address" bytea NOT NULL,
+  "value" varchar(64) NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT (now()),
+  "min_blockscout_block" bigint
+);
+
+CREATE UNIQUE INDEX ON "leaderboard_data" ("chart_id", "date", "period_days", "rank");
+
+COMMENT ON TABLE "leaderboard_data" IS 'Table contains ranked entries of leaderboard charts';
+
+COMMENT ON COLUMN "leaderboard_data"."period_days" IS 'Number of days ending with the date that the entries are ranked over';
+
+ALTER TABLE "leaderboard_data" ADD FOREIGN KEY ("chart_id") REFERENCES "charts" ("id");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "leaderboard_data";
+
+DELETE FROM "charts" WHERE "chart_type" = 'LEADERBOARD';
+
+ALTER TYPE "chart_type" RENAME TO "chart_type_old";
+
+CREATE TYPE "chart_type" AS ENUM (
+  'COUNTER',
+  'LINE'
+);
+
+ALTER TABLE "charts"
+  ALTER COLUMN "chart_type" TYPE "chart_type" USING "chart_type"::text::"chart_type";
+
+DROP TYPE "chart_type_old";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,24 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Total counters used to keep a single sum of the parent chart,
+    // now they keep running total for every date. Removed points are
+    // recalculated by the next update of the counters.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DELETE FROM "chart_data"
+WHERE "chart_id" IN (
+  SELECT "id" FROM "charts" WHERE "name" IN ('totalTxns', 'totalNativeCoinTransfers')
+);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
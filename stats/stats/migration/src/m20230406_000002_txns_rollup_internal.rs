@@ -0,0 +1,22 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // new value of enum can be used only after the transaction that added it
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+UPDATE "charts" SET "chart_type" = 'INTERNAL' WHERE "name" = 'txnsRollup';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+UPDATE "charts" SET "chart_type" = 'LINE' WHERE "name" = 'txnsRollup';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
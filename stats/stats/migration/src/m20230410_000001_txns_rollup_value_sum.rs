@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // saved hours don't have the new column, so the rollup is cleared
+    // and the next update calculates it from the start
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DELETE FROM "txns_rollup";
+
+ALTER TABLE "txns_rollup"
+  ADD COLUMN "value_sum" numeric NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN "txns_rollup"."value_sum" IS 'Native coins transferred by successful transactions';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "txns_rollup"
+  DROP COLUMN "value_sum";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
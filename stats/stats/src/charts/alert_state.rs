@@ -0,0 +1,140 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use entity::kv_storage;
+use sea_orm::{prelude::*, sea_query, ConnectionTrait, Set};
+use std::str::FromStr;
+
+const KEY_PREFIX: &str = "alert:";
+
+/// Last delivered state of an alert rule.
+///
+/// Is saved only when the rule changes its state,
+/// so alerts are not sent again after restart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertState {
+    pub is_firing: bool,
+    /// Date of the chart point that changed the state
+    pub date: NaiveDate,
+    pub changed_at: NaiveDateTime,
+}
+
+impl AlertState {
+    fn encode(&self) -> String {
+        let state = if self.is_firing { "firing" } else { "resolved" };
+        format!(
+            "{}|{}|{}",
+            state,
+            self.date,
+            self.changed_at.format("%Y-%m-%dT%H:%M:%S")
+        )
+    }
+
+    fn decode(value: &str) -> Option<Self> {
+        let mut parts = value.split('|');
+        let is_firing = match parts.next()? {
+            "firing" => true,
+            "resolved" => false,
+            _ => return None,
+        };
+        let state = Self {
+            is_firing,
+            date: NaiveDate::from_str(parts.next()?).ok()?,
+            changed_at: NaiveDateTime::from_str(parts.next()?).ok()?,
+        };
+        parts.next().is_none().then_some(state)
+    }
+}
+
+fn key(rule_id: &str) -> String {
+    format!("{KEY_PREFIX}{rule_id}")
+}
+
+pub async fn get_alert_state<C: ConnectionTrait>(
+    db: &C,
+    rule_id: &str,
+) -> Result<Option<AlertState>, DbErr> {
+    let row = kv_storage::Entity::find_by_id(key(rule_id)).one(db).await?;
+    let state = row.and_then(|row| {
+        let state = AlertState::decode(&row.value);
+        if state.is_none() {
+            tracing::warn!(rule = rule_id, value = %row.value, "ignoring invalid alert state");
+        }
+        state
+    });
+    Ok(state)
+}
+
+pub async fn save_alert_state<C: ConnectionTrait>(
+    db: &C,
+    rule_id: &str,
+    state: &AlertState,
+) -> Result<(), DbErr> {
+    kv_storage::Entity::insert(kv_storage::ActiveModel {
+        key: Set(key(rule_id)),
+        value: Set(state.encode()),
+    })
+    .on_conflict(
+        sea_query::OnConflict::column(kv_storage::Column::Key)
+            .update_column(kv_storage::Column::Value)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use pretty_assertions::assert_eq;
+
+    fn state(is_firing: bool) -> AlertState {
+        AlertState {
+            is_firing,
+            date: NaiveDate::from_str("2022-11-10").unwrap(),
+            changed_at: NaiveDateTime::from_str("2022-11-11T12:30:00").unwrap(),
+        }
+    }
+
+    #[test]
+    fn encode_decode_works() {
+        let firing = state(true);
+        let encoded = firing.encode();
+        assert_eq!("firing|2022-11-10|2022-11-11T12:30:00", encoded);
+        assert_eq!(Some(firing), AlertState::decode(&encoded));
+        let resolved = state(false);
+        assert_eq!(
+            Some(resolved.clone()),
+            AlertState::decode(&resolved.encode())
+        );
+
+        for invalid in [
+            "",
+            "pending|2022-11-10|2022-11-11T12:30:00",
+            "firing|2022-11-10",
+            "firing|2022-11-10|2022-11-11T12:30:00|1",
+        ] {
+            assert_eq!(None, AlertState::decode(invalid));
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn save_alert_state_works() {
+        let db = init_db::<migration::Migrator>("save_alert_state_works", None).await;
+        assert_eq!(None, get_alert_state(&db, "rule").await.unwrap());
+
+        save_alert_state(&db, "rule", &state(true)).await.unwrap();
+        assert_eq!(
+            Some(state(true)),
+            get_alert_state(&db, "rule").await.unwrap()
+        );
+
+        save_alert_state(&db, "rule", &state(false)).await.unwrap();
+        assert_eq!(
+            Some(state(false)),
+            get_alert_state(&db, "rule").await.unwrap()
+        );
+        assert_eq!(None, get_alert_state(&db, "other").await.unwrap());
+    }
+}
@@ -0,0 +1,269 @@
+use super::{
+    chain, find_chart,
+    insert::{insert_data_many, DateValue},
+    mutex::get_global_update_mutex,
+    updater::get_min_block_blockscout,
+    Chart, UpdateError,
+};
+use crate::get_chart_data;
+use chrono::{Duration, NaiveDate};
+use entity::{chart_data, sea_orm_active_enums::ChartResolution};
+use sea_orm::{prelude::*, TransactionTrait};
+use std::{collections::HashMap, str::FromStr};
+
+/// Saved point of the chart that differs from the value recalculated from blockscout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub date: NaiveDate,
+    /// `None` if there is no point of the date
+    pub stored: Option<String>,
+    /// `None` if blockscout has no data of the date
+    pub recalculated: Option<String>,
+}
+
+/// Up to `size` evenly spaced dates from `first` to `last` inclusive
+pub fn sample_dates(first: NaiveDate, last: NaiveDate, size: usize) -> Vec<NaiveDate> {
+    let days = (last - first).num_days();
+    if days < 0 || size == 0 {
+        return vec![];
+    }
+    let total = days as usize + 1;
+    if size >= total {
+        return (0..total)
+            .map(|i| first + Duration::days(i as i64))
+            .collect();
+    }
+    if size == 1 {
+        return vec![last];
+    }
+    (0..size)
+        .map(|i| first + Duration::days((i * (total - 1) / (size - 1)) as i64))
+        .collect()
+}
+
+/// Numbers are compared by value, so `1.50` is equal to `1.5`
+fn values_equal(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    match (f64::from_str(a), f64::from_str(b)) {
+        (Ok(a), Ok(b)) => (a - b).abs() <= f64::EPSILON * a.abs().max(b.abs()) * 4.0,
+        _ => false,
+    }
+}
+
+/// Compares saved daily points of the dates with values recalculated from blockscout.
+/// `None` if the chart is not calculated directly from blockscout
+pub async fn audit_chart(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &(dyn Chart + Send + Sync),
+    dates: &[NaiveDate],
+) -> Result<Option<Vec<Mismatch>>, UpdateError> {
+    let (first, last) = match (dates.iter().min(), dates.iter().max()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(Some(vec![])),
+    };
+    let recalculated = match chart.recalculate(blockscout, dates).await? {
+        Some(values) => values,
+        None => return Ok(None),
+    };
+    let mut recalculated: HashMap<_, _> = recalculated
+        .into_iter()
+        .map(|point| (point.date, point.value))
+        .collect();
+    let mut stored: HashMap<_, _> = get_chart_data(db, chart.name(), Some(first), Some(last))
+        .await?
+        .into_iter()
+        .map(|point| (point.date, point.value))
+        .collect();
+
+    let mut dates = dates.to_vec();
+    dates.sort();
+    dates.dedup();
+    let mismatches = dates
+        .into_iter()
+        .filter_map(|date| {
+            let stored = stored.remove(&date);
+            let recalculated = recalculated.remove(&date);
+            let is_equal = match (&stored, &recalculated) {
+                (Some(stored), Some(recalculated)) => values_equal(stored, recalculated),
+                (None, None) => true,
+                _ => false,
+            };
+            (!is_equal).then_some(Mismatch {
+                date,
+                stored,
+                recalculated,
+            })
+        })
+        .collect();
+    Ok(Some(mismatches))
+}
+
+/// Replaces mismatched daily points with the recalculated values.
+///
+/// Points of other resolutions and charts calculated from the chart are not changed,
+/// they have to be invalidated from the first mismatched date.
+/// Takes the update mutex of the chart, so that points are not repaired during its update.
+pub async fn repair_chart(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &(dyn Chart + Send + Sync),
+    mismatches: &[Mismatch],
+) -> Result<(), UpdateError> {
+    let mutex = get_global_update_mutex(&chain::current(), chart.name()).await;
+    let _permit = mutex.lock().await;
+    let chart_id = find_chart(db, chart.name())
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or_else(|| UpdateError::NotFound(chart.name().into()))?;
+    let min_blockscout_block = get_min_block_blockscout(blockscout)
+        .await
+        .map_err(UpdateError::BlockscoutDB)?;
+
+    let removed: Vec<_> = mismatches
+        .iter()
+        .filter(|mismatch| mismatch.recalculated.is_none())
+        .map(|mismatch| mismatch.date)
+        .collect();
+    let values: Vec<_> = mismatches
+        .iter()
+        .filter_map(|mismatch| {
+            mismatch.recalculated.as_ref().map(|value| {
+                DateValue {
+                    date: mismatch.date,
+                    value: value.clone(),
+                }
+                .active_model(chart_id, Some(min_blockscout_block))
+            })
+        })
+        .collect();
+
+    let txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+    if !removed.is_empty() {
+        chart_data::Entity::delete_many()
+            .filter(chart_data::Column::ChartId.eq(chart_id))
+            .filter(chart_data::Column::Resolution.eq(ChartResolution::Day))
+            .filter(chart_data::Column::Series.eq(""))
+            .filter(chart_data::Column::Date.is_in(removed))
+            .exec(&txn)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+    }
+    insert_data_many(&txn, values)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    txn.commit().await.map_err(UpdateError::StatsDB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lines::NewBlocks,
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+    };
+    use pretty_assertions::assert_eq;
+    use sea_orm::{sea_query::Expr, Set};
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::from_str(s).expect("cannot parse date")
+    }
+
+    #[test]
+    fn sample_dates_works() {
+        assert_eq!(
+            vec![d("2022-11-09"), d("2022-11-10"), d("2022-11-11")],
+            sample_dates(d("2022-11-09"), d("2022-11-11"), 5)
+        );
+        assert_eq!(
+            vec![d("2022-11-01"), d("2022-11-05"), d("2022-11-10")],
+            sample_dates(d("2022-11-01"), d("2022-11-10"), 3)
+        );
+        assert_eq!(
+            vec![d("2022-11-10")],
+            sample_dates(d("2022-11-01"), d("2022-11-10"), 1)
+        );
+        assert_eq!(
+            Vec::<NaiveDate>::new(),
+            sample_dates(d("2022-11-10"), d("2022-11-01"), 3)
+        );
+    }
+
+    #[test]
+    fn values_equal_works() {
+        assert!(values_equal("1.50", "1.5"));
+        assert!(values_equal("0.30000000000000004", "0.3"));
+        assert!(values_equal("abc", "abc"));
+        assert!(!values_equal("1", "2"));
+        assert!(!values_equal("abc", "1"));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn audit_and_repair_chart() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("audit_and_repair_chart", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        let chart = NewBlocks::default();
+        chart.create(&db).await.unwrap();
+        chart.update(&db, &blockscout, true).await.unwrap();
+        let dates = sample_dates(d("2022-11-09"), d("2022-11-12"), 10);
+        let mismatches = audit_chart(&db, &blockscout, &chart, &dates).await.unwrap();
+        assert_eq!(Some(vec![]), mismatches);
+
+        chart_data::Entity::update_many()
+            .col_expr(chart_data::Column::Value, Expr::value("100"))
+            .filter(chart_data::Column::Date.eq(d("2022-11-10")))
+            .exec(&db)
+            .await
+            .unwrap();
+        chart_data::Entity::delete_many()
+            .filter(chart_data::Column::Date.eq(d("2022-11-11")))
+            .exec(&db)
+            .await
+            .unwrap();
+        chart_data::Entity::insert(chart_data::ActiveModel {
+            chart_id: Set(1),
+            date: Set(d("2022-11-13")),
+            value: Set("5".into()),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+        let dates = sample_dates(d("2022-11-09"), d("2022-11-13"), 10);
+        let mismatches = audit_chart(&db, &blockscout, &chart, &dates)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![
+                Mismatch {
+                    date: d("2022-11-10"),
+                    stored: Some("100".into()),
+                    recalculated: Some("3".into()),
+                },
+                Mismatch {
+                    date: d("2022-11-11"),
+                    stored: None,
+                    recalculated: Some("4".into()),
+                },
+                Mismatch {
+                    date: d("2022-11-13"),
+                    stored: Some("5".into()),
+                    recalculated: None,
+                },
+            ],
+            mismatches
+        );
+
+        repair_chart(&db, &blockscout, &chart, &mismatches)
+            .await
+            .unwrap();
+        let mismatches = audit_chart(&db, &blockscout, &chart, &dates).await.unwrap();
+        assert_eq!(Some(vec![]), mismatches);
+    }
+}
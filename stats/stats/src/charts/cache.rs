@@ -1,3 +1,4 @@
+use super::timezone::{self, Timezone};
 use std::{future::Future, sync::Arc};
 use tokio::sync::Mutex;
 
@@ -5,12 +6,15 @@ use tokio::sync::Mutex;
 struct CacheData<T> {
     data: Option<T>,
     version: u64,
+    /// Timezone of the update that calculated the data
+    timezone: Timezone,
 }
 
 impl<T> CacheData<T> {
-    fn update(&mut self, data: T) {
+    fn update(&mut self, data: T, timezone: Timezone) {
         self.data = Some(data);
         self.version += 1;
+        self.timezone = timezone;
     }
 }
 
@@ -25,18 +29,44 @@ impl<T: Clone> Cache<T> {
         &mut self,
         updater: F,
     ) -> Result<T, E> {
+        let timezone = timezone::current();
         let mut cache = self.data.lock().await;
         let data = match cache.data.as_ref() {
-            Some(data) if cache.version > self.last_version => data.clone(),
+            Some(data) if cache.version > self.last_version && cache.timezone == timezone => {
+                data.clone()
+            }
             _ => {
                 let new_data = updater.await?;
-                cache.update(new_data.clone());
+                cache.update(new_data.clone(), timezone);
                 new_data
             }
         };
         self.last_version = cache.version;
         Ok(data)
     }
+
+    /// Returns data calculated for `version` or a later one, calling `updater`
+    /// only if the cached data is older. Versions are assigned by the caller,
+    /// so all handles asking for the same version share one calculation.
+    ///
+    /// Shouldn't be mixed with [`Cache::get_or_update`] on the same cache
+    pub async fn get_or_update_version<E, F: Future<Output = Result<T, E>>>(
+        &self,
+        version: u64,
+        updater: F,
+    ) -> Result<T, E> {
+        let mut cache = self.data.lock().await;
+        match cache.data.as_ref() {
+            Some(data) if cache.version >= version => Ok(data.clone()),
+            _ => {
+                let new_data = updater.await?;
+                cache.data = Some(new_data.clone());
+                cache.version = version;
+                cache.timezone = timezone::current();
+                Ok(new_data)
+            }
+        }
+    }
 }
 
 impl<T> Clone for Cache<T> {
@@ -91,4 +121,52 @@ mod tests {
         );
         assert_eq!(Ok(5), baz.get_or_update(async move { value(5) }).await);
     }
+
+    #[tokio::test]
+    async fn updates_once_per_version() {
+        let foo: Cache<u32> = Cache::default();
+        let bar = foo.clone();
+
+        assert_eq!(
+            Ok(1),
+            foo.get_or_update_version(0, async move { value(1) }).await
+        );
+        assert_eq!(
+            Ok(1),
+            bar.get_or_update_version(0, async move { assert_not_called() })
+                .await
+        );
+
+        assert_eq!(
+            Ok(2),
+            bar.get_or_update_version(2, async move { value(2) }).await
+        );
+        assert_eq!(
+            Ok(2),
+            foo.get_or_update_version(1, async move { assert_not_called() })
+                .await
+        );
+        assert_eq!(
+            Ok(2),
+            foo.get_or_update_version(2, async move { assert_not_called() })
+                .await
+        );
+        assert_eq!(
+            Ok(3),
+            foo.get_or_update_version(3, async move { value(3) }).await
+        );
+    }
+
+    #[tokio::test]
+    async fn recalculates_for_other_timezone() {
+        let mut foo: Cache<u32> = Cache::default();
+        let mut bar = foo.clone();
+        let timezone: Timezone = "+03:00".parse().unwrap();
+
+        assert_eq!(Ok(1), foo.get_or_update(async move { value(1) }).await);
+        assert_eq!(
+            Ok(2),
+            timezone::scope(timezone, bar.get_or_update(async move { value(2) })).await
+        );
+    }
 }
@@ -0,0 +1,16 @@
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+/// Runs chart update of the chain, see [`current`]
+pub async fn scope<F: Future>(chain_id: String, f: F) -> F::Output {
+    CURRENT.scope(chain_id, f).await
+}
+
+/// Id of the chain of the running chart update, empty outside of [`scope`].
+/// Separates update mutexes and metrics of charts with the same name in different chains
+pub fn current() -> String {
+    CURRENT.try_with(Clone::clone).unwrap_or_default()
+}
@@ -1,10 +1,19 @@
-use super::mutex::get_global_update_mutex;
-use crate::ReadError;
+use super::{
+    chain,
+    mutex::get_global_update_mutex,
+    reorg::{clear_invalidation, get_invalidation},
+    resolution::Resolution,
+};
+use crate::{DateValue, ReadError};
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
 use entity::{charts, sea_orm_active_enums::ChartType};
-use sea_orm::{prelude::*, sea_query, FromQueryResult, QuerySelect, Set};
+use sea_orm::{prelude::*, sea_query, sea_query::Expr, FromQueryResult, QuerySelect, Set};
+use std::sync::Arc;
 use thiserror::Error;
 
+pub type ArcChart = Arc<dyn Chart + Send + Sync + 'static>;
+
 #[derive(Error, Debug)]
 pub enum UpdateError {
     #[error("blockscout database error: {0}")]
@@ -31,6 +40,30 @@ pub trait Chart: Sync {
     fn name(&self) -> &str;
     fn chart_type(&self) -> ChartType;
 
+    /// Resolutions the chart is materialized with.
+    /// Every chart is materialized with [`Resolution::Day`].
+    fn resolutions(&self) -> &[Resolution] {
+        &[Resolution::Day]
+    }
+
+    /// Named series stored besides the main value of every point.
+    /// Series are materialized only with [`Resolution::Day`].
+    fn series(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Charts which data is used to calculate the chart.
+    /// They are not updated by the chart itself and must be updated before it
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![]
+    }
+
+    /// Whether dates of the chart depend on the timezone of its update.
+    /// Charts in any timezone may depend on charts that don't use timezone
+    fn uses_timezone(&self) -> bool {
+        true
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         create_chart(db, self.name().into(), self.chart_type()).await
     }
@@ -42,6 +75,16 @@ pub trait Chart: Sync {
         force_full: bool,
     ) -> Result<(), UpdateError>;
 
+    /// Daily values of the dates calculated from blockscout without saving them.
+    /// `None` if the chart is not calculated directly from blockscout
+    async fn recalculate(
+        &self,
+        _blockscout: &DatabaseConnection,
+        _dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        Ok(None)
+    }
+
     async fn update_with_mutex(
         &self,
         db: &DatabaseConnection,
@@ -49,7 +92,7 @@ pub trait Chart: Sync {
         force_full: bool,
     ) -> Result<(), UpdateError> {
         let name = self.name();
-        let mutex = get_global_update_mutex(name).await;
+        let mutex = get_global_update_mutex(&chain::current(), name).await;
         let _permit = {
             match mutex.try_lock() {
                 Ok(v) => v,
@@ -62,7 +105,19 @@ pub trait Chart: Sync {
                 }
             }
         };
-        self.update(db, blockscout, force_full).await
+        // reorg checks don't take the mutex, so the points
+        // invalidated during the update must stay invalidated
+        let invalidation = get_invalidation(db, name)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        self.update(db, blockscout, force_full).await?;
+        // points invalidated before the update are recalculated now
+        if let Some(invalidation) = invalidation {
+            clear_invalidation(db, name, &invalidation)
+                .await
+                .map_err(UpdateError::StatsDB)?;
+        }
+        Ok(())
     }
 }
 
@@ -104,3 +159,43 @@ pub async fn create_chart(
     .await?;
     Ok(())
 }
+
+/// Records successful update of the chart, clearing previous error
+pub async fn set_update_succeeded(db: &DatabaseConnection, name: &str) -> Result<(), DbErr> {
+    charts::Entity::update_many()
+        .col_expr(
+            charts::Column::LastUpdatedAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .col_expr(
+            charts::Column::LastError,
+            Expr::value(Option::<String>::None),
+        )
+        .col_expr(
+            charts::Column::LastErrorAt,
+            Expr::value(Option::<NaiveDateTime>::None),
+        )
+        .filter(charts::Column::Name.eq(name))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Records error of failed or cancelled update.
+/// Time of the last successful update is kept
+pub async fn set_update_failed(
+    db: &DatabaseConnection,
+    name: &str,
+    error: &str,
+) -> Result<(), DbErr> {
+    charts::Entity::update_many()
+        .col_expr(charts::Column::LastError, Expr::value(error))
+        .col_expr(
+            charts::Column::LastErrorAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(charts::Column::Name.eq(name))
+        .exec(db)
+        .await?;
+    Ok(())
+}
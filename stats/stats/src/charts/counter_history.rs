@@ -0,0 +1,122 @@
+use super::{
+    find_chart,
+    insert::{insert_data_many, TimespanValue},
+    resolution::Resolution,
+    timezone::Timezone,
+    UpdateError,
+};
+use crate::get_last_chart_data;
+use chrono::Duration;
+use entity::{chart_data, sea_orm_active_enums::ChartResolution};
+use sea_orm::prelude::*;
+
+/// Saves the last value of the counter as the point of the current hour of the timezone.
+///
+/// Counters keep one point per day, which is overwritten by every update of the day,
+/// so hourly points keep the history of the counter within a day.
+pub async fn save_counter_snapshot(
+    db: &DatabaseConnection,
+    name: &str,
+    timezone: Timezone,
+) -> Result<(), UpdateError> {
+    let chart_id = find_chart(db, name)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or_else(|| UpdateError::NotFound(name.into()))?;
+    let last = match get_last_chart_data(db, name, 1).await?.pop() {
+        Some(last) => last,
+        None => return Ok(()),
+    };
+    let snapshot = TimespanValue {
+        timespan: Resolution::Hour.period_start(timezone.now()),
+        value: last.value,
+    };
+    insert_data_many(
+        db,
+        [snapshot.active_model(chart_id, Resolution::Hour, None)],
+    )
+    .await
+    .map_err(UpdateError::StatsDB)
+}
+
+/// Removes hourly points of the counter older than `retention_days` days,
+/// so only daily points are left for older dates. Returns number of removed points
+pub async fn compact_counter_history(
+    db: &DatabaseConnection,
+    name: &str,
+    timezone: Timezone,
+    retention_days: u32,
+) -> Result<u64, UpdateError> {
+    let chart_id = find_chart(db, name)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or_else(|| UpdateError::NotFound(name.into()))?;
+    let oldest_kept = timezone.today() - Duration::days(retention_days.into());
+    let result = chart_data::Entity::delete_many()
+        .filter(chart_data::Column::ChartId.eq(chart_id))
+        .filter(chart_data::Column::Resolution.eq(ChartResolution::Hour))
+        .filter(chart_data::Column::Date.lt(oldest_kept))
+        .exec(db)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        counters::TotalBlocks,
+        get_chart_data_with_resolution,
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        Chart,
+    };
+    use chrono::NaiveDateTime;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn save_and_compact_counter_history() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("save_and_compact_counter_history", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        let chart = TotalBlocks::default();
+        chart.create(&db).await.unwrap();
+        chart.update(&db, &blockscout, true).await.unwrap();
+
+        let timezone = Timezone::utc();
+        save_counter_snapshot(&db, chart.name(), timezone)
+            .await
+            .unwrap();
+        let old = TimespanValue {
+            timespan: NaiveDateTime::from_str("2022-11-10T05:00:00").unwrap(),
+            value: "3".into(),
+        };
+        let chart_id = find_chart(&db, chart.name()).await.unwrap().unwrap();
+        insert_data_many(&db, [old.active_model(chart_id, Resolution::Hour, None)])
+            .await
+            .unwrap();
+
+        let history =
+            get_chart_data_with_resolution(&db, chart.name(), Resolution::Hour, None, None)
+                .await
+                .unwrap();
+        assert_eq!(2, history.len());
+        assert_eq!(old, history[0]);
+        assert_eq!(
+            Resolution::Hour.period_start(timezone.now()),
+            history[1].timespan
+        );
+
+        let removed = compact_counter_history(&db, chart.name(), timezone, 30)
+            .await
+            .unwrap();
+        assert_eq!(1, removed);
+        let history =
+            get_chart_data_with_resolution(&db, chart.name(), Resolution::Hour, None, None)
+                .await
+                .unwrap();
+        assert_eq!(1, history.len());
+    }
+}
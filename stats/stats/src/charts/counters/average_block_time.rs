@@ -1,6 +1,7 @@
 use crate::{
     charts::{
         insert::{DateValue, DateValueDouble},
+        timezone,
         updater::ChartFullUpdater,
     },
     UpdateError,
@@ -18,11 +19,13 @@ impl ChartFullUpdater for AverageBlockTime {
         &self,
         blockscout: &DatabaseConnection,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("timestamp");
         let item = DateValueDouble::find_by_statement(Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"
+            &format!(
+                r#"
             SELECT
-                max(timestamp)::date as date, 
+                max({timestamp})::date as date, 
                 (CASE WHEN avg(diff) IS NULL THEN 0 ELSE avg(diff) END)::float as value
             FROM
             (
@@ -34,7 +37,8 @@ impl ChartFullUpdater for AverageBlockTime {
                 FROM "blocks"
                 WHERE consensus = true
             ) t
-            "#,
+            "#
+            ),
             vec![],
         ))
         .one(blockscout)
@@ -1,5 +1,5 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartFullUpdater},
+    charts::{insert::DateValue, timezone, updater::ChartFullUpdater},
     UpdateError,
 };
 use async_trait::async_trait;
@@ -23,9 +23,10 @@ impl ChartFullUpdater for CompletedTxns {
         // since amount of dropped txns (b.consensus = false) is very small,
         // the second query will execute very quickly.
         // also we need last date of block, that's why 3rd query is needed
+        let timestamp = timezone::current().local_sql("b.timestamp");
         let data = DateValue::find_by_statement(Statement::from_string(
             DbBackend::Postgres,
-            r#"SELECT (all_success - all_success_dropped)::TEXT AS value, last_block_date AS date 
+            format!(r#"SELECT (all_success - all_success_dropped)::TEXT AS value, last_block_date AS date 
             FROM (
                 SELECT (
                     SELECT COUNT(*) AS all_success
@@ -37,12 +38,11 @@ impl ChartFullUpdater for CompletedTxns {
                     JOIN blocks b ON t.block_hash = b.hash
                     WHERE t.status = 1 AND b.consensus = false
                 ), (
-                    SELECT MAX(b.timestamp)::DATE AS last_block_date
+                    SELECT MAX({timestamp})::DATE AS last_block_date
                     FROM blocks b
                     WHERE b.consensus = true
                 )
-            ) AS sub"#
-                .into(),
+            ) AS sub"#),
         ))
         .one(blockscout)
         .await
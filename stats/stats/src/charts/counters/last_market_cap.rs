@@ -0,0 +1,84 @@
+use crate::{
+    charts::{
+        create_chart,
+        insert::DateValue,
+        updater::{last_point, ChartDependentUpdater},
+        ArcChart,
+    },
+    lines::MarketCap,
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Market cap of the last day with a price
+#[derive(Default)]
+pub struct LastMarketCap {
+    parent: Arc<MarketCap>,
+}
+
+impl LastMarketCap {
+    pub fn new(parent: Arc<MarketCap>) -> Self {
+        Self { parent }
+    }
+}
+
+#[async_trait]
+impl ChartDependentUpdater<MarketCap> for LastMarketCap {
+    fn parent(&self) -> Arc<MarketCap> {
+        self.parent.clone()
+    }
+
+    async fn get_values(
+        &self,
+        _last_row: Option<DateValue>,
+        parent_data: Vec<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let last = last_point(parent_data);
+        Ok(last.into_iter().collect())
+    }
+}
+
+#[async_trait]
+impl crate::Chart for LastMarketCap {
+    fn name(&self) -> &str {
+        "lastMarketCap"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Counter
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.parent.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_counter;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_last_market_cap() {
+        let counter = LastMarketCap::default();
+        simple_test_counter("update_last_market_cap", counter, "12000").await;
+    }
+}
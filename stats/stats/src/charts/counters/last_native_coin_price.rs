@@ -0,0 +1,84 @@
+use crate::{
+    charts::{
+        create_chart,
+        insert::DateValue,
+        updater::{last_point, ChartDependentUpdater},
+        ArcChart,
+    },
+    lines::NativeCoinPrice,
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Last known closing price of the native coin
+#[derive(Default)]
+pub struct LastNativeCoinPrice {
+    parent: Arc<NativeCoinPrice>,
+}
+
+impl LastNativeCoinPrice {
+    pub fn new(parent: Arc<NativeCoinPrice>) -> Self {
+        Self { parent }
+    }
+}
+
+#[async_trait]
+impl ChartDependentUpdater<NativeCoinPrice> for LastNativeCoinPrice {
+    fn parent(&self) -> Arc<NativeCoinPrice> {
+        self.parent.clone()
+    }
+
+    async fn get_values(
+        &self,
+        _last_row: Option<DateValue>,
+        parent_data: Vec<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let last = last_point(parent_data);
+        Ok(last.into_iter().collect())
+    }
+}
+
+#[async_trait]
+impl crate::Chart for LastNativeCoinPrice {
+    fn name(&self) -> &str {
+        "lastNativeCoinPrice"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Counter
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.parent.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_counter;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_last_native_coin_price() {
+        let counter = LastNativeCoinPrice::default();
+        simple_test_counter("update_last_native_coin_price", counter, "3").await;
+    }
+}
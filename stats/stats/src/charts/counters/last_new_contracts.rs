@@ -3,6 +3,7 @@ use crate::{
         create_chart,
         insert::DateValue,
         updater::{last_point, ChartDependentUpdater},
+        ArcChart,
     },
     lines::NewContracts,
     UpdateError,
@@ -29,7 +30,11 @@ impl ChartDependentUpdater<NewContracts> for LastNewContracts {
         self.parent.clone()
     }
 
-    async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError> {
+    async fn get_values(
+        &self,
+        _last_row: Option<DateValue>,
+        parent_data: Vec<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
         let last = last_point(parent_data);
         Ok(last.into_iter().collect())
     }
@@ -45,6 +50,10 @@ impl crate::Chart for LastNewContracts {
         ChartType::Counter
     }
 
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -1,5 +1,7 @@
 mod average_block_time;
 mod completed_txns;
+mod last_market_cap;
+mod last_native_coin_price;
 mod last_new_contracts;
 mod last_new_verified_contracts;
 mod mock;
@@ -14,6 +16,8 @@ mod total_verified_contracts;
 
 pub use average_block_time::AverageBlockTime;
 pub use completed_txns::CompletedTxns;
+pub use last_market_cap::LastMarketCap;
+pub use last_native_coin_price::LastNativeCoinPrice;
 pub use last_new_contracts::LastNewContracts;
 pub use last_new_verified_contracts::LastNewVerifiedContracts;
 pub use mock::MockCounter;
@@ -1,5 +1,5 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartFullUpdater},
+    charts::{insert::DateValue, timezone, updater::ChartFullUpdater},
     UpdateError,
 };
 use async_trait::async_trait;
@@ -35,7 +35,7 @@ impl ChartFullUpdater for TotalBlocks {
             .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
         let data = DateValue {
-            date: data.timestamp.date(),
+            date: timezone::current().local_time(data.timestamp).date(),
             value: data.number.to_string(),
         };
         Ok(vec![data])
@@ -2,7 +2,8 @@ use crate::{
     charts::{
         create_chart,
         insert::DateValue,
-        updater::{parse_and_sum, ChartDependentUpdater},
+        updater::{parse_and_growth, ChartDependentUpdater},
+        ArcChart,
     },
     lines::NewNativeCoinTransfers,
     Chart, UpdateError,
@@ -29,9 +30,12 @@ impl ChartDependentUpdater<NewNativeCoinTransfers> for TotalNativeCoinTransfers
         self.parent.clone()
     }
 
-    async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError> {
-        let sum = parse_and_sum::<i64>(parent_data, self.name(), self.parent.name())?;
-        Ok(sum.into_iter().collect())
+    async fn get_values(
+        &self,
+        last_row: Option<DateValue>,
+        parent_data: Vec<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        parse_and_growth::<i64>(parent_data, last_row, self.name(), self.parent.name())
     }
 }
 
@@ -45,6 +49,10 @@ impl crate::Chart for TotalNativeCoinTransfers {
         ChartType::Counter
     }
 
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
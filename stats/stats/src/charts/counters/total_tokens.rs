@@ -1,5 +1,5 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartFullUpdater},
+    charts::{insert::DateValue, timezone, updater::ChartFullUpdater},
     UpdateError,
 };
 use async_trait::async_trait;
@@ -15,21 +15,23 @@ impl ChartFullUpdater for TotalTokens {
         &self,
         blockscout: &DatabaseConnection,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("timestamp");
         let data = DateValue::find_by_statement(Statement::from_string(
             DbBackend::Postgres,
-            r#"
+            format!(
+                r#"
             SELECT 
                 (
                     SELECT count(*)::text
                         FROM tokens
                 ) AS "value",
                 (
-                    SELECT max(timestamp)::date as "date" 
+                    SELECT max({timestamp})::date as "date" 
                         FROM blocks
                         WHERE blocks.consensus = true
                 ) AS "date"
             "#
-            .into(),
+            ),
         ))
         .one(blockscout)
         .await
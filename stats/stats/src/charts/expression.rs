@@ -0,0 +1,708 @@
+use crate::{
+    charts::{
+        create_chart, find_chart,
+        insert::{insert_data_many, DateValue},
+        updater::{get_last_row, get_min_block_blockscout},
+        ArcChart,
+    },
+    get_chart_data, get_chart_start_date, Chart, Resolution, UpdateError,
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+const CUMSUM_FUNCTION: &str = "cumsum";
+const MOVING_AVERAGE_PREFIX: &str = "ma";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ExpressionError {
+    #[error("invalid expression of chart '{name}': {message}")]
+    Syntax { name: String, message: String },
+    #[error("expression of chart '{name}' refers to unknown chart '{chart}'")]
+    UnknownChart { name: String, chart: String },
+    #[error("expression of chart '{0}' doesn't refer to any chart")]
+    NoCharts(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    /// `None` if the result is not a finite number, e.g. on division by zero
+    fn apply(&self, a: f64, b: f64) -> Option<f64> {
+        let value = match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+        };
+        value.is_finite().then_some(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Chart(String),
+    Number(f64),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    /// Average of points of the last `n` days
+    MovingAverage(u32, Box<Expr>),
+    CumSum(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Symbol(char),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&c) = chars
+                .peek()
+                .filter(|c| c.is_ascii_alphanumeric() || **c == '_')
+            {
+                ident.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Ident(ident));
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                number.push(c);
+                chars.next();
+            }
+            let number = number
+                .parse()
+                .map_err(|_| format!("invalid number '{number}'"))?;
+            tokens.push(Token::Number(number));
+        } else if "+-*/()".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected character '{c}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser, `*` and `/` bind tighter than `+` and `-`
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{symbol}'"))
+        }
+    }
+
+    fn parse(mut self) -> Result<Expr, String> {
+        let expr = self.sum()?;
+        match self.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {token:?}")),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut expr = self.product()?;
+        loop {
+            let op = if self.eat('+') {
+                Op::Add
+            } else if self.eat('-') {
+                Op::Sub
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                Op::Mul
+            } else if self.eat('/') {
+                Op::Div
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Expr::Number(number)),
+            Some(Token::Symbol('(')) => {
+                let expr = self.sum()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) if self.eat('(') => {
+                let arg = Box::new(self.sum()?);
+                self.expect(')')?;
+                function(&ident, arg)
+            }
+            Some(Token::Ident(ident)) => Ok(Expr::Chart(ident)),
+            Some(token) => Err(format!("unexpected {token:?}")),
+            None => Err("unexpected end of expression".into()),
+        }
+    }
+}
+
+fn function(name: &str, arg: Box<Expr>) -> Result<Expr, String> {
+    if name == CUMSUM_FUNCTION {
+        return Ok(Expr::CumSum(arg));
+    }
+    match name
+        .strip_prefix(MOVING_AVERAGE_PREFIX)
+        .and_then(|days| days.parse::<u32>().ok())
+    {
+        Some(days) if days > 0 => Ok(Expr::MovingAverage(days, arg)),
+        _ => Err(format!("unknown function '{name}'")),
+    }
+}
+
+/// Values of an expression: a constant or points of dates
+#[derive(Debug, Clone, PartialEq)]
+enum Values {
+    Const(f64),
+    Points(BTreeMap<NaiveDate, f64>),
+}
+
+impl Expr {
+    fn charts<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Expr::Chart(name) => {
+                if !names.contains(&name.as_str()) {
+                    names.push(name)
+                }
+            }
+            Expr::Number(_) => {}
+            Expr::Neg(arg) | Expr::MovingAverage(_, arg) | Expr::CumSum(arg) => arg.charts(names),
+            Expr::Binary(_, left, right) => {
+                left.charts(names);
+                right.charts(names);
+            }
+        }
+    }
+
+    /// Number of days before a date, which points are needed to calculate the date.
+    /// `None` if the whole history is needed
+    fn lookback_days(&self) -> Option<i64> {
+        match self {
+            Expr::Chart(_) | Expr::Number(_) => Some(0),
+            Expr::Neg(arg) => arg.lookback_days(),
+            Expr::Binary(_, left, right) => Some(left.lookback_days()?.max(right.lookback_days()?)),
+            Expr::MovingAverage(days, arg) => Some(arg.lookback_days()? + i64::from(*days) - 1),
+            Expr::CumSum(_) => None,
+        }
+    }
+
+    /// Binary operations are calculated for dates present in both operands,
+    /// dates where the result is not a finite number are skipped.
+    /// `start` is the first date of `data` if charts have points before it
+    fn evaluate(
+        &self,
+        data: &HashMap<String, BTreeMap<NaiveDate, f64>>,
+        start: Option<NaiveDate>,
+    ) -> Values {
+        match self {
+            Expr::Chart(name) => Values::Points(data.get(name).cloned().unwrap_or_default()),
+            Expr::Number(number) => Values::Const(*number),
+            Expr::Neg(arg) => match arg.evaluate(data, start) {
+                Values::Const(value) => Values::Const(-value),
+                Values::Points(points) => Values::Points(
+                    points
+                        .into_iter()
+                        .map(|(date, value)| (date, -value))
+                        .collect(),
+                ),
+            },
+            Expr::Binary(op, left, right) => {
+                match (left.evaluate(data, start), right.evaluate(data, start)) {
+                    (Values::Const(a), Values::Const(b)) => match op.apply(a, b) {
+                        Some(value) => Values::Const(value),
+                        None => Values::Points(BTreeMap::new()),
+                    },
+                    (Values::Points(points), Values::Const(b)) => Values::Points(
+                        points
+                            .into_iter()
+                            .filter_map(|(date, a)| op.apply(a, b).map(|value| (date, value)))
+                            .collect(),
+                    ),
+                    (Values::Const(a), Values::Points(points)) => Values::Points(
+                        points
+                            .into_iter()
+                            .filter_map(|(date, b)| op.apply(a, b).map(|value| (date, value)))
+                            .collect(),
+                    ),
+                    (Values::Points(left), Values::Points(right)) => Values::Points(
+                        left.into_iter()
+                            .filter_map(|(date, a)| {
+                                let b = right.get(&date)?;
+                                op.apply(a, *b).map(|value| (date, value))
+                            })
+                            .collect(),
+                    ),
+                }
+            }
+            Expr::MovingAverage(days, arg) => match arg.evaluate(data, start) {
+                Values::Const(value) => Values::Const(value),
+                Values::Points(points) => {
+                    let filled = fill_gaps(&points, start);
+                    let days = *days as usize;
+                    let mut sum = 0.0;
+                    Values::Points(
+                        filled
+                            .iter()
+                            .enumerate()
+                            .map(|(i, (date, value))| {
+                                sum += value;
+                                if i >= days {
+                                    sum -= filled[i - days].1;
+                                }
+                                // window is shorter only before the first point
+                                (*date, sum / (i + 1).min(days) as f64)
+                            })
+                            .collect(),
+                    )
+                }
+            },
+            Expr::CumSum(arg) => match arg.evaluate(data, start) {
+                Values::Const(value) => Values::Const(value),
+                Values::Points(points) => {
+                    let mut sum = 0.0;
+                    Values::Points(
+                        points
+                            .into_iter()
+                            .map(|(date, value)| {
+                                sum += value;
+                                (date, sum)
+                            })
+                            .collect(),
+                    )
+                }
+            },
+        }
+    }
+}
+
+/// Points of all dates from the first point (or `start`) to the last point,
+/// missing dates have zero value
+fn fill_gaps(points: &BTreeMap<NaiveDate, f64>, start: Option<NaiveDate>) -> Vec<(NaiveDate, f64)> {
+    let (mut date, last) = match (points.keys().next(), points.keys().next_back()) {
+        (Some(first), Some(last)) => (start.map_or(*first, |start| start.min(*first)), *last),
+        _ => return Vec::new(),
+    };
+    let mut filled = Vec::new();
+    while date <= last {
+        filled.push((date, points.get(&date).copied().unwrap_or_default()));
+        date += Duration::days(1);
+    }
+    filled
+}
+
+/// Line chart calculated from other charts with an expression from config.
+///
+/// Expression consists of chart names, numbers, `+ - * /` operators, parentheses
+/// and functions `cumsum(x)` (running total) and `maN(x)` (average of the last `N` days,
+/// missing days are zero), e.g. `ma7(newTxns)` or `txnsFee / newTxns`.
+/// Charts of the expression are updated before it, see [`Chart::dependencies`].
+/// Points starting from the last saved one are recalculated, so only these points
+/// and `N - 1` days before them are read, expressions with `cumsum` read the whole history.
+pub struct ExpressionChart {
+    name: String,
+    expression: Expr,
+    parents: Vec<ArcChart>,
+}
+
+impl ExpressionChart {
+    /// `find_chart` returns chart by its name, the chart may be not enabled itself
+    pub fn new(
+        name: String,
+        expression: &str,
+        find_chart: impl Fn(&str) -> Option<ArcChart>,
+    ) -> Result<Self, ExpressionError> {
+        let expression = tokenize(expression)
+            .and_then(|tokens| Parser { tokens, pos: 0 }.parse())
+            .map_err(|message| ExpressionError::Syntax {
+                name: name.clone(),
+                message,
+            })?;
+        let mut names = Vec::new();
+        expression.charts(&mut names);
+        if names.is_empty() {
+            return Err(ExpressionError::NoCharts(name));
+        }
+        let parents = names
+            .into_iter()
+            .map(|chart| {
+                find_chart(chart).ok_or_else(|| ExpressionError::UnknownChart {
+                    name: name.clone(),
+                    chart: chart.to_owned(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            name,
+            expression,
+            parents,
+        })
+    }
+
+    /// Values of the dates starting from `from`, all values if it's `None`
+    async fn get_values(
+        &self,
+        db: &DatabaseConnection,
+        from: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let read_from = from
+            .zip(self.expression.lookback_days())
+            .map(|(from, days)| from - Duration::days(days));
+        // days before the first read date are counted by functions
+        // only if the charts have points of these days
+        let mut start = None;
+        if let Some(read_from) = read_from {
+            for parent in self.parents.iter() {
+                let parent_start = get_chart_start_date(db, parent.name(), Resolution::Day).await?;
+                if parent_start.map_or(false, |parent_start| parent_start < read_from) {
+                    start = Some(read_from);
+                }
+            }
+        }
+        let mut data = HashMap::new();
+        for parent in self.parents.iter() {
+            let points = get_chart_data(db, parent.name(), read_from, None)
+                .await?
+                .into_iter()
+                .map(|point| {
+                    let value = point.value.parse::<f64>().map_err(|e| {
+                        UpdateError::Internal(format!(
+                            "failed to parse values in chart '{}': {e}",
+                            parent.name()
+                        ))
+                    })?;
+                    Ok((point.date, value))
+                })
+                .collect::<Result<_, UpdateError>>()?;
+            data.insert(parent.name().to_owned(), points);
+        }
+        let points = match self.expression.evaluate(&data, start) {
+            Values::Points(points) => points,
+            Values::Const(_) => BTreeMap::new(),
+        };
+        Ok(points
+            .into_iter()
+            .filter(|(date, _)| from.map_or(true, |from| *date >= from))
+            .map(|(date, value)| DateValue {
+                date,
+                value: value.to_string(),
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Chart for ExpressionChart {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        self.parents.clone()
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        for parent in self.parents.iter() {
+            parent.create(db).await?;
+        }
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let last_row = get_last_row(self, chart_id, min_blockscout_block, db, force_full).await?;
+        // the point of the last row is recalculated, it could be partial
+        let from = last_row.map(|row| row.date);
+        let values = self
+            .get_values(db, from)
+            .await?
+            .into_iter()
+            .map(|v| v.active_model(chart_id, Some(min_blockscout_block)));
+        insert_data_many(db, values)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lines::NewBlocks, tests::simple_test::simple_test_chart};
+    use pretty_assertions::assert_eq;
+    use std::{str::FromStr, sync::Arc};
+
+    fn parse(expression: &str) -> Result<Expr, String> {
+        tokenize(expression).and_then(|tokens| Parser { tokens, pos: 0 }.parse())
+    }
+
+    fn chart(name: &str) -> Box<Expr> {
+        Box::new(Expr::Chart(name.into()))
+    }
+
+    fn points(points: &[(&str, f64)]) -> BTreeMap<NaiveDate, f64> {
+        points
+            .iter()
+            .map(|(date, value)| (NaiveDate::from_str(date).unwrap(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn parse_works() {
+        assert_eq!(
+            Ok(Expr::MovingAverage(7, chart("newTxns"))),
+            parse("ma7(newTxns)")
+        );
+        assert_eq!(
+            Ok(Expr::Binary(
+                Op::Sub,
+                chart("a"),
+                Box::new(Expr::Binary(
+                    Op::Mul,
+                    chart("b"),
+                    Box::new(Expr::Neg(Box::new(Expr::Number(2.5))))
+                ))
+            )),
+            parse("a - b * -2.5")
+        );
+        assert_eq!(
+            Ok(Expr::Binary(
+                Op::Div,
+                Box::new(Expr::CumSum(Box::new(Expr::Binary(
+                    Op::Add,
+                    chart("a"),
+                    chart("b")
+                )))),
+                chart("c")
+            )),
+            parse(" cumsum( (a + b) ) / c ")
+        );
+        for expression in ["", "a +", "ma0(a)", "sum(a)", "(a", "a b", "a % b", "1..2"] {
+            assert!(parse(expression).is_err(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn new_checks_charts() {
+        let find =
+            |name: &str| (name == "newBlocks").then(|| Arc::new(NewBlocks::default()) as ArcChart);
+        let chart = ExpressionChart::new("a".into(), "newBlocks / 2 + newBlocks", find).unwrap();
+        assert_eq!(1, chart.dependencies().len());
+        assert_eq!(
+            Some(ExpressionError::UnknownChart {
+                name: "a".into(),
+                chart: "newTxns".into()
+            }),
+            ExpressionChart::new("a".into(), "newTxns + newBlocks", find).err()
+        );
+        assert_eq!(
+            Some(ExpressionError::NoCharts("a".into())),
+            ExpressionChart::new("a".into(), "1 + 2", find).err()
+        );
+    }
+
+    #[test]
+    fn evaluate_works() {
+        let data = HashMap::from([
+            (
+                "a".to_owned(),
+                points(&[
+                    ("2023-01-01", 2.0),
+                    ("2023-01-02", 4.0),
+                    ("2023-01-04", 6.0),
+                ]),
+            ),
+            (
+                "b".to_owned(),
+                points(&[
+                    ("2023-01-01", 1.0),
+                    ("2023-01-02", 0.0),
+                    ("2023-01-03", 5.0),
+                ]),
+            ),
+        ]);
+        for (expression, expected) in [
+            ("a / b", Values::Points(points(&[("2023-01-01", 2.0)]))),
+            (
+                "cumsum(a) - 1",
+                Values::Points(points(&[
+                    ("2023-01-01", 1.0),
+                    ("2023-01-02", 5.0),
+                    ("2023-01-04", 11.0),
+                ])),
+            ),
+            (
+                "ma2(a)",
+                Values::Points(points(&[
+                    ("2023-01-01", 2.0),
+                    ("2023-01-02", 3.0),
+                    ("2023-01-03", 2.0),
+                    ("2023-01-04", 3.0),
+                ])),
+            ),
+            ("2 * 3", Values::Const(6.0)),
+        ] {
+            assert_eq!(
+                expected,
+                parse(expression).unwrap().evaluate(&data, None),
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn moving_average_fills_gaps() {
+        let data = HashMap::from([(
+            "a".to_owned(),
+            points(&[
+                ("2023-01-01", 3.0),
+                ("2023-01-02", 3.0),
+                ("2023-01-03", 3.0),
+                ("2023-01-06", 6.0),
+            ]),
+        )]);
+        assert_eq!(
+            Values::Points(points(&[
+                ("2023-01-01", 3.0),
+                ("2023-01-02", 3.0),
+                ("2023-01-03", 3.0),
+                ("2023-01-04", 2.0),
+                ("2023-01-05", 1.0),
+                ("2023-01-06", 2.0),
+            ])),
+            parse("ma3(a)").unwrap().evaluate(&data, None)
+        );
+
+        // chart has points before the read ones
+        let start = NaiveDate::from_str("2022-12-31").ok();
+        assert_eq!(
+            Values::Points(points(&[
+                ("2022-12-31", 0.0),
+                ("2023-01-01", 1.5),
+                ("2023-01-02", 2.0),
+            ])),
+            parse("ma3(a)").unwrap().evaluate(
+                &HashMap::from([(
+                    "a".to_owned(),
+                    points(&[("2023-01-01", 3.0), ("2023-01-02", 3.0)])
+                )]),
+                start
+            )
+        );
+    }
+
+    #[test]
+    fn lookback_days_works() {
+        for (expression, expected) in [
+            ("a / b", Some(0)),
+            ("ma7(a) - ma3(ma2(b))", Some(6)),
+            ("-ma2(a) * 2", Some(1)),
+            ("ma7(cumsum(a))", None),
+        ] {
+            assert_eq!(
+                expected,
+                parse(expression).unwrap().lookback_days(),
+                "{expression}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_expression_chart() {
+        let find =
+            |name: &str| (name == "newBlocks").then(|| Arc::new(NewBlocks::default()) as ArcChart);
+        let chart = ExpressionChart::new("blocksGrowth".into(), "cumsum(newBlocks)", find).unwrap();
+        simple_test_chart(
+            "update_expression_chart",
+            chart,
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "4"),
+                ("2022-11-11", "8"),
+                ("2022-11-12", "9"),
+                ("2022-12-01", "10"),
+                ("2023-01-01", "11"),
+                ("2023-02-01", "12"),
+                ("2023-03-01", "13"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{charts::insert::DateValue, UpdateError};
+use chrono::NaiveDate;
+use sea_orm::prelude::Decimal;
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// What to do with points of days that have no price in `market_history`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MissingPrice {
+    /// Points without price of their day are not saved
+    #[default]
+    Skip,
+    /// The last known price before the day is used
+    CarryForward,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid missing price handling '{0}': expected 'skip' or 'carry_forward'")]
+pub struct ParseMissingPriceError(String);
+
+impl Display for MissingPrice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MissingPrice::Skip => f.write_str("skip"),
+            MissingPrice::CarryForward => f.write_str("carry_forward"),
+        }
+    }
+}
+
+impl FromStr for MissingPrice {
+    type Err = ParseMissingPriceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Ok(MissingPrice::Skip),
+            "carry_forward" => Ok(MissingPrice::CarryForward),
+            _ => Err(ParseMissingPriceError(s.to_owned())),
+        }
+    }
+}
+
+fn parse_decimal(point: &DateValue, chart_name: &str) -> Result<Decimal, UpdateError> {
+    Decimal::from_str(&point.value)
+        .or_else(|_| Decimal::from_scientific(&point.value))
+        .map_err(|e| {
+            UpdateError::Internal(format!(
+                "failed to parse values in chart '{chart_name}': {e}"
+            ))
+        })
+}
+
+/// Multiplies `data` by prices of the same days
+pub fn to_fiat(
+    data: Vec<DateValue>,
+    prices: Vec<DateValue>,
+    missing_price: MissingPrice,
+    chart_name: &str,
+) -> Result<Vec<DateValue>, UpdateError> {
+    let prices = prices
+        .into_iter()
+        .map(|point| Ok((point.date, parse_decimal(&point, chart_name)?)))
+        .collect::<Result<BTreeMap<NaiveDate, Decimal>, UpdateError>>()?;
+    let mut result = Vec::with_capacity(data.len());
+    for point in data {
+        let price = match missing_price {
+            MissingPrice::Skip => prices.get(&point.date),
+            MissingPrice::CarryForward => prices.range(..=point.date).next_back().map(|(_, p)| p),
+        };
+        let price = match price {
+            Some(price) => price,
+            None => continue,
+        };
+        let value = parse_decimal(&point, chart_name)?
+            .checked_mul(*price)
+            .ok_or_else(|| {
+                UpdateError::Internal(format!("overflow of values in chart '{chart_name}'"))
+            })?;
+        result.push(DateValue {
+            date: point.date,
+            value: value.normalize().to_string(),
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn points(points: &[(&str, &str)]) -> Vec<DateValue> {
+        points
+            .iter()
+            .map(|(date, value)| DateValue {
+                date: NaiveDate::from_str(date).unwrap(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_missing_price_works() {
+        for missing_price in [MissingPrice::Skip, MissingPrice::CarryForward] {
+            assert_eq!(
+                Ok(missing_price),
+                MissingPrice::from_str(&missing_price.to_string())
+            );
+        }
+        assert!(MissingPrice::from_str("zero").is_err());
+    }
+
+    #[test]
+    fn to_fiat_works() {
+        let data = points(&[
+            ("2022-11-08", "1"),
+            ("2022-11-09", "2"),
+            ("2022-11-10", "0.5"),
+            ("2022-11-12", "6666.666666666667"),
+        ]);
+        let prices = points(&[("2022-11-09", "1.5"), ("2022-11-10", "3")]);
+        assert_eq!(
+            points(&[("2022-11-09", "3"), ("2022-11-10", "1.5")]),
+            to_fiat(data.clone(), prices.clone(), MissingPrice::Skip, "test").unwrap()
+        );
+        assert_eq!(
+            points(&[
+                ("2022-11-09", "3"),
+                ("2022-11-10", "1.5"),
+                ("2022-11-12", "20000.000000000001"),
+            ]),
+            to_fiat(data, prices, MissingPrice::CarryForward, "test").unwrap()
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use std::num::ParseIntError;
 
-use chrono::{NaiveDate, Utc};
-use entity::chart_data;
+use super::{
+    resolution::{start_of_day, Resolution},
+    timezone::Timezone,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use entity::{chart_data, sea_orm_active_enums::ChartResolution};
 use sea_orm::{prelude::*, sea_query, ConnectionTrait, FromQueryResult, Set};
 
 #[derive(FromQueryResult, Debug, Clone)]
@@ -79,6 +83,9 @@ impl DateValue {
             value: Set(self.value.clone()),
             created_at: Default::default(),
             min_blockscout_block: Set(min_blockscout_block),
+            resolution: Set(ChartResolution::Day),
+            time: Set(NaiveTime::default()),
+            series: Set(String::new()),
         }
     }
 
@@ -89,8 +96,9 @@ impl DateValue {
         }
     }
 
-    pub fn relevant_or_zero(self) -> DateValue {
-        let today = Utc::now().date_naive();
+    /// Zero point of today if the point is older than today in the timezone
+    pub fn relevant_or_zero(self, timezone: Timezone) -> DateValue {
+        let today = timezone.today();
         if self.date < today {
             DateValue::zero(today)
         } else {
@@ -98,12 +106,116 @@ impl DateValue {
         }
     }
 
-    pub fn is_partial(&self) -> bool {
-        let today = Utc::now().date_naive();
+    pub fn is_partial(&self, timezone: Timezone) -> bool {
+        let today = timezone.today();
         self.date >= today
     }
 }
 
+/// Point of a chart materialized with some [`Resolution`],
+/// `timespan` is the start of the period.
+#[derive(FromQueryResult, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimespanValue {
+    pub timespan: NaiveDateTime,
+    pub value: String,
+}
+
+impl TimespanValue {
+    pub fn active_model(
+        &self,
+        chart_id: i32,
+        resolution: Resolution,
+        min_blockscout_block: Option<i64>,
+    ) -> chart_data::ActiveModel {
+        chart_data::ActiveModel {
+            id: Default::default(),
+            chart_id: Set(chart_id),
+            date: Set(self.timespan.date()),
+            value: Set(self.value.clone()),
+            created_at: Default::default(),
+            min_blockscout_block: Set(min_blockscout_block),
+            resolution: Set(resolution.into()),
+            time: Set(self.timespan.time()),
+            series: Set(String::new()),
+        }
+    }
+}
+
+impl From<DateValue> for TimespanValue {
+    fn from(value: DateValue) -> Self {
+        Self {
+            timespan: start_of_day(value.date),
+            value: value.value,
+        }
+    }
+}
+
+/// Daily points are identified by the date of the period start
+impl From<TimespanValue> for DateValue {
+    fn from(value: TimespanValue) -> Self {
+        Self {
+            date: value.timespan.date(),
+            value: value.value,
+        }
+    }
+}
+
+#[derive(FromQueryResult, Debug, Clone)]
+pub struct TimespanValueDouble {
+    pub timespan: NaiveDateTime,
+    pub value: f64,
+}
+
+impl From<TimespanValueDouble> for TimespanValue {
+    fn from(value: TimespanValueDouble) -> Self {
+        Self {
+            timespan: value.timespan,
+            value: value.value.to_string(),
+        }
+    }
+}
+
+/// Names of series stored by distribution charts
+pub const DISTRIBUTION_SERIES: [&str; 4] = ["p10", "p50", "p90", "max"];
+
+/// Percentiles of values of the date.
+/// Median is stored as the main value of the point
+#[derive(FromQueryResult, Debug, Clone)]
+pub struct DateDistribution {
+    pub date: NaiveDate,
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+impl DateDistribution {
+    pub fn active_models(
+        &self,
+        chart_id: i32,
+        min_blockscout_block: Option<i64>,
+    ) -> Vec<chart_data::ActiveModel> {
+        let main = DateValue {
+            date: self.date,
+            value: self.p50.to_string(),
+        }
+        .active_model(chart_id, min_blockscout_block);
+        let series = DISTRIBUTION_SERIES
+            .into_iter()
+            .zip([self.p10, self.p50, self.p90, self.max])
+            .map(|(name, value)| {
+                let mut model = DateValue {
+                    date: self.date,
+                    value: value.to_string(),
+                }
+                .active_model(chart_id, min_blockscout_block);
+                model.series = Set(name.to_owned());
+                model
+            });
+        std::iter::once(main).chain(series).collect()
+    }
+}
+
 pub async fn insert_data_many<C, D>(db: &C, data: D) -> Result<(), DbErr>
 where
     C: ConnectionTrait,
@@ -115,7 +227,10 @@ where
             .on_conflict(
                 sea_query::OnConflict::columns([
                     chart_data::Column::ChartId,
+                    chart_data::Column::Resolution,
                     chart_data::Column::Date,
+                    chart_data::Column::Time,
+                    chart_data::Column::Series,
                 ])
                 .update_column(chart_data::Column::Value)
                 .to_owned(),
@@ -0,0 +1,7 @@
+mod top_accounts_by_txns;
+mod top_contracts_by_gas_used;
+mod top_tokens_by_transfers;
+
+pub use top_accounts_by_txns::TopAccountsByTxns;
+pub use top_contracts_by_gas_used::TopContractsByGasUsed;
+pub use top_tokens_by_transfers::TopTokensByTransfers;
@@ -0,0 +1,70 @@
+use crate::{charts::updater::ChartLeaderboardUpdater, UpdateError};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+
+/// Senders with the most transactions that were not dropped or replaced
+#[derive(Default, Debug)]
+pub struct TopAccountsByTxns {}
+
+impl ChartLeaderboardUpdater for TopAccountsByTxns {
+    fn query(&self) -> &'static str {
+        r#"
+        SELECT
+            t.from_address_hash as address,
+            COUNT(*)::TEXT as value
+        FROM transactions t
+        JOIN blocks       b ON t.block_hash = b.hash
+        WHERE
+            b.timestamp >= $1 AND
+            b.timestamp < $2 AND
+            b.consensus = true AND
+            (t.error IS NULL OR t.error::text != 'dropped/replaced')
+        GROUP BY t.from_address_hash
+        ORDER BY COUNT(*) DESC, address
+        LIMIT $3
+        "#
+    }
+}
+
+#[async_trait]
+impl crate::Chart for TopAccountsByTxns {
+    fn name(&self) -> &str {
+        "topAccountsByTxns"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Leaderboard
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopAccountsByTxns;
+    use crate::tests::{mock_blockscout::mock_address_hash, simple_test::simple_test_leaderboard};
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_accounts_by_txns() {
+        let chart = TopAccountsByTxns::default();
+        simple_test_leaderboard(
+            "update_top_accounts_by_txns",
+            chart,
+            vec![
+                ("2023-02-01", 1, vec![(mock_address_hash(4), "4")]),
+                ("2023-03-01", 1, vec![]),
+                ("2023-03-01", 30, vec![(mock_address_hash(4), "4")]),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{charts::updater::ChartLeaderboardUpdater, UpdateError};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+
+/// Contracts called by transactions that used the most gas
+#[derive(Default, Debug)]
+pub struct TopContractsByGasUsed {}
+
+impl ChartLeaderboardUpdater for TopContractsByGasUsed {
+    fn query(&self) -> &'static str {
+        r#"
+        SELECT
+            t.to_address_hash as address,
+            SUM(t.gas_used)::TEXT as value
+        FROM transactions t
+        JOIN blocks       b ON t.block_hash = b.hash
+        JOIN addresses    a ON t.to_address_hash = a.hash
+        WHERE
+            b.timestamp >= $1 AND
+            b.timestamp < $2 AND
+            b.consensus = true AND
+            a.contract_code IS NOT NULL AND
+            t.gas_used IS NOT NULL
+        GROUP BY t.to_address_hash
+        ORDER BY SUM(t.gas_used) DESC, address
+        LIMIT $3
+        "#
+    }
+}
+
+#[async_trait]
+impl crate::Chart for TopContractsByGasUsed {
+    fn name(&self) -> &str {
+        "topContractsByGasUsed"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Leaderboard
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopContractsByGasUsed;
+    use crate::tests::{mock_blockscout::mock_address_hash, simple_test::simple_test_leaderboard};
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_contracts_by_gas_used() {
+        let chart = TopContractsByGasUsed::default();
+        // transactions of mock data don't call contracts
+        simple_test_leaderboard(
+            "update_top_contracts_by_gas_used",
+            chart,
+            vec![("2023-02-01", 1, vec![]), ("2023-03-01", 30, vec![])],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{charts::updater::ChartLeaderboardUpdater, UpdateError};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+
+/// Tokens with the most transfers
+#[derive(Default, Debug)]
+pub struct TopTokensByTransfers {}
+
+impl ChartLeaderboardUpdater for TopTokensByTransfers {
+    fn query(&self) -> &'static str {
+        r#"
+        SELECT
+            tt.token_contract_address_hash as address,
+            COUNT(*)::TEXT as value
+        FROM token_transfers tt
+        JOIN blocks          b ON tt.block_hash = b.hash
+        WHERE
+            b.timestamp >= $1 AND
+            b.timestamp < $2 AND
+            b.consensus = true
+        GROUP BY tt.token_contract_address_hash
+        ORDER BY COUNT(*) DESC, address
+        LIMIT $3
+        "#
+    }
+}
+
+#[async_trait]
+impl crate::Chart for TopTokensByTransfers {
+    fn name(&self) -> &str {
+        "topTokensByTransfers"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Leaderboard
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopTokensByTransfers;
+    use crate::tests::{mock_blockscout::mock_address_hash, simple_test::simple_test_leaderboard};
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_tokens_by_transfers() {
+        let chart = TopTokensByTransfers::default();
+        simple_test_leaderboard(
+            "update_top_tokens_by_transfers",
+            chart,
+            vec![
+                ("2023-02-01", 1, vec![(mock_address_hash(1), "2")]),
+                ("2023-02-07", 7, vec![(mock_address_hash(1), "2")]),
+                ("2023-03-01", 1, vec![]),
+            ],
+        )
+        .await;
+    }
+}
@@ -3,13 +3,15 @@ use crate::{
     charts::{
         cache::Cache,
         insert::{DateValue, DateValueInt},
-        updater::ChartFullUpdater,
+        timezone,
+        updater::{utc_day_bounds, ChartFullUpdater},
     },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::prelude::*;
+use sea_orm::{prelude::*, DbBackend, Statement};
 use tokio::sync::Mutex;
 
 pub struct AccountsGrowth {
@@ -39,6 +41,35 @@ impl AccountsGrowth {
 
 #[async_trait]
 impl ChartFullUpdater for AccountsGrowth {
+    /// Growth is the amount of accounts before the end of the dates, so only it is bounded
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
+        let (_, to_time) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT date, value::TEXT as value FROM (
+                    SELECT 
+                        first_tx.date as date,
+                        sum(count(*)) OVER (ORDER BY first_tx.date) as value
+                    FROM (
+                        SELECT DISTINCT ON (t.from_address_hash)
+                            {timestamp}::date as date
+                        FROM transactions  t
+                        JOIN blocks        b ON t.block_hash = b.hash
+                        WHERE b.consensus = true AND b.timestamp < $2
+                        ORDER BY t.from_address_hash, {timestamp}
+                    ) first_tx
+                    GROUP BY first_tx.date
+                ) growth
+                WHERE date >= $1;
+                "#
+            ),
+            vec![from.into(), to_time.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
@@ -68,6 +99,14 @@ impl crate::Chart for AccountsGrowth {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -1,55 +1,108 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    charts::{
+        insert::{DateValue, TimespanValue},
+        resolution::start_of_day,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
+    },
+    Resolution, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
 #[derive(Default, Debug)]
 pub struct ActiveAccounts {}
 
-#[async_trait]
-impl ChartPartialUpdater for ActiveAccounts {
-    async fn get_values(
+impl ActiveAccounts {
+    /// Points of the resolution that start after `last_row`
+    async fn get_timespan_values(
         &self,
         blockscout: &DatabaseConnection,
-        last_row: Option<DateValue>,
-    ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
+        last_row: Option<NaiveDateTime>,
+        resolution: Resolution,
+    ) -> Result<Vec<TimespanValue>, UpdateError> {
+        let timezone = timezone::current();
+        let timestamp = timezone.local_sql("blocks.timestamp");
+        let mut values = vec![resolution.sql_precision().into()];
+        // filter by raw timestamp, so that the index on it could be used
+        let filter = match last_row {
+            Some(timespan) => {
+                values.push(timezone.utc_time(resolution.period_end(timespan)).into());
+                "AND blocks.timestamp >= $2"
+            }
+            None => "",
+        };
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
                 r#"
                 SELECT 
-                    DATE(blocks.timestamp) as date, 
+                    date_trunc($1, {timestamp}) as timespan, 
                     COUNT(DISTINCT from_address_hash)::TEXT as value
                 FROM transactions 
                 JOIN blocks on transactions.block_hash = blocks.hash
-                WHERE date(blocks.timestamp) > $1 AND blocks.consensus = true
-                GROUP BY date(blocks.timestamp);
-                "#,
-                vec![row.date.into()],
+                WHERE blocks.consensus = true {filter}
+                GROUP BY timespan;
+                "#
             ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
+            values,
+        );
+
+        let data = TimespanValue::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl ChartPartialUpdater for ActiveAccounts {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
                 r#"
                 SELECT 
-                    DATE(blocks.timestamp) as date, 
+                    DATE({timestamp}) as date, 
                     COUNT(DISTINCT from_address_hash)::TEXT as value
                 FROM transactions 
                 JOIN blocks on transactions.block_hash = blocks.hash
-                WHERE blocks.consensus = true
-                GROUP BY date(blocks.timestamp);
-                "#,
-                vec![],
+                WHERE
+                    blocks.consensus = true AND
+                    blocks.timestamp >= $1 AND
+                    blocks.timestamp < $2
+                GROUP BY date;
+                "#
             ),
-        };
+            vec![from.into(), to.into()],
+        ))
+    }
 
-        let data = DateValue::find_by_statement(stmnt)
-            .all(blockscout)
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let last_row = last_row.map(|row| start_of_day(row.date));
+        let data = self
+            .get_timespan_values(blockscout, last_row, Resolution::Day)
+            .await?;
+        Ok(data.into_iter().map(DateValue::from).collect())
+    }
+
+    async fn get_values_with_resolution(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<TimespanValue>,
+        resolution: Resolution,
+    ) -> Result<Vec<TimespanValue>, UpdateError> {
+        self.get_timespan_values(blockscout, last_row.map(|row| row.timespan), resolution)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        Ok(data)
     }
 }
 
@@ -62,6 +115,10 @@ impl crate::Chart for ActiveAccounts {
         ChartType::Line
     }
 
+    fn resolutions(&self) -> &[Resolution] {
+        &[Resolution::Day, Resolution::Week, Resolution::Month]
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
@@ -70,6 +127,14 @@ impl crate::Chart for ActiveAccounts {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
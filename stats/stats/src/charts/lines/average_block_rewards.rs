@@ -1,11 +1,13 @@
 use crate::{
     charts::{
         insert::{DateValue, DateValueDouble},
-        updater::ChartPartialUpdater,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
     },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -16,36 +18,64 @@ const ETH: i64 = 1_000_000_000_000_000_000;
 
 #[async_trait]
 impl ChartPartialUpdater for AverageBlockRewards {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT
+                    DATE({timestamp}) as date,
+                    (AVG(block_rewards.reward) / $1)::FLOAT::TEXT as value
+                FROM block_rewards
+                JOIN blocks ON block_rewards.block_hash = blocks.hash
+                WHERE
+                    blocks.timestamp >= $2 AND
+                    blocks.timestamp < $3 AND
+                    blocks.consensus = true
+                GROUP BY date
+                "#
+            ),
+            vec![ETH.into(), from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"
+                &format!(
+                    r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    DATE({timestamp}) as date,
                     (AVG(block_rewards.reward) / $1)::FLOAT as value
                 FROM block_rewards
                 JOIN blocks ON block_rewards.block_hash = blocks.hash
-                WHERE date(blocks.timestamp) > $2 AND blocks.consensus = true
+                WHERE date({timestamp}) > $2 AND blocks.consensus = true
                 GROUP BY date
-                "#,
+                "#
+                ),
                 vec![ETH.into(), row.date.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"
+                &format!(
+                    r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    DATE({timestamp}) as date,
                     (AVG(block_rewards.reward) / $1)::FLOAT as value
                 FROM block_rewards
                 JOIN blocks ON block_rewards.block_hash = blocks.hash
                 WHERE blocks.consensus = true
                 GROUP BY date
-                "#,
+                "#
+                ),
                 vec![ETH.into()],
             ),
         };
@@ -77,6 +107,14 @@ impl crate::Chart for AverageBlockRewards {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,13 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    charts::{
+        insert::DateValue,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
+    },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -11,36 +16,63 @@ pub struct AverageBlockSize {}
 
 #[async_trait]
 impl ChartPartialUpdater for AverageBlockSize {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT
+                    DATE({timestamp}) as date,
+                    ROUND(AVG(blocks.size))::TEXT as value
+                FROM blocks
+                WHERE
+                    blocks.timestamp >= $1 AND
+                    blocks.timestamp < $2 AND
+                    consensus = true
+                GROUP BY date
+                "#
+            ),
+            vec![from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"
+                &format!(
+                    r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    DATE({timestamp}) as date,
                     ROUND(AVG(blocks.size))::TEXT as value
                 FROM blocks
                 WHERE 
-                    DATE(blocks.timestamp) > $1 AND 
+                    DATE({timestamp}) > $1 AND 
                     consensus = true
                 GROUP BY date
-                "#,
+                "#
+                ),
                 vec![row.date.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"
+                &format!(
+                    r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    DATE({timestamp}) as date,
                     ROUND(AVG(blocks.size))::TEXT as value
                 FROM blocks
                 WHERE consensus = true
                 GROUP BY date
-                "#,
+                "#
+                ),
                 vec![],
             ),
         };
@@ -71,6 +103,14 @@ impl crate::Chart for AverageBlockSize {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
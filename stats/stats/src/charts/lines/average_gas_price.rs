@@ -1,73 +1,63 @@
 use crate::{
-    charts::{
-        insert::{DateValue, DateValueDouble},
-        updater::ChartPartialUpdater,
-    },
-    UpdateError,
+    charts::{create_chart, txns_rollup::TxnsRollup, updater::ChartRollupUpdater, ArcChart, Chart},
+    DateValue, Resolution, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use sea_orm::prelude::*;
+use std::sync::Arc;
 
 #[derive(Default, Debug)]
-pub struct AverageGasPrice {}
+pub struct AverageGasPrice {
+    rollup: Arc<TxnsRollup>,
+}
 
-const GWEI: i64 = 1_000_000_000;
+impl AverageGasPrice {
+    pub fn new(rollup: Arc<TxnsRollup>) -> Self {
+        Self { rollup }
+    }
+}
 
-#[async_trait]
-impl ChartPartialUpdater for AverageGasPrice {
-    async fn get_values(
-        &self,
-        blockscout: &DatabaseConnection,
-        last_row: Option<DateValue>,
-    ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                    SELECT
-                        blocks.timestamp::date as date,
-                        (AVG(gas_price) / $1)::float as value
-                    FROM transactions
-                    JOIN blocks ON transactions.block_hash = blocks.hash
-                    WHERE date(blocks.timestamp) > $2 AND blocks.consensus = true
-                    GROUP BY date
-                    "#,
-                vec![GWEI.into(), row.date.into()],
-            ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                    SELECT
-                        blocks.timestamp::date as date,
-                        (AVG(gas_price) / $1)::float as value
-                    FROM transactions
-                    JOIN blocks ON transactions.block_hash = blocks.hash
-                    WHERE blocks.consensus = true
-                    GROUP BY date
-                    "#,
-                vec![GWEI.into()],
-            ),
-        };
+impl ChartRollupUpdater for AverageGasPrice {
+    fn rollup(&self) -> Arc<TxnsRollup> {
+        self.rollup.clone()
+    }
 
-        let data = DateValueDouble::find_by_statement(stmnt)
-            .all(blockscout)
-            .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        let data = data.into_iter().map(DateValue::from).collect();
-        Ok(data)
+    fn value_sql(&self) -> &'static str {
+        // price in gwei
+        "SUM(gas_price_sum) / NULLIF(SUM(gas_price_count), 0) / 1000000000"
     }
 }
 
 #[async_trait]
-impl crate::Chart for AverageGasPrice {
+impl Chart for AverageGasPrice {
     fn name(&self) -> &str {
         "averageGasPrice"
     }
+
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
 
+    fn resolutions(&self) -> &[Resolution] {
+        &[
+            Resolution::Day,
+            Resolution::Hour,
+            Resolution::Week,
+            Resolution::Month,
+        ]
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.rollup.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.rollup.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
@@ -76,6 +66,14 @@ impl crate::Chart for AverageGasPrice {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
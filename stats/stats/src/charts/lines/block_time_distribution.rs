@@ -0,0 +1,145 @@
+use crate::{
+    charts::{
+        insert::{DateDistribution, DateValue, DISTRIBUTION_SERIES},
+        timezone,
+        updater::ChartDistributionUpdater,
+    },
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Seconds between consecutive consensus blocks.
+/// Interval belongs to the date of the later block
+#[derive(Default, Debug)]
+pub struct BlockTimeDistribution {}
+
+#[async_trait]
+impl ChartDistributionUpdater for BlockTimeDistribution {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+    ) -> Result<Vec<DateDistribution>, UpdateError> {
+        let timestamp = timezone::current().local_sql("timestamp");
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                SELECT
+                    date,
+                    percentile_cont(0.1) WITHIN GROUP (ORDER BY diff) as p10,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY diff) as p50,
+                    percentile_cont(0.9) WITHIN GROUP (ORDER BY diff) as p90,
+                    MAX(diff) as max
+                FROM (
+                    SELECT
+                        DATE({timestamp}) as date,
+                        EXTRACT(
+                            EPOCH FROM timestamp - lag(timestamp) OVER (ORDER BY number)
+                        )::FLOAT as diff
+                    FROM blocks
+                    WHERE consensus = true
+                ) t
+                WHERE
+                    diff IS NOT NULL AND
+                    date > $1
+                GROUP BY date
+                "#
+                ),
+                vec![row.date.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                SELECT
+                    date,
+                    percentile_cont(0.1) WITHIN GROUP (ORDER BY diff) as p10,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY diff) as p50,
+                    percentile_cont(0.9) WITHIN GROUP (ORDER BY diff) as p90,
+                    MAX(diff) as max
+                FROM (
+                    SELECT
+                        DATE({timestamp}) as date,
+                        EXTRACT(
+                            EPOCH FROM timestamp - lag(timestamp) OVER (ORDER BY number)
+                        )::FLOAT as diff
+                    FROM blocks
+                    WHERE consensus = true
+                ) t
+                WHERE diff IS NOT NULL
+                GROUP BY date
+                "#
+                ),
+                vec![],
+            ),
+        };
+
+        let data = DateDistribution::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for BlockTimeDistribution {
+    fn name(&self) -> &str {
+        "blockTimeDistribution"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn series(&self) -> &[&'static str] {
+        &DISTRIBUTION_SERIES
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_distribution_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_block_time_distribution() {
+        let chart = BlockTimeDistribution::default();
+        simple_test_distribution_chart(
+            "update_block_time_distribution",
+            chart,
+            vec![
+                ("2022-11-10", ["8640.6", "43199", "43199.8", "43200"]),
+                (
+                    "2022-11-11",
+                    [
+                        "3240.7000000000003",
+                        "21599.5",
+                        "39959.700000000004",
+                        "43200",
+                    ],
+                ),
+                ("2022-11-12", ["1", "1", "1", "1"]),
+                ("2022-12-01", ["1677600", "1677600", "1677600", "1677600"]),
+                ("2023-01-01", ["2678400", "2678400", "2678400", "2678400"]),
+                ("2023-02-01", ["2678400", "2678400", "2678400", "2678400"]),
+                ("2023-03-01", ["2419200", "2419200", "2419200", "2419200"]),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    charts::{
+        insert::{DateDistribution, DateValue, DISTRIBUTION_SERIES},
+        timezone,
+        updater::ChartDistributionUpdater,
+    },
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct GasPriceDistribution {}
+
+const GWEI: i64 = 1_000_000_000;
+
+#[async_trait]
+impl ChartDistributionUpdater for GasPriceDistribution {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+    ) -> Result<Vec<DateDistribution>, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                SELECT
+                    date,
+                    percentile_cont(0.1) WITHIN GROUP (ORDER BY value) as p10,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY value) as p50,
+                    percentile_cont(0.9) WITHIN GROUP (ORDER BY value) as p90,
+                    MAX(value) as max
+                FROM (
+                    SELECT
+                        DATE({timestamp}) as date,
+                        (t.gas_price / $1)::FLOAT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        DATE({timestamp}) > $2 AND
+                        b.consensus = true
+                ) v
+                GROUP BY date
+                "#
+                ),
+                vec![GWEI.into(), row.date.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                SELECT
+                    date,
+                    percentile_cont(0.1) WITHIN GROUP (ORDER BY value) as p10,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY value) as p50,
+                    percentile_cont(0.9) WITHIN GROUP (ORDER BY value) as p90,
+                    MAX(value) as max
+                FROM (
+                    SELECT
+                        DATE({timestamp}) as date,
+                        (t.gas_price / $1)::FLOAT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE b.consensus = true
+                ) v
+                GROUP BY date
+                "#
+                ),
+                vec![GWEI.into()],
+            ),
+        };
+
+        let data = DateDistribution::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for GasPriceDistribution {
+    fn name(&self) -> &str {
+        "gasPriceDistribution"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn series(&self) -> &[&'static str] {
+        &DISTRIBUTION_SERIES
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_distribution_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_gas_price_distribution() {
+        let chart = GasPriceDistribution::default();
+        simple_test_distribution_chart(
+            "update_gas_price_distribution",
+            chart,
+            vec![
+                ("2022-11-09", ["0", "0", "1.123456789", "1.123456789"]),
+                (
+                    "2022-11-10",
+                    ["1.123456789", "1.6851851835", "3.370370367", "3.370370367"],
+                ),
+                (
+                    "2022-11-11",
+                    ["1.123456789", "1.123456789", "6.740740734", "6.740740734"],
+                ),
+                (
+                    "2022-11-12",
+                    ["1.123456789", "8.987654312", "8.987654312", "8.987654312"],
+                ),
+                (
+                    "2022-12-01",
+                    [
+                        "1.123456789",
+                        "10.111111101",
+                        "10.111111101",
+                        "10.111111101",
+                    ],
+                ),
+                (
+                    "2023-01-01",
+                    ["1.123456789", "1.123456789", "1.123456789", "1.123456789"],
+                ),
+                (
+                    "2023-02-01",
+                    [
+                        "4.493827156",
+                        "12.358024679",
+                        "12.358024679",
+                        "12.358024679",
+                    ],
+                ),
+                (
+                    "2023-03-01",
+                    ["1.123456789", "1.123456789", "1.123456789", "1.123456789"],
+                ),
+            ],
+        )
+        .await;
+    }
+}
@@ -1,11 +1,13 @@
 use crate::{
     charts::{
         insert::{DateValue, DateValueDecimal},
-        updater::ChartPartialUpdater,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
     },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -14,11 +16,35 @@ pub struct GasUsedGrowth {}
 
 #[async_trait]
 impl ChartPartialUpdater for GasUsedGrowth {
+    /// Growth is the sum over all previous days, so only the end of the dates is bounded
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
+        let (_, to_time) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT date, value::TEXT as value FROM (
+                    SELECT 
+                        DATE({timestamp}) as date, 
+                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date({timestamp}))) AS value
+                    FROM blocks
+                    WHERE blocks.timestamp < $2 AND blocks.consensus = true
+                    GROUP BY date({timestamp})
+                ) growth
+                WHERE date >= $1;
+                "#
+            ),
+            vec![from.into(), to_time.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
         let data = match last_row {
             Some(row) => {
                 let last_value = Decimal::from_str_exact(&row.value).map_err(|e| {
@@ -26,15 +52,17 @@ impl ChartPartialUpdater for GasUsedGrowth {
                 })?;
                 let stmnt = Statement::from_sql_and_values(
                     DbBackend::Postgres,
-                    r#"
+                    &format!(
+                        r#"
                     SELECT 
-                        DATE(blocks.timestamp) as date, 
-                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date(blocks.timestamp))) AS value
+                        DATE({timestamp}) as date, 
+                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date({timestamp}))) AS value
                     FROM blocks
-                    WHERE DATE(blocks.timestamp) > $1 AND blocks.consensus = true
-                    GROUP BY date(blocks.timestamp)
+                    WHERE DATE({timestamp}) > $1 AND blocks.consensus = true
+                    GROUP BY date({timestamp})
                     ORDER BY date;
-                    "#,
+                    "#
+                    ),
                     vec![row.date.into()],
                 );
                 DateValueDecimal::find_by_statement(stmnt)
@@ -52,15 +80,17 @@ impl ChartPartialUpdater for GasUsedGrowth {
             None => {
                 let stmnt = Statement::from_sql_and_values(
                     DbBackend::Postgres,
-                    r#"
+                    &format!(
+                        r#"
                     SELECT 
-                        DATE(blocks.timestamp) as date, 
-                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date(blocks.timestamp)))::TEXT AS value
+                        DATE({timestamp}) as date, 
+                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date({timestamp})))::TEXT AS value
                     FROM blocks
                     WHERE blocks.consensus = true
-                    GROUP BY date(blocks.timestamp)
+                    GROUP BY date({timestamp})
                     ORDER BY date;
-                    "#,
+                    "#
+                    ),
                     vec![],
                 );
                 DateValue::find_by_statement(stmnt)
@@ -91,6 +121,14 @@ impl crate::Chart for GasUsedGrowth {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
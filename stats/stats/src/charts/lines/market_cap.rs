@@ -0,0 +1,119 @@
+use super::{NativeCoinPrice, NativeCoinSupply};
+use crate::{
+    charts::{create_chart, fiat::MissingPrice, updater::ChartFiatUpdater, ArcChart, Chart},
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Native coin supply valued at the closing price of the day.
+///
+/// `market_history` of blockscout keeps only prices, so the market cap is calculated
+/// from [`NativeCoinSupply`].
+#[derive(Default, Debug)]
+pub struct MarketCap {
+    parent: Arc<NativeCoinSupply>,
+    price: Arc<NativeCoinPrice>,
+    missing_price: MissingPrice,
+}
+
+impl MarketCap {
+    pub fn new(
+        parent: Arc<NativeCoinSupply>,
+        price: Arc<NativeCoinPrice>,
+        missing_price: MissingPrice,
+    ) -> Self {
+        Self {
+            parent,
+            price,
+            missing_price,
+        }
+    }
+}
+
+impl ChartFiatUpdater for MarketCap {
+    fn parent(&self) -> ArcChart {
+        self.parent.clone()
+    }
+
+    fn price(&self) -> Arc<NativeCoinPrice> {
+        self.price.clone()
+    }
+
+    fn missing_price(&self) -> MissingPrice {
+        self.missing_price
+    }
+}
+
+#[async_trait]
+impl Chart for MarketCap {
+    fn name(&self) -> &str {
+        "marketCap"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone(), self.price.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.parent.create(db).await?;
+        self.price.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_market_cap() {
+        let chart = MarketCap::default();
+        simple_test_chart(
+            "update_market_cap",
+            chart,
+            vec![
+                ("2022-11-09", "10000.0000000000005"),
+                ("2022-11-10", "12000"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_market_cap_carry_forward() {
+        let chart = MarketCap::new(
+            Default::default(),
+            Default::default(),
+            MissingPrice::CarryForward,
+        );
+        simple_test_chart(
+            "update_market_cap_carry_forward",
+            chart,
+            vec![
+                ("2022-11-09", "10000.0000000000005"),
+                ("2022-11-10", "12000"),
+                ("2022-11-11", "10000"),
+            ],
+        )
+        .await;
+    }
+}
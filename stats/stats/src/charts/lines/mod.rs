@@ -7,9 +7,13 @@ mod average_block_size;
 mod average_gas_limit;
 mod average_gas_price;
 mod average_txn_fee;
+mod block_time_distribution;
 mod contracts_growth;
+mod gas_price_distribution;
 mod gas_used_growth;
+mod market_cap;
 mod native_coin_holders_growth;
+mod native_coin_price;
 mod native_coin_supply;
 mod new_accounts;
 mod new_blocks;
@@ -18,9 +22,13 @@ mod new_native_coin_holders;
 mod new_native_coin_transfers;
 mod new_txns;
 mod new_verified_contracts;
+mod txn_fee_distribution;
 mod txns_fee;
+mod txns_fee_usd;
 mod txns_growth;
 mod txns_success_rate;
+mod txns_volume;
+mod txns_volume_usd;
 mod verified_contracts_growth;
 
 pub use accounts_growth::AccountsGrowth;
@@ -30,10 +38,14 @@ pub use average_block_size::AverageBlockSize;
 pub use average_gas_limit::AverageGasLimit;
 pub use average_gas_price::AverageGasPrice;
 pub use average_txn_fee::AverageTxnFee;
+pub use block_time_distribution::BlockTimeDistribution;
 pub use contracts_growth::ContractsGrowth;
+pub use gas_price_distribution::GasPriceDistribution;
 pub use gas_used_growth::GasUsedGrowth;
+pub use market_cap::MarketCap;
 pub use mock::MockLine;
 pub use native_coin_holders_growth::NativeCoinHoldersGrowth;
+pub use native_coin_price::NativeCoinPrice;
 pub use native_coin_supply::NativeCoinSupply;
 pub use new_accounts::NewAccounts;
 pub use new_blocks::NewBlocks;
@@ -42,7 +54,11 @@ pub use new_native_coin_holders::NewNativeCoinHolders;
 pub use new_native_coin_transfers::NewNativeCoinTransfers;
 pub use new_txns::NewTxns;
 pub use new_verified_contracts::NewVerifiedContracts;
+pub use txn_fee_distribution::TxnFeeDistribution;
 pub use txns_fee::TxnsFee;
+pub use txns_fee_usd::TxnsFeeUsd;
 pub use txns_growth::TxnsGrowth;
 pub use txns_success_rate::TxnsSuccessRate;
+pub use txns_volume::TxnsVolume;
+pub use txns_volume_usd::TxnsVolumeUsd;
 pub use verified_contracts_growth::VerifiedContractsGrowth;
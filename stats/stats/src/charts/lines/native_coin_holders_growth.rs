@@ -3,6 +3,7 @@ use crate::{
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -11,6 +12,21 @@ pub struct NativeCoinHoldersGrowth {}
 
 #[async_trait]
 impl ChartPartialUpdater for NativeCoinHoldersGrowth {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+                SELECT 
+                    day as date,
+                    count(*)::TEXT as value
+                FROM address_coin_balances_daily
+                WHERE value != 0 AND day >= $1 AND day < $2
+                GROUP BY day;
+            "#,
+            vec![from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
@@ -69,6 +85,14 @@ impl crate::Chart for NativeCoinHoldersGrowth {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,112 @@
+use crate::{
+    charts::{insert::DateValue, updater::ChartFullUpdater},
+    UpdateError,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Daily closing price of the native coin from `market_history`
+#[derive(Default, Debug)]
+pub struct NativeCoinPrice {}
+
+#[async_trait]
+impl ChartFullUpdater for NativeCoinPrice {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                date,
+                closing_price::TEXT as value
+            FROM market_history
+            WHERE
+                date >= $1 AND
+                date < $2 AND
+                closing_price IS NOT NULL
+            "#,
+            vec![from.into(), to.into()],
+        ))
+    }
+
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = Statement::from_string(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                date,
+                closing_price::TEXT as value
+            FROM market_history
+            WHERE
+                date IS NOT NULL AND
+                closing_price IS NOT NULL
+            ORDER BY date
+            "#
+            .into(),
+        );
+        let data = DateValue::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NativeCoinPrice {
+    fn name(&self) -> &str {
+        "nativeCoinPrice"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    /// Dates of `market_history` are already days
+    fn uses_timezone(&self) -> bool {
+        false
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NativeCoinPrice;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_native_coin_price() {
+        let chart = NativeCoinPrice::default();
+        simple_test_chart(
+            "update_native_coin_price",
+            chart,
+            vec![
+                ("2022-11-09", "1.5"),
+                ("2022-11-10", "2"),
+                ("2022-11-12", "3"),
+            ],
+        )
+        .await;
+    }
+}
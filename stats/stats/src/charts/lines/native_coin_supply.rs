@@ -6,6 +6,7 @@ use crate::{
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -16,6 +17,30 @@ pub struct NativeCoinSupply {}
 
 #[async_trait]
 impl ChartPartialUpdater for NativeCoinSupply {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+                SELECT date, value::TEXT as value FROM 
+                (
+                    SELECT
+                        day as date,
+                        (sum(
+                            CASE 
+                                WHEN address_hash = '\x0000000000000000000000000000000000000000' THEN -value
+                                ELSE value
+                            END
+                        ) / $1)::float AS value
+                    FROM address_coin_balances_daily
+                    WHERE day >= $2 AND day < $3
+                    GROUP BY day
+                ) as intermediate
+                WHERE value is not NULL;
+            "#,
+            vec![ETH.into(), from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
@@ -92,6 +117,14 @@ impl crate::Chart for NativeCoinSupply {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -2,11 +2,13 @@ use crate::{
     charts::{
         cache::Cache,
         insert::{DateValue, DateValueInt},
-        updater::ChartFullUpdater,
+        timezone,
+        updater::{utc_day_bounds, ChartFullUpdater},
     },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 use tokio::sync::Mutex;
@@ -25,22 +27,25 @@ impl NewAccounts {
     pub async fn read_values(
         blockscout: &DatabaseConnection,
     ) -> Result<Vec<DateValueInt>, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
         let stmnt = Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"
+            &format!(
+                r#"
                 SELECT 
                     first_tx.date as date,
                     count(*) as value
                 FROM (
                     SELECT DISTINCT ON (t.from_address_hash)
-                        b.timestamp::date as date
+                        {timestamp}::date as date
                     FROM transactions  t
                     JOIN blocks        b ON t.block_hash = b.hash
                     WHERE b.consensus = true
-                    ORDER BY t.from_address_hash, b.timestamp
+                    ORDER BY t.from_address_hash, {timestamp}
                 ) first_tx
                 GROUP BY first_tx.date;
-                "#,
+                "#
+            ),
             vec![],
         );
 
@@ -55,6 +60,44 @@ impl NewAccounts {
 
 #[async_trait]
 impl ChartFullUpdater for NewAccounts {
+    /// Accounts of the dates that had no transactions before them
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT 
+                    first_tx.date as date,
+                    count(*)::TEXT as value
+                FROM (
+                    SELECT DISTINCT ON (t.from_address_hash)
+                        {timestamp}::date as date
+                    FROM transactions  t
+                    JOIN blocks        b ON t.block_hash = b.hash
+                    WHERE
+                        b.consensus = true AND
+                        b.timestamp >= $1 AND
+                        b.timestamp < $2 AND
+                        NOT EXISTS (
+                            SELECT 1
+                            FROM transactions  prev_t
+                            JOIN blocks        prev_b ON prev_t.block_hash = prev_b.hash
+                            WHERE
+                                prev_t.from_address_hash = t.from_address_hash AND
+                                prev_b.consensus = true AND
+                                prev_b.timestamp < $1
+                        )
+                    ORDER BY t.from_address_hash, {timestamp}
+                ) first_tx
+                GROUP BY first_tx.date;
+                "#
+            ),
+            vec![from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
@@ -86,6 +129,14 @@ impl crate::Chart for NewAccounts {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
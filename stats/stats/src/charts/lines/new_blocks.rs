@@ -1,44 +1,52 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    charts::{
+        insert::{DateValue, TimespanValue},
+        resolution::start_of_day,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
+    },
+    Resolution, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
 #[derive(Default, Debug)]
 pub struct NewBlocks {}
 
-#[async_trait]
-impl ChartPartialUpdater for NewBlocks {
-    async fn get_values(
+impl NewBlocks {
+    /// Points of the resolution that start after `last_row`
+    async fn get_timespan_values(
         &self,
         blockscout: &DatabaseConnection,
-        last_row: Option<DateValue>,
-    ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                    SELECT date(blocks.timestamp) as date, COUNT(*)::TEXT as value
-                        FROM public.blocks
-                        WHERE date(blocks.timestamp) > $1 AND consensus = true
-                        GROUP BY date;
-                    "#,
-                vec![row.date.into()],
-            ),
-            None => Statement::from_string(
-                DbBackend::Postgres,
+        last_row: Option<NaiveDateTime>,
+        resolution: Resolution,
+    ) -> Result<Vec<TimespanValue>, UpdateError> {
+        let timezone = timezone::current();
+        let timestamp = timezone.local_sql("blocks.timestamp");
+        let mut values = vec![resolution.sql_precision().into()];
+        // filter by raw timestamp, so that the index on it could be used
+        let filter = match last_row {
+            Some(timespan) => {
+                values.push(timezone.utc_time(resolution.period_end(timespan)).into());
+                "AND blocks.timestamp >= $2"
+            }
+            None => "",
+        };
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
                 r#"
-                    SELECT date(blocks.timestamp) as date, COUNT(*)::TEXT as value
-                        FROM public.blocks
-                        WHERE consensus = true
-                        GROUP BY date;
-                    "#
-                .into(),
+                SELECT date_trunc($1, {timestamp}) as timespan, COUNT(*)::TEXT as value
+                    FROM public.blocks
+                    WHERE consensus = true {filter}
+                    GROUP BY timespan;
+                "#
             ),
-        };
-        let data = DateValue::find_by_statement(stmnt)
+            values,
+        );
+        let data = TimespanValue::find_by_statement(stmnt)
             .all(blockscout)
             .await
             .map_err(UpdateError::BlockscoutDB)?;
@@ -46,6 +54,51 @@ impl ChartPartialUpdater for NewBlocks {
     }
 }
 
+#[async_trait]
+impl ChartPartialUpdater for NewBlocks {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("blocks.timestamp");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT DATE({timestamp}) as date, COUNT(*)::TEXT as value
+                    FROM public.blocks
+                    WHERE
+                        consensus = true AND
+                        blocks.timestamp >= $1 AND
+                        blocks.timestamp < $2
+                    GROUP BY date;
+                "#
+            ),
+            vec![from.into(), to.into()],
+        ))
+    }
+
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let last_row = last_row.map(|row| start_of_day(row.date));
+        let data = self
+            .get_timespan_values(blockscout, last_row, Resolution::Day)
+            .await?;
+        Ok(data.into_iter().map(DateValue::from).collect())
+    }
+
+    async fn get_values_with_resolution(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<TimespanValue>,
+        resolution: Resolution,
+    ) -> Result<Vec<TimespanValue>, UpdateError> {
+        self.get_timespan_values(blockscout, last_row.map(|row| row.timespan), resolution)
+            .await
+    }
+}
+
 #[async_trait]
 impl crate::Chart for NewBlocks {
     fn name(&self) -> &str {
@@ -56,6 +109,15 @@ impl crate::Chart for NewBlocks {
         ChartType::Line
     }
 
+    fn resolutions(&self) -> &[Resolution] {
+        &[
+            Resolution::Day,
+            Resolution::Hour,
+            Resolution::Week,
+            Resolution::Month,
+        ]
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
@@ -64,6 +126,14 @@ impl crate::Chart for NewBlocks {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -72,7 +142,10 @@ mod tests {
     use crate::{
         charts::updater::get_min_block_blockscout,
         get_chart_data,
-        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        tests::{
+            init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data,
+            simple_test::simple_test_chart,
+        },
         Chart,
     };
     use chrono::NaiveDate;
@@ -266,4 +339,27 @@ mod tests {
         ];
         assert_eq!(expected, data);
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_blocks_in_timezone() {
+        let timezone = "+03:00".parse().unwrap();
+        timezone::scope(
+            timezone,
+            simple_test_chart(
+                "update_new_blocks_in_timezone",
+                NewBlocks::default(),
+                vec![
+                    ("2022-11-10", "3"),
+                    ("2022-11-11", "4"),
+                    ("2022-11-12", "2"),
+                    ("2022-12-01", "1"),
+                    ("2023-01-01", "1"),
+                    ("2023-02-01", "1"),
+                    ("2023-03-01", "1"),
+                ],
+            ),
+        )
+        .await;
+    }
 }
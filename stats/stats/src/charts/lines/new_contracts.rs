@@ -1,4 +1,7 @@
-use crate::{charts::updater::ChartBatchUpdater, UpdateError};
+use crate::{
+    charts::{timezone, updater::ChartBatchUpdater},
+    DateValue, UpdateError,
+};
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
@@ -10,9 +13,11 @@ pub struct NewContracts {}
 #[async_trait]
 impl ChartBatchUpdater for NewContracts {
     fn get_query(&self, from: NaiveDate, to: NaiveDate) -> Statement {
+        let timestamp = timezone::current().local_sql("b.timestamp");
         Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"SELECT day AS date, COUNT(*)::text AS value
+            &format!(
+                r#"SELECT day AS date, COUNT(*)::text AS value
                 FROM (
                     SELECT 
                         DISTINCT ON (txns_plus_internal_txns.hash)
@@ -20,29 +25,30 @@ impl ChartBatchUpdater for NewContracts {
                     FROM (
                         SELECT
                             t.created_contract_address_hash AS hash,
-                            b.timestamp::date AS day
+                            {timestamp}::date AS day
                         FROM transactions t
                             JOIN blocks b ON b.hash = t.block_hash
                         WHERE
                             t.created_contract_address_hash NOTNULL AND
                             b.consensus = TRUE AND
-                            b.timestamp::date < $2 AND
-                            b.timestamp::date >= $1
+                            {timestamp}::date < $2 AND
+                            {timestamp}::date >= $1
                         UNION
                         SELECT
                             it.created_contract_address_hash AS hash,
-                            b.timestamp::date AS day
+                            {timestamp}::date AS day
                         FROM internal_transactions it
                             JOIN blocks b ON b.hash = it.block_hash
                         WHERE
                             it.created_contract_address_hash NOTNULL AND
                             b.consensus = TRUE AND
-                            b.timestamp::date < $2 AND
-                            b.timestamp::date >= $1
+                            {timestamp}::date < $2 AND
+                            {timestamp}::date >= $1
                     ) txns_plus_internal_txns
                 ) sub
                 GROUP BY sub.day;
-                "#,
+                "#
+            ),
             vec![from.into(), to.into()],
         )
     }
@@ -66,6 +72,14 @@ impl crate::Chart for NewContracts {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -4,7 +4,7 @@ use crate::{
         create_chart,
         insert::{DateValue, DateValueInt},
         updater::ChartDependentUpdater,
-        Chart,
+        ArcChart, Chart,
     },
     UpdateError,
 };
@@ -31,9 +31,11 @@ impl ChartDependentUpdater<NativeCoinHoldersGrowth> for NewNativeCoinHolders {
 
     async fn get_values(
         &self,
+        last_row: Option<DateValue>,
         mut parent_data: Vec<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         parent_data.sort();
+        // parent point of `last_row` date is used only as a base of the next difference
         let data: Result<Vec<_>, _> = parent_data
             .into_iter()
             .map(DateValueInt::try_from)
@@ -46,6 +48,10 @@ impl ChartDependentUpdater<NativeCoinHoldersGrowth> for NewNativeCoinHolders {
                 }))
             })
             .map(|point| point.map(DateValue::from))
+            .filter(|point| match (point, &last_row) {
+                (Ok(point), Some(row)) => point.date > row.date,
+                _ => true,
+            })
             .collect();
         Ok(data.map_err(|e| {
             let parent_name = self.parent.name();
@@ -66,6 +72,10 @@ impl Chart for NewNativeCoinHolders {
         ChartType::Line
     }
 
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -1,8 +1,13 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    charts::{
+        insert::DateValue,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
+    },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -11,34 +16,63 @@ pub struct NewNativeCoinTransfers {}
 
 #[async_trait]
 impl ChartPartialUpdater for NewNativeCoinTransfers {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+                SELECT 
+                    DATE({timestamp}) as date,
+                    COUNT(*)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp >= $1 AND
+                    b.timestamp < $2 AND
+                    b.consensus = true AND
+                    LENGTH(t.input) = 0 AND
+                    t.value >= 0
+                GROUP BY date
+                "#
+            ),
+            vec![from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"
+                &format!(
+                    r#"
                 SELECT 
-                    DATE(b.timestamp) as date,
+                    DATE({timestamp}) as date,
                     COUNT(*)::TEXT as value
                 FROM transactions t
                 JOIN blocks       b ON t.block_hash = b.hash
                 WHERE
-                    DATE(b.timestamp) > $1 AND
+                    DATE({timestamp}) > $1 AND
                     b.consensus = true AND
                     LENGTH(t.input) = 0 AND
                     t.value >= 0
                 GROUP BY date
-                "#,
+                "#
+                ),
                 vec![row.date.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"
+                &format!(
+                    r#"
                 SELECT 
-                    DATE(b.timestamp) as date,
+                    DATE({timestamp}) as date,
                     COUNT(*)::TEXT as value
                 FROM transactions t
                 JOIN blocks       b ON t.block_hash = b.hash
@@ -47,7 +81,8 @@ impl ChartPartialUpdater for NewNativeCoinTransfers {
                     LENGTH(t.input) = 0 AND
                     t.value >= 0
                 GROUP BY date
-                "#,
+                "#
+                ),
                 vec![],
             ),
         };
@@ -78,6 +113,14 @@ impl crate::Chart for NewNativeCoinTransfers {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
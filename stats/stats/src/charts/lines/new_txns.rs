@@ -1,62 +1,36 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    charts::{create_chart, txns_rollup::TxnsRollup, updater::ChartRollupUpdater, ArcChart, Chart},
+    DateValue, Resolution, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use sea_orm::prelude::*;
+use std::sync::Arc;
 
 #[derive(Default, Debug)]
-pub struct NewTxns {}
+pub struct NewTxns {
+    rollup: Arc<TxnsRollup>,
+}
 
-#[async_trait]
-impl ChartPartialUpdater for NewTxns {
-    async fn get_values(
-        &self,
-        blockscout: &DatabaseConnection,
-        last_row: Option<DateValue>,
-    ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    date(b.timestamp) as date, 
-                    COUNT(*)::TEXT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE 
-                    date(b.timestamp) > $1 AND 
-                    b.consensus = true
-                GROUP BY date;
-                "#,
-                vec![row.date.into()],
-            ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    date(b.timestamp) as date, 
-                    COUNT(*)::TEXT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE b.consensus = true
-                GROUP BY date;
-                "#,
-                vec![],
-            ),
-        };
+impl NewTxns {
+    pub fn new(rollup: Arc<TxnsRollup>) -> Self {
+        Self { rollup }
+    }
+}
 
-        let data = DateValue::find_by_statement(stmnt)
-            .all(blockscout)
-            .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        Ok(data)
+impl ChartRollupUpdater for NewTxns {
+    fn rollup(&self) -> Arc<TxnsRollup> {
+        self.rollup.clone()
+    }
+
+    fn value_sql(&self) -> &'static str {
+        "SUM(txns)"
     }
 }
 
 #[async_trait]
-impl crate::Chart for NewTxns {
+impl Chart for NewTxns {
     fn name(&self) -> &str {
         "newTxns"
     }
@@ -65,6 +39,24 @@ impl crate::Chart for NewTxns {
         ChartType::Line
     }
 
+    fn resolutions(&self) -> &[Resolution] {
+        &[
+            Resolution::Day,
+            Resolution::Hour,
+            Resolution::Week,
+            Resolution::Month,
+        ]
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.rollup.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.rollup.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
@@ -73,12 +65,23 @@ impl crate::Chart for NewTxns {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::NewTxns;
-    use crate::tests::simple_test::simple_test_chart;
+    use crate::{
+        tests::simple_test::{simple_test_chart, simple_test_chart_with_resolution},
+        Resolution,
+    };
 
     #[tokio::test]
     #[ignore = "needs database to run"]
@@ -100,4 +103,42 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_txns_weekly() {
+        let chart = NewTxns::default();
+        simple_test_chart_with_resolution(
+            "update_new_txns_weekly",
+            chart,
+            Resolution::Week,
+            vec![
+                ("2022-11-07", "36"),
+                ("2022-11-28", "5"),
+                ("2022-12-26", "1"),
+                ("2023-01-30", "4"),
+                ("2023-02-27", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_txns_monthly() {
+        let chart = NewTxns::default();
+        simple_test_chart_with_resolution(
+            "update_new_txns_monthly",
+            chart,
+            Resolution::Month,
+            vec![
+                ("2022-11-01", "36"),
+                ("2022-12-01", "5"),
+                ("2023-01-01", "1"),
+                ("2023-02-01", "4"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
 }
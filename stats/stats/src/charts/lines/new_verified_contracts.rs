@@ -1,8 +1,13 @@
 use crate::{
-    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    charts::{
+        insert::DateValue,
+        timezone,
+        updater::{utc_day_bounds, ChartPartialUpdater},
+    },
     UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -11,29 +16,53 @@ pub struct NewVerifiedContracts {}
 
 #[async_trait]
 impl ChartPartialUpdater for NewVerifiedContracts {
+    fn get_dates_query(&self, from: NaiveDate, to: NaiveDate) -> Result<Statement, UpdateError> {
+        let timestamp = timezone::current().local_sql("smart_contracts.inserted_at");
+        let (from, to) = utc_day_bounds(from, to);
+        Ok(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"SELECT
+                    DATE({timestamp}) as date,
+                    COUNT(*)::TEXT as value
+                FROM smart_contracts
+                WHERE
+                    smart_contracts.inserted_at >= $1 AND
+                    smart_contracts.inserted_at < $2
+                GROUP BY DATE({timestamp})"#
+            ),
+            vec![from.into(), to.into()],
+        ))
+    }
+
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
+        let timestamp = timezone::current().local_sql("smart_contracts.inserted_at");
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"SELECT
-                    DATE(smart_contracts.inserted_at) as date,
+                &format!(
+                    r#"SELECT
+                    DATE({timestamp}) as date,
                     COUNT(*)::TEXT as value
                 FROM smart_contracts
-                WHERE DATE(smart_contracts.inserted_at) > $1
-                GROUP BY DATE(smart_contracts.inserted_at)"#,
+                WHERE DATE({timestamp}) > $1
+                GROUP BY DATE({timestamp})"#
+                ),
                 vec![row.date.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"SELECT
-                    DATE(smart_contracts.inserted_at) as date,
+                &format!(
+                    r#"SELECT
+                    DATE({timestamp}) as date,
                     COUNT(*)::TEXT as value
                 FROM smart_contracts
-                GROUP BY DATE(smart_contracts.inserted_at)"#,
+                GROUP BY DATE({timestamp})"#
+                ),
                 vec![],
             ),
         };
@@ -64,6 +93,14 @@ impl crate::Chart for NewVerifiedContracts {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
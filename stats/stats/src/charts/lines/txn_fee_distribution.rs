@@ -0,0 +1,193 @@
+use crate::{
+    charts::{
+        insert::{DateDistribution, DateValue, DISTRIBUTION_SERIES},
+        timezone,
+        updater::ChartDistributionUpdater,
+    },
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct TxnFeeDistribution {}
+
+const ETHER: i64 = i64::pow(10, 18);
+
+#[async_trait]
+impl ChartDistributionUpdater for TxnFeeDistribution {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+    ) -> Result<Vec<DateDistribution>, UpdateError> {
+        let timestamp = timezone::current().local_sql("b.timestamp");
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                SELECT
+                    date,
+                    percentile_cont(0.1) WITHIN GROUP (ORDER BY value) as p10,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY value) as p50,
+                    percentile_cont(0.9) WITHIN GROUP (ORDER BY value) as p90,
+                    MAX(value) as max
+                FROM (
+                    SELECT
+                        DATE({timestamp}) as date,
+                        (t.gas_used * t.gas_price / $1)::FLOAT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        DATE({timestamp}) > $2 AND
+                        b.consensus = true
+                ) v
+                GROUP BY date
+                "#
+                ),
+                vec![ETHER.into(), row.date.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                SELECT
+                    date,
+                    percentile_cont(0.1) WITHIN GROUP (ORDER BY value) as p10,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY value) as p50,
+                    percentile_cont(0.9) WITHIN GROUP (ORDER BY value) as p90,
+                    MAX(value) as max
+                FROM (
+                    SELECT
+                        DATE({timestamp}) as date,
+                        (t.gas_used * t.gas_price / $1)::FLOAT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE b.consensus = true
+                ) v
+                GROUP BY date
+                "#
+                ),
+                vec![ETHER.into()],
+            ),
+        };
+
+        let data = DateDistribution::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for TxnFeeDistribution {
+    fn name(&self) -> &str {
+        "txnFeeDistribution"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn series(&self) -> &[&'static str] {
+        &DISTRIBUTION_SERIES
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_distribution_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_txn_fee_distribution() {
+        let chart = TxnFeeDistribution::default();
+        simple_test_distribution_chart(
+            "update_txn_fee_distribution",
+            chart,
+            vec![
+                (
+                    "2022-11-09",
+                    ["0", "0", "0.000023592592569", "0.000023592592569"],
+                ),
+                (
+                    "2022-11-10",
+                    [
+                        "0.000023592592569",
+                        "0.0000353888888535",
+                        "0.000070777777707",
+                        "0.000070777777707",
+                    ],
+                ),
+                (
+                    "2022-11-11",
+                    [
+                        "0.000023592592569",
+                        "0.000023592592569",
+                        "0.000141555555414",
+                        "0.000141555555414",
+                    ],
+                ),
+                (
+                    "2022-11-12",
+                    [
+                        "0.000023592592569",
+                        "0.000188740740552",
+                        "0.000188740740552",
+                        "0.000188740740552",
+                    ],
+                ),
+                (
+                    "2022-12-01",
+                    [
+                        "0.000023592592569",
+                        "0.000212333333121",
+                        "0.000212333333121",
+                        "0.000212333333121",
+                    ],
+                ),
+                (
+                    "2023-01-01",
+                    [
+                        "0.000023592592569",
+                        "0.000023592592569",
+                        "0.000023592592569",
+                        "0.000023592592569",
+                    ],
+                ),
+                (
+                    "2023-02-01",
+                    [
+                        "0.000094370370276",
+                        "0.000259518518259",
+                        "0.000259518518259",
+                        "0.000259518518259",
+                    ],
+                ),
+                (
+                    "2023-03-01",
+                    [
+                        "0.000023592592569",
+                        "0.000023592592569",
+                        "0.000023592592569",
+                        "0.000023592592569",
+                    ],
+                ),
+            ],
+        )
+        .await;
+    }
+}
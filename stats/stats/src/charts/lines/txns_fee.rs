@@ -1,70 +1,37 @@
 use crate::{
-    charts::{
-        insert::{DateValue, DateValueDouble},
-        updater::ChartPartialUpdater,
-    },
-    UpdateError,
+    charts::{create_chart, txns_rollup::TxnsRollup, updater::ChartRollupUpdater, ArcChart, Chart},
+    DateValue, Resolution, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use sea_orm::prelude::*;
+use std::sync::Arc;
 
 #[derive(Default, Debug)]
-pub struct TxnsFee {}
+pub struct TxnsFee {
+    rollup: Arc<TxnsRollup>,
+}
 
-const ETHER: i64 = i64::pow(10, 18);
+impl TxnsFee {
+    pub fn new(rollup: Arc<TxnsRollup>) -> Self {
+        Self { rollup }
+    }
+}
 
-#[async_trait]
-impl ChartPartialUpdater for TxnsFee {
-    async fn get_values(
-        &self,
-        blockscout: &DatabaseConnection,
-        last_row: Option<DateValue>,
-    ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    DATE(b.timestamp) as date, 
-                    (SUM(t.gas_used * t.gas_price) / $1)::FLOAT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE
-                    DATE(b.timestamp) > $2 AND
-                    b.consensus = true
-                GROUP BY DATE(b.timestamp)
-                "#,
-                vec![ETHER.into(), row.date.into()],
-            ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    DATE(b.timestamp) as date, 
-                    (SUM(t.gas_used * t.gas_price) / $1)::FLOAT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE b.consensus = true
-                GROUP BY DATE(b.timestamp)
-                "#,
-                vec![ETHER.into()],
-            ),
-        };
+impl ChartRollupUpdater for TxnsFee {
+    fn rollup(&self) -> Arc<TxnsRollup> {
+        self.rollup.clone()
+    }
 
-        let data = DateValueDouble::find_by_statement(stmnt)
-            .all(blockscout)
-            .await
-            .map_err(UpdateError::BlockscoutDB)?
-            .into_iter()
-            .map(DateValue::from)
-            .collect::<Vec<_>>();
-        Ok(data)
+    fn value_sql(&self) -> &'static str {
+        // fee in ether
+        "SUM(fee_sum) / 1000000000000000000"
     }
 }
 
 #[async_trait]
-impl crate::Chart for TxnsFee {
+impl Chart for TxnsFee {
     fn name(&self) -> &str {
         "txnsFee"
     }
@@ -73,6 +40,24 @@ impl crate::Chart for TxnsFee {
         ChartType::Line
     }
 
+    fn resolutions(&self) -> &[Resolution] {
+        &[
+            Resolution::Day,
+            Resolution::Hour,
+            Resolution::Week,
+            Resolution::Month,
+        ]
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.rollup.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.rollup.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
@@ -81,6 +66,14 @@ impl crate::Chart for TxnsFee {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,106 @@
+use super::{NativeCoinPrice, TxnsFee};
+use crate::{
+    charts::{create_chart, fiat::MissingPrice, updater::ChartFiatUpdater, ArcChart, Chart},
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Transaction fees of the day valued at the closing price of the day
+#[derive(Default, Debug)]
+pub struct TxnsFeeUsd {
+    parent: Arc<TxnsFee>,
+    price: Arc<NativeCoinPrice>,
+    missing_price: MissingPrice,
+}
+
+impl TxnsFeeUsd {
+    pub fn new(
+        parent: Arc<TxnsFee>,
+        price: Arc<NativeCoinPrice>,
+        missing_price: MissingPrice,
+    ) -> Self {
+        Self {
+            parent,
+            price,
+            missing_price,
+        }
+    }
+}
+
+impl ChartFiatUpdater for TxnsFeeUsd {
+    fn parent(&self) -> ArcChart {
+        self.parent.clone()
+    }
+
+    fn price(&self) -> Arc<NativeCoinPrice> {
+        self.price.clone()
+    }
+
+    fn missing_price(&self) -> MissingPrice {
+        self.missing_price
+    }
+}
+
+#[async_trait]
+impl Chart for TxnsFeeUsd {
+    fn name(&self) -> &str {
+        "txnsFeeUsd"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone(), self.price.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.parent.create(db).await?;
+        self.price.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_txns_fee_usd() {
+        let chart = TxnsFeeUsd::new(
+            Default::default(),
+            Default::default(),
+            MissingPrice::CarryForward,
+        );
+        simple_test_chart(
+            "update_txns_fee_usd",
+            chart,
+            vec![
+                ("2022-11-09", "0.000070777777707"),
+                ("2022-11-10", "0.000990888887898"),
+                ("2022-11-11", "0.001934592590658"),
+                ("2022-11-12", "0.001840222220382"),
+                ("2022-12-01", "0.002052555553503"),
+                ("2023-01-01", "0.000070777777707"),
+                ("2023-02-01", "0.002406444442038"),
+                ("2023-03-01", "0.000070777777707"),
+            ],
+        )
+        .await;
+    }
+}
@@ -1,73 +1,37 @@
 use crate::{
-    charts::{
-        insert::{DateValue, DateValueDouble},
-        updater::ChartPartialUpdater,
-    },
-    UpdateError,
+    charts::{create_chart, txns_rollup::TxnsRollup, updater::ChartRollupUpdater, ArcChart, Chart},
+    DateValue, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use sea_orm::prelude::*;
+use std::sync::Arc;
 
 #[derive(Default, Debug)]
-pub struct TxnsSuccessRate {}
+pub struct TxnsSuccessRate {
+    rollup: Arc<TxnsRollup>,
+}
 
-#[async_trait]
-impl ChartPartialUpdater for TxnsSuccessRate {
-    async fn get_values(
-        &self,
-        blockscout: &DatabaseConnection,
-        last_row: Option<DateValue>,
-    ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    DATE(b.timestamp) as date, 
-                    COUNT(CASE WHEN t.error IS NULL THEN 1 END)::FLOAT
-                        / COUNT(*)::FLOAT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE b.consensus = true 
-                    AND t.block_hash IS NOT NULL 
-                    AND (t.error IS NULL OR t.error::text != 'dropped/replaced')
-                    AND DATE(b.timestamp) > $1
-                GROUP BY DATE(b.timestamp)
-                "#,
-                vec![row.date.into()],
-            ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    DATE(b.timestamp) as date, 
-                    COUNT(CASE WHEN t.error IS NULL THEN 1 END)::FLOAT
-                        / COUNT(*)::FLOAT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE b.consensus = true 
-                    AND t.block_hash IS NOT NULL 
-                    AND (t.error IS NULL OR t.error::text != 'dropped/replaced')
-                GROUP BY DATE(b.timestamp)
-                "#,
-                vec![],
-            ),
-        };
+impl TxnsSuccessRate {
+    pub fn new(rollup: Arc<TxnsRollup>) -> Self {
+        Self { rollup }
+    }
+}
+
+impl ChartRollupUpdater for TxnsSuccessRate {
+    fn rollup(&self) -> Arc<TxnsRollup> {
+        self.rollup.clone()
+    }
 
-        let data = DateValueDouble::find_by_statement(stmnt)
-            .all(blockscout)
-            .await
-            .map_err(UpdateError::BlockscoutDB)?
-            .into_iter()
-            .map(DateValue::from)
-            .collect::<Vec<_>>();
-        Ok(data)
+    fn value_sql(&self) -> &'static str {
+        // dropped and replaced transactions are not counted
+        "SUM(succeeded_txns)::FLOAT / NULLIF(SUM(finished_txns), 0)::FLOAT"
     }
 }
 
 #[async_trait]
-impl crate::Chart for TxnsSuccessRate {
+impl Chart for TxnsSuccessRate {
     fn name(&self) -> &str {
         "txnsSuccessRate"
     }
@@ -76,6 +40,15 @@ impl crate::Chart for TxnsSuccessRate {
         ChartType::Line
     }
 
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.rollup.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.rollup.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
@@ -84,6 +57,14 @@ impl crate::Chart for TxnsSuccessRate {
     ) -> Result<(), UpdateError> {
         self.update_with_values(db, blockscout, force_full).await
     }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
 }
 
 #[cfg(test)]
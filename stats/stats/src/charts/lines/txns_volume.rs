@@ -0,0 +1,103 @@
+use crate::{
+    charts::{create_chart, txns_rollup::TxnsRollup, updater::ChartRollupUpdater, ArcChart, Chart},
+    DateValue, Resolution, UpdateError,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Native coins transferred by successful transactions per day
+#[derive(Default, Debug)]
+pub struct TxnsVolume {
+    rollup: Arc<TxnsRollup>,
+}
+
+impl TxnsVolume {
+    pub fn new(rollup: Arc<TxnsRollup>) -> Self {
+        Self { rollup }
+    }
+}
+
+impl ChartRollupUpdater for TxnsVolume {
+    fn rollup(&self) -> Arc<TxnsRollup> {
+        self.rollup.clone()
+    }
+
+    fn value_sql(&self) -> &'static str {
+        // volume in ether, periods without transferred coins are skipped
+        "NULLIF(SUM(value_sum), 0) / 1000000000000000000"
+    }
+}
+
+#[async_trait]
+impl Chart for TxnsVolume {
+    fn name(&self) -> &str {
+        "txnsVolume"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn resolutions(&self) -> &[Resolution] {
+        &[
+            Resolution::Day,
+            Resolution::Hour,
+            Resolution::Week,
+            Resolution::Month,
+        ]
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.rollup.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.rollup.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        self.recalculate_values(blockscout, dates).await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TxnsVolume;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_txns_volume() {
+        let chart = TxnsVolume::default();
+        simple_test_chart(
+            "update_txns_volume",
+            chart,
+            vec![
+                ("2022-11-09", "0.000002"),
+                ("2022-11-10", "0.000004"),
+                ("2022-11-11", "0.000004"),
+                ("2022-11-12", "0.000002"),
+                ("2022-12-01", "0.000002"),
+                ("2023-02-01", "0.000002"),
+            ],
+        )
+        .await;
+    }
+}
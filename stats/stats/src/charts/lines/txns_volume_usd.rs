@@ -0,0 +1,97 @@
+use super::{NativeCoinPrice, TxnsVolume};
+use crate::{
+    charts::{create_chart, fiat::MissingPrice, updater::ChartFiatUpdater, ArcChart, Chart},
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Native coins transferred per day valued at the closing price of the day
+#[derive(Default, Debug)]
+pub struct TxnsVolumeUsd {
+    parent: Arc<TxnsVolume>,
+    price: Arc<NativeCoinPrice>,
+    missing_price: MissingPrice,
+}
+
+impl TxnsVolumeUsd {
+    pub fn new(
+        parent: Arc<TxnsVolume>,
+        price: Arc<NativeCoinPrice>,
+        missing_price: MissingPrice,
+    ) -> Self {
+        Self {
+            parent,
+            price,
+            missing_price,
+        }
+    }
+}
+
+impl ChartFiatUpdater for TxnsVolumeUsd {
+    fn parent(&self) -> ArcChart {
+        self.parent.clone()
+    }
+
+    fn price(&self) -> Arc<NativeCoinPrice> {
+        self.price.clone()
+    }
+
+    fn missing_price(&self) -> MissingPrice {
+        self.missing_price
+    }
+}
+
+#[async_trait]
+impl Chart for TxnsVolumeUsd {
+    fn name(&self) -> &str {
+        "txnsVolumeUsd"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone(), self.price.clone()]
+    }
+
+    async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        self.parent.create(db).await?;
+        self.price.create(db).await?;
+        create_chart(db, self.name().into(), self.chart_type()).await
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, force_full).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_txns_volume_usd() {
+        let chart = TxnsVolumeUsd::default();
+        simple_test_chart(
+            "update_txns_volume_usd",
+            chart,
+            vec![
+                ("2022-11-09", "0.000003"),
+                ("2022-11-10", "0.000008"),
+                ("2022-11-12", "0.000006"),
+            ],
+        )
+        .await;
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
         create_chart,
         insert::DateValue,
         updater::{parse_and_growth, ChartDependentUpdater},
+        ArcChart,
     },
     UpdateError,
 };
@@ -30,8 +31,12 @@ impl ChartDependentUpdater<NewVerifiedContracts> for VerifiedContractsGrowth {
         self.parent.clone()
     }
 
-    async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError> {
-        parse_and_growth::<i64>(parent_data, self.parent.name())
+    async fn get_values(
+        &self,
+        last_row: Option<DateValue>,
+        parent_data: Vec<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        parse_and_growth::<i64>(parent_data, last_row, self.name(), self.parent.name())
     }
 }
 
@@ -45,6 +50,10 @@ impl crate::Chart for VerifiedContractsGrowth {
         ChartType::Line
     }
 
+    fn dependencies(&self) -> Vec<ArcChart> {
+        vec![self.parent.clone()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -1,9 +1,25 @@
+pub mod alert_state;
+pub mod audit;
 pub mod cache;
+pub mod chain;
 mod chart;
+pub mod counter_history;
 pub mod counters;
+pub mod expression;
+pub mod fiat;
 pub mod insert;
+pub mod leaderboards;
 pub mod lines;
 mod mutex;
+pub mod reorg;
+pub mod resolution;
+pub mod sql_chart;
+pub mod timezone;
+pub mod tokens;
+pub mod txns_rollup;
 pub mod updater;
 
-pub use chart::{create_chart, find_chart, Chart, UpdateError};
+pub use chart::{
+    create_chart, find_chart, set_update_failed, set_update_succeeded, ArcChart, Chart, UpdateError,
+};
+pub use mutex::is_update_mutex_locked;
@@ -2,19 +2,29 @@ use lazy_static::lazy_static;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{Mutex, RwLock};
 
+type MutexKey = (String, String);
+
 lazy_static! {
-    pub static ref UPDATE_MUTEX: RwLock<HashMap<String, Arc<Mutex<()>>>> = RwLock::default();
+    pub static ref UPDATE_MUTEX: RwLock<HashMap<MutexKey, Arc<Mutex<()>>>> = RwLock::default();
 }
 
-pub async fn get_global_update_mutex(key: &str) -> Arc<Mutex<()>> {
-    let maybe_mutex = UPDATE_MUTEX.read().await.get(key).cloned();
+pub async fn get_global_update_mutex(chain_id: &str, name: &str) -> Arc<Mutex<()>> {
+    let key = (chain_id.to_owned(), name.to_owned());
+    let maybe_mutex = UPDATE_MUTEX.read().await.get(&key).cloned();
     match maybe_mutex {
         Some(mutex) => mutex,
         None => {
             let mut map = UPDATE_MUTEX.write().await;
-            let mutex = Arc::new(Mutex::default());
-            map.insert(key.to_owned(), mutex.clone());
-            mutex
+            map.entry(key).or_default().clone()
         }
     }
 }
+
+/// Whether update of the chart of the chain is running or waits for another update
+pub async fn is_update_mutex_locked(chain_id: &str, name: &str) -> bool {
+    let key = (chain_id.to_owned(), name.to_owned());
+    let maybe_mutex = UPDATE_MUTEX.read().await.get(&key).cloned();
+    maybe_mutex
+        .map(|mutex| mutex.try_lock().is_err())
+        .unwrap_or_default()
+}
@@ -0,0 +1,516 @@
+use super::{
+    resolution::{start_of_day, Resolution},
+    timezone::Timezone,
+    updater::clear_batch_progress,
+};
+use crate::UpdateError;
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use entity::{
+    chart_data, charts, kv_storage,
+    sea_orm_active_enums::{ChartResolution, ChartType},
+};
+use sea_orm::{
+    prelude::*, sea_query, ConnectionTrait, DbBackend, FromQueryResult, QuerySelect, Set,
+    Statement, TransactionTrait,
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    str::FromStr,
+};
+
+const WATERMARK_KEY: &str = "reorg_check:watermark";
+const HANDLED_KEY: &str = "reorg_check:handled";
+const INVALIDATED_KEY_PREFIX: &str = "reorg_check:invalidated_from:";
+const TIMEZONE_KEY_PREFIX: &str = "timezone:";
+
+/// Dates that are recalculated by every partial update anyway
+const RECENT_DAYS: i64 = 1;
+
+/// Period before the saved watermark that is checked again.
+/// `updated_at` is set before the change is committed, so blocks committed
+/// after the previous check can have it earlier than the watermark
+const WATERMARK_OVERLAP_MINUTES: i64 = 5;
+
+fn invalidated_key(chart_name: &str) -> String {
+    format!("{INVALIDATED_KEY_PREFIX}{chart_name}")
+}
+
+async fn get_value<C: ConnectionTrait>(db: &C, key: &str) -> Result<Option<String>, DbErr> {
+    kv_storage::Entity::find_by_id(key.to_owned())
+        .one(db)
+        .await
+        .map(|row| row.map(|row| row.value))
+}
+
+async fn set_value<C: ConnectionTrait>(db: &C, key: &str, value: String) -> Result<(), DbErr> {
+    kv_storage::Entity::insert(kv_storage::ActiveModel {
+        key: Set(key.to_owned()),
+        value: Set(value),
+    })
+    .on_conflict(
+        sea_query::OnConflict::column(kv_storage::Column::Key)
+            .update_column(kv_storage::Column::Value)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+    Ok(())
+}
+
+/// Earliest date of the chart that was affected by reorg and is not recalculated yet
+pub async fn get_invalidated_from<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+) -> Result<Option<NaiveDate>, DbErr> {
+    let value = get_invalidation(db, chart_name).await?;
+    Ok(value.and_then(|value| {
+        let date = value.split_whitespace().next()?;
+        NaiveDate::from_str(date).ok()
+    }))
+}
+
+/// Saved invalidation of the chart: the earliest invalidated date and the time of invalidation.
+/// Every invalidation changes the value, even if the date stays the same
+pub async fn get_invalidation<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+) -> Result<Option<String>, DbErr> {
+    get_value(db, &invalidated_key(chart_name)).await
+}
+
+/// Makes the next update of the chart recalculate points starting from the date
+pub async fn invalidate_from<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+    date: NaiveDate,
+) -> Result<(), DbErr> {
+    let invalidated_from = get_invalidated_from(db, chart_name)
+        .await?
+        .map_or(date, |invalidated| invalidated.min(date));
+    set_value(
+        db,
+        &invalidated_key(chart_name),
+        format!("{invalidated_from} {}", Utc::now().naive_utc()),
+    )
+    .await
+}
+
+/// Removes the invalidation read by [`get_invalidation`] before the update of the chart.
+/// Invalidations made during the update are kept, so their points are recalculated next time
+pub async fn clear_invalidation<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+    invalidation: &str,
+) -> Result<(), DbErr> {
+    kv_storage::Entity::delete_many()
+        .filter(kv_storage::Column::Key.eq(invalidated_key(chart_name)))
+        .filter(kv_storage::Column::Value.eq(invalidation))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[derive(FromQueryResult)]
+struct FirstDate {
+    date: Option<NaiveDate>,
+}
+
+/// Saves timezone that points of the chart are calculated in.
+///
+/// If the chart was calculated in another timezone (UTC if it was never saved),
+/// all its points are invalidated, so the next update recalculates the whole chart
+/// and replaces them. Returns the previous timezone if it was changed
+pub async fn save_chart_timezone(
+    db: &DatabaseConnection,
+    chart_name: &str,
+    timezone: Timezone,
+) -> Result<Option<Timezone>, DbErr> {
+    let key = format!("{TIMEZONE_KEY_PREFIX}{chart_name}");
+    let saved = get_value(db, &key)
+        .await?
+        .and_then(|value| Timezone::from_str(&value).ok())
+        .unwrap_or_default();
+    let txn = db.begin().await?;
+    let changed = saved != timezone;
+    if changed {
+        let first_date = FirstDate::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT MIN(data.date) as date
+            FROM (
+                SELECT chart_id, date FROM chart_data
+                UNION ALL
+                SELECT chart_id, date FROM leaderboard_data
+            ) data
+            JOIN charts ON data.chart_id = charts.id
+            WHERE charts.name = $1;
+            "#,
+            vec![chart_name.into()],
+        ))
+        .one(&txn)
+        .await?
+        .and_then(|row| row.date);
+        if let Some(date) = first_date {
+            invalidate_from(&txn, chart_name, date).await?;
+            clear_batch_progress(&txn, chart_name).await?;
+        }
+    }
+    set_value(&txn, &key, timezone.to_string()).await?;
+    txn.commit().await?;
+    Ok(changed.then_some(saved))
+}
+
+#[derive(FromQueryResult)]
+struct Watermark {
+    updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(FromQueryResult)]
+struct ChangedBlock {
+    hash: Vec<u8>,
+    updated_at: NaiveDateTime,
+    time: NaiveDateTime,
+}
+
+impl ChangedBlock {
+    /// Identifies the change of the block, so it's handled only once
+    fn change_key(&self) -> String {
+        let hash: String = self.hash.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!("{hash}@{}", self.updated_at)
+    }
+}
+
+#[derive(FromQueryResult)]
+struct ChartInfo {
+    id: i32,
+    name: String,
+    chart_type: ChartType,
+}
+
+/// Local dates of the times that are not recalculated by partial updates anyway
+fn affected_dates(times: &[NaiveDateTime], timezone: Timezone) -> BTreeSet<NaiveDate> {
+    let recent_date = timezone.today() - Duration::days(RECENT_DAYS);
+    times
+        .iter()
+        .map(|time| timezone.local_time(*time).date())
+        .filter(|date| *date < recent_date)
+        .collect()
+}
+
+/// Finds blocks that were changed in blockscout since the previous check
+/// (consensus flips, replaced blocks) and removes points of line charts
+/// for the dates of these blocks.
+///
+/// Partial updates always recalculate the latest days, so only older dates are checked.
+/// Every check also looks [`WATERMARK_OVERLAP_MINUTES`] before the previous one
+/// and skips blocks that the previous check has already handled.
+/// Dates are taken in the timezone of every chart from `timezones`, UTC if it's missing.
+/// All charts are marked, so their next update starts from the earliest removed date:
+/// counters calculated from line charts keep running totals of these dates.
+/// The first check only remembers the current state of blockscout.
+/// Returns all invalidated dates.
+pub async fn invalidate_reorged_dates(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    timezones: &HashMap<String, Timezone>,
+) -> Result<Vec<NaiveDate>, UpdateError> {
+    let new_watermark = Watermark::find_by_statement(Statement::from_string(
+        DbBackend::Postgres,
+        "SELECT MAX(updated_at) as updated_at FROM blocks;".into(),
+    ))
+    .one(blockscout)
+    .await
+    .map_err(UpdateError::BlockscoutDB)?
+    .and_then(|watermark| watermark.updated_at);
+    let new_watermark = match new_watermark {
+        Some(watermark) => watermark,
+        None => return Ok(vec![]),
+    };
+    let old_watermark = get_value(db, WATERMARK_KEY)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .and_then(|value| NaiveDateTime::from_str(&value).ok());
+    let old_watermark = match old_watermark {
+        Some(watermark) => watermark,
+        None => {
+            set_value(db, WATERMARK_KEY, new_watermark.to_string())
+                .await
+                .map_err(UpdateError::StatsDB)?;
+            return Ok(vec![]);
+        }
+    };
+
+    let handled: HashSet<String> = get_value(db, HANDLED_KEY)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .map(|value| value.split(',').map(str::to_owned).collect())
+        .unwrap_or_default();
+    let overlap = Duration::minutes(WATERMARK_OVERLAP_MINUTES);
+    // blocks of recent days are not affected in any timezone
+    let recent_time = Utc::now().naive_utc() - Duration::days(RECENT_DAYS);
+    // minutes are precise enough for any utc offset
+    let changed = ChangedBlock::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT hash, updated_at, date_trunc('minute', timestamp) as time
+        FROM blocks
+        WHERE
+            updated_at > $1 AND
+            updated_at <= $2 AND
+            timestamp < $3;
+        "#,
+        vec![
+            (old_watermark - overlap).into(),
+            new_watermark.into(),
+            recent_time.into(),
+        ],
+    ))
+    .all(blockscout)
+    .await
+    .map_err(UpdateError::BlockscoutDB)?;
+    let times: Vec<NaiveDateTime> = changed
+        .iter()
+        .filter(|block| !handled.contains(&block.change_key()))
+        .map(|block| block.time)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    // the next check looks at these blocks again
+    let handled: Vec<String> = changed
+        .iter()
+        .filter(|block| block.updated_at > new_watermark - overlap)
+        .map(ChangedBlock::change_key)
+        .collect();
+
+    let mut all_dates = BTreeSet::new();
+    let txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+    if !times.is_empty() {
+        let all_charts = charts::Entity::find()
+            .select_only()
+            .column(charts::Column::Id)
+            .column(charts::Column::Name)
+            .column(charts::Column::ChartType)
+            .into_model::<ChartInfo>()
+            .all(&txn)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        for chart in all_charts {
+            let timezone = timezones.get(&chart.name).copied().unwrap_or_default();
+            let dates = affected_dates(&times, timezone);
+            let first_date = match dates.iter().next() {
+                Some(date) => *date,
+                None => continue,
+            };
+            if chart.chart_type == ChartType::Line {
+                for resolution in [
+                    Resolution::Hour,
+                    Resolution::Day,
+                    Resolution::Week,
+                    Resolution::Month,
+                ] {
+                    let periods: BTreeSet<NaiveDate> = dates
+                        .iter()
+                        .map(|date| resolution.period_start(start_of_day(*date)).date())
+                        .collect();
+                    chart_data::Entity::delete_many()
+                        .filter(chart_data::Column::ChartId.eq(chart.id))
+                        .filter(
+                            chart_data::Column::Resolution.eq(ChartResolution::from(resolution)),
+                        )
+                        .filter(chart_data::Column::Date.is_in(periods))
+                        .exec(&txn)
+                        .await
+                        .map_err(UpdateError::StatsDB)?;
+                }
+            }
+            invalidate_from(&txn, &chart.name, first_date)
+                .await
+                .map_err(UpdateError::StatsDB)?;
+            all_dates.extend(dates);
+        }
+        if !all_dates.is_empty() {
+            tracing::warn!(dates = ?all_dates, "found changed blocks, invalidated chart points");
+        }
+    }
+    set_value(&txn, WATERMARK_KEY, new_watermark.to_string())
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    set_value(&txn, HANDLED_KEY, handled.join(","))
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    txn.commit().await.map_err(UpdateError::StatsDB)?;
+    Ok(all_dates.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        get_chart_data,
+        lines::NewBlocks,
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        Chart,
+    };
+    use blockscout_db::entity::blocks;
+    use pretty_assertions::assert_eq;
+    use sea_orm::sea_query::Expr;
+
+    #[test]
+    fn affected_dates_are_local() {
+        let times = [NaiveDateTime::from_str("2022-11-10T22:30:00").unwrap()];
+        assert_eq!(
+            BTreeSet::from([NaiveDate::from_str("2022-11-10").unwrap()]),
+            affected_dates(&times, Timezone::utc())
+        );
+        assert_eq!(
+            BTreeSet::from([NaiveDate::from_str("2022-11-11").unwrap()]),
+            affected_dates(&times, "+03:00".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn timezone_change_invalidates_chart() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("timezone_change_invalidates_chart", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        let chart = NewBlocks::default();
+        chart.create(&db).await.unwrap();
+        let utc = Timezone::utc();
+        let moscow: Timezone = "+03:00".parse().unwrap();
+
+        // chart without points is not invalidated
+        assert_eq!(
+            Some(utc),
+            save_chart_timezone(&db, chart.name(), moscow)
+                .await
+                .unwrap()
+        );
+        assert_eq!(None, get_invalidated_from(&db, chart.name()).await.unwrap());
+
+        chart
+            .update_with_mutex(&db, &blockscout, true)
+            .await
+            .unwrap();
+        assert_eq!(
+            None,
+            save_chart_timezone(&db, chart.name(), moscow)
+                .await
+                .unwrap()
+        );
+        assert_eq!(None, get_invalidated_from(&db, chart.name()).await.unwrap());
+
+        assert_eq!(
+            Some(moscow),
+            save_chart_timezone(&db, chart.name(), utc).await.unwrap()
+        );
+        let first_date = get_chart_data(&db, chart.name(), None, None)
+            .await
+            .unwrap()
+            .first()
+            .map(|point| point.date);
+        assert_eq!(
+            first_date,
+            get_invalidated_from(&db, chart.name()).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn reorg_invalidates_affected_dates() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("reorg_invalidates_affected_dates", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        let chart = NewBlocks::default();
+        chart.create(&db).await.unwrap();
+        chart
+            .update_with_mutex(&db, &blockscout, true)
+            .await
+            .unwrap();
+
+        // first check only saves watermark
+        assert_eq!(
+            Vec::<NaiveDate>::new(),
+            invalidate_reorged_dates(&db, &blockscout, &HashMap::new())
+                .await
+                .unwrap()
+        );
+
+        // block of 2022-11-10 lost consensus
+        let reorg_time = NaiveDateTime::from_str("2030-01-01T00:00:00").unwrap();
+        blocks::Entity::update_many()
+            .col_expr(blocks::Column::Consensus, Expr::value(false))
+            .col_expr(blocks::Column::UpdatedAt, Expr::value(reorg_time))
+            .filter(blocks::Column::Number.eq(2))
+            .exec(&blockscout)
+            .await
+            .unwrap();
+        let date = NaiveDate::from_str("2022-11-10").unwrap();
+        assert_eq!(
+            vec![date],
+            invalidate_reorged_dates(&db, &blockscout, &HashMap::new())
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(date),
+            get_invalidated_from(&db, chart.name()).await.unwrap()
+        );
+        let data = get_chart_data(&db, chart.name(), None, None).await.unwrap();
+        assert!(data.iter().all(|point| point.date != date));
+
+        chart
+            .update_with_mutex(&db, &blockscout, false)
+            .await
+            .unwrap();
+        let data = get_chart_data(&db, chart.name(), None, None).await.unwrap();
+        let point = data.iter().find(|point| point.date == date).unwrap();
+        assert_eq!("2", point.value);
+        assert_eq!(None, get_invalidated_from(&db, chart.name()).await.unwrap());
+
+        // invalidation made during the update is kept
+        let invalidation = get_invalidation(&db, chart.name()).await.unwrap();
+        assert_eq!(None, invalidation);
+        invalidate_from(&db, chart.name(), date).await.unwrap();
+        let invalidation = get_invalidation(&db, chart.name()).await.unwrap().unwrap();
+        invalidate_from(&db, chart.name(), date).await.unwrap();
+        clear_invalidation(&db, chart.name(), &invalidation)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(date),
+            get_invalidated_from(&db, chart.name()).await.unwrap()
+        );
+        chart
+            .update_with_mutex(&db, &blockscout, false)
+            .await
+            .unwrap();
+        assert_eq!(None, get_invalidated_from(&db, chart.name()).await.unwrap());
+
+        // block of 2022-11-11 was committed after the check, but changed before it
+        blocks::Entity::update_many()
+            .col_expr(blocks::Column::Consensus, Expr::value(false))
+            .col_expr(
+                blocks::Column::UpdatedAt,
+                Expr::value(reorg_time - Duration::minutes(1)),
+            )
+            .filter(blocks::Column::Number.eq(4))
+            .exec(&blockscout)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![NaiveDate::from_str("2022-11-11").unwrap()],
+            invalidate_reorged_dates(&db, &blockscout, &HashMap::new())
+                .await
+                .unwrap()
+        );
+
+        // nothing changed since the last check
+        assert_eq!(
+            Vec::<NaiveDate>::new(),
+            invalidate_reorged_dates(&db, &blockscout, &HashMap::new())
+                .await
+                .unwrap()
+        );
+    }
+}
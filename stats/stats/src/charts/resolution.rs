@@ -0,0 +1,204 @@
+use super::timezone::Timezone;
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Timelike};
+use entity::sea_orm_active_enums::ChartResolution;
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// Granularity at which chart points are materialized.
+///
+/// Every point is identified by the start of its period:
+/// hour start for `Hour`, day for `Day`,
+/// monday for `Week` and the first day of month for `Month`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Resolution {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("unknown resolution '{0}'")]
+pub struct ParseResolutionError(String);
+
+impl Resolution {
+    /// Field name for postgres `date_trunc` function
+    pub fn sql_precision(&self) -> &'static str {
+        match self {
+            Resolution::Hour => "hour",
+            Resolution::Day => "day",
+            Resolution::Week => "week",
+            Resolution::Month => "month",
+        }
+    }
+
+    pub fn period_start(&self, time: NaiveDateTime) -> NaiveDateTime {
+        let date = time.date();
+        let start = match self {
+            Resolution::Hour => {
+                return date
+                    .and_hms_opt(time.hour(), 0, 0)
+                    .expect("hour of existing time is valid")
+            }
+            Resolution::Day => date,
+            Resolution::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            Resolution::Month => date.with_day(1).expect("first day of month always exists"),
+        };
+        start_of_day(start)
+    }
+
+    pub fn period_end(&self, start: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Resolution::Hour => start + Duration::hours(1),
+            Resolution::Day => start + Duration::days(1),
+            Resolution::Week => start + Duration::weeks(1),
+            Resolution::Month => start
+                .checked_add_months(Months::new(1))
+                .expect("date out of range"),
+        }
+    }
+
+    /// Whether the period that starts at `start` is not finished yet
+    /// in the timezone of the chart
+    pub fn is_partial(&self, start: NaiveDateTime, timezone: Timezone) -> bool {
+        self.period_end(start) > timezone.now()
+    }
+
+    pub fn format_timespan(&self, start: NaiveDateTime) -> String {
+        match self {
+            Resolution::Hour => start.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            _ => start.date().to_string(),
+        }
+    }
+}
+
+pub fn start_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(0, 0, 0).expect("midnight always exists")
+}
+
+impl Display for Resolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.sql_precision())
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = ParseResolutionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hour" => Ok(Resolution::Hour),
+            "day" => Ok(Resolution::Day),
+            "week" => Ok(Resolution::Week),
+            "month" => Ok(Resolution::Month),
+            _ => Err(ParseResolutionError(s.to_owned())),
+        }
+    }
+}
+
+impl From<Resolution> for ChartResolution {
+    fn from(value: Resolution) -> Self {
+        match value {
+            Resolution::Hour => ChartResolution::Hour,
+            Resolution::Day => ChartResolution::Day,
+            Resolution::Week => ChartResolution::Week,
+            Resolution::Month => ChartResolution::Month,
+        }
+    }
+}
+
+impl From<ChartResolution> for Resolution {
+    fn from(value: ChartResolution) -> Self {
+        match value {
+            ChartResolution::Hour => Resolution::Hour,
+            ChartResolution::Day => Resolution::Day,
+            ChartResolution::Week => Resolution::Week,
+            ChartResolution::Month => Resolution::Month,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::from_str(s).expect("cannot parse datetime")
+    }
+
+    #[test]
+    fn period_bounds_work() {
+        for (resolution, time, start, end) in [
+            (
+                Resolution::Hour,
+                "2022-11-10T12:34:56",
+                "2022-11-10T12:00:00",
+                "2022-11-10T13:00:00",
+            ),
+            (
+                Resolution::Hour,
+                "2022-11-10T23:59:59",
+                "2022-11-10T23:00:00",
+                "2022-11-11T00:00:00",
+            ),
+            (
+                Resolution::Day,
+                "2022-11-10T12:34:56",
+                "2022-11-10T00:00:00",
+                "2022-11-11T00:00:00",
+            ),
+            (
+                Resolution::Week,
+                "2022-11-09T23:59:59",
+                "2022-11-07T00:00:00",
+                "2022-11-14T00:00:00",
+            ),
+            (
+                Resolution::Week,
+                "2023-01-01T10:00:00",
+                "2022-12-26T00:00:00",
+                "2023-01-02T00:00:00",
+            ),
+            (
+                Resolution::Month,
+                "2022-12-31T10:00:00",
+                "2022-12-01T00:00:00",
+                "2023-01-01T00:00:00",
+            ),
+            (
+                Resolution::Month,
+                "2023-02-01T00:00:00",
+                "2023-02-01T00:00:00",
+                "2023-03-01T00:00:00",
+            ),
+        ] {
+            let actual_start = resolution.period_start(dt(time));
+            assert_eq!(dt(start), actual_start, "{resolution} start of {time}");
+            assert_eq!(
+                dt(end),
+                resolution.period_end(actual_start),
+                "{resolution} end of {time}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_resolution_works() {
+        assert_eq!(Ok(Resolution::Hour), Resolution::from_str("HOUR"));
+        assert_eq!(Ok(Resolution::Week), Resolution::from_str("week"));
+        assert_eq!(
+            Err(ParseResolutionError("year".into())),
+            Resolution::from_str("year")
+        );
+        for resolution in [
+            Resolution::Hour,
+            Resolution::Day,
+            Resolution::Week,
+            Resolution::Month,
+        ] {
+            assert_eq!(Ok(resolution), resolution.to_string().parse());
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{
+    charts::{
+        insert::DateValue,
+        timezone,
+        updater::{get_min_date_blockscout, ChartBatchUpdater, ChartFullUpdater},
+    },
+    UpdateError,
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement, Value};
+use thiserror::Error;
+
+const FROM_PLACEHOLDER: &str = "{from}";
+const TO_PLACEHOLDER: &str = "{to}";
+const UTC_OFFSET_PLACEHOLDER: &str = "{utc_offset}";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SqlTemplateError {
+    #[error("query of line chart '{0}' must contain {{from}} and {{to}} placeholders")]
+    MissingPlaceholder(String),
+    #[error("query of chart '{0}' is empty")]
+    Empty(String),
+    #[error("chart '{0}' defined by query must be a counter or a line chart")]
+    UnsupportedType(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    From,
+    To,
+    UtcOffset,
+}
+
+/// Chart defined by sql query in config.
+///
+/// Query is executed against blockscout database and has to return `date` and `value::TEXT` columns.
+/// `{from}` and `{to}` placeholders are substituted with the dates of half-open interval to calculate.
+/// `{utc_offset}` is substituted with the `interval` of the chart timezone, so dates
+/// of the timezone are calculated as `date(timestamp + {utc_offset})`.
+/// Line charts are calculated in batches, counters are calculated over the whole history at once.
+#[derive(Debug)]
+pub struct SqlChart {
+    name: String,
+    chart_type: ChartType,
+    query: String,
+    params: Vec<Placeholder>,
+}
+
+impl SqlChart {
+    pub fn new(
+        name: String,
+        chart_type: ChartType,
+        template: &str,
+    ) -> Result<Self, SqlTemplateError> {
+        if chart_type == ChartType::Leaderboard {
+            return Err(SqlTemplateError::UnsupportedType(name));
+        }
+        if template.trim().is_empty() {
+            return Err(SqlTemplateError::Empty(name));
+        }
+        let (query, params) = compile_template(template);
+        if chart_type == ChartType::Line
+            && !(params.contains(&Placeholder::From) && params.contains(&Placeholder::To))
+        {
+            return Err(SqlTemplateError::MissingPlaceholder(name));
+        }
+        Ok(Self {
+            name,
+            chart_type,
+            query,
+            params,
+        })
+    }
+
+    fn statement(&self, from: NaiveDate, to: NaiveDate) -> Statement {
+        let values: Vec<Value> = self
+            .params
+            .iter()
+            .map(|param| match param {
+                Placeholder::From => from.into(),
+                Placeholder::To => to.into(),
+                Placeholder::UtcOffset => timezone::current().sql_interval().into(),
+            })
+            .collect();
+        Statement::from_sql_and_values(DbBackend::Postgres, &self.query, values)
+    }
+}
+
+/// Replaces placeholders with positional parameters in order of their first appearance
+fn compile_template(template: &str) -> (String, Vec<Placeholder>) {
+    let mut params = vec![];
+    let mut query = String::with_capacity(template.len());
+    let mut rest = template;
+    loop {
+        let next = [
+            (FROM_PLACEHOLDER, Placeholder::From),
+            (TO_PLACEHOLDER, Placeholder::To),
+            (UTC_OFFSET_PLACEHOLDER, Placeholder::UtcOffset),
+        ]
+        .into_iter()
+        .filter_map(|(pattern, param)| rest.find(pattern).map(|pos| (pos, pattern, param)))
+        .min_by_key(|(pos, _, _)| *pos);
+        match next {
+            Some((pos, pattern, param)) => {
+                let index = match params.iter().position(|p| *p == param) {
+                    Some(index) => index,
+                    None => {
+                        params.push(param);
+                        params.len() - 1
+                    }
+                };
+                query.push_str(&rest[..pos]);
+                query.push_str(&format!("${}", index + 1));
+                // parameter is passed as text
+                if param == Placeholder::UtcOffset {
+                    query.push_str("::interval");
+                }
+                rest = &rest[pos + pattern.len()..];
+            }
+            None => {
+                query.push_str(rest);
+                break;
+            }
+        }
+    }
+    (query, params)
+}
+
+#[async_trait]
+impl ChartBatchUpdater for SqlChart {
+    fn get_query(&self, from: NaiveDate, to: NaiveDate) -> Statement {
+        self.statement(from, to)
+    }
+}
+
+#[async_trait]
+impl ChartFullUpdater for SqlChart {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let timezone = timezone::current();
+        let from = get_min_date_blockscout(blockscout)
+            .await
+            .map(|time| timezone.local_time(time).date())
+            .map_err(UpdateError::BlockscoutDB)?;
+        let to = timezone.today() + Duration::days(1);
+        let data = DateValue::find_by_statement(self.statement(from, to))
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for SqlChart {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chart_type(&self) -> ChartType {
+        self.chart_type.clone()
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        match self.chart_type {
+            ChartType::Line => {
+                ChartBatchUpdater::update_with_values(self, db, blockscout, force_full).await
+            }
+            ChartType::Counter => {
+                ChartFullUpdater::update_with_values(self, db, blockscout, force_full).await
+            }
+            ChartType::Leaderboard | ChartType::Internal => Err(UpdateError::Internal(format!(
+                "chart '{}' of unsupported type",
+                self.name
+            ))),
+        }
+    }
+
+    /// Counters are calculated only for the current day, so only lines are recalculated
+    async fn recalculate(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Option<Vec<DateValue>>, UpdateError> {
+        match self.chart_type {
+            ChartType::Line => ChartBatchUpdater::recalculate_values(self, blockscout, dates)
+                .await
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::{simple_test_chart, simple_test_counter};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn compile_template_works() {
+        for (template, expected_query, expected_params) in [
+            (
+                "SELECT * FROM blocks WHERE date >= {from} AND date < {to}",
+                "SELECT * FROM blocks WHERE date >= $1 AND date < $2",
+                vec![Placeholder::From, Placeholder::To],
+            ),
+            (
+                "SELECT {to}, {from}, {to}",
+                "SELECT $1, $2, $1",
+                vec![Placeholder::To, Placeholder::From],
+            ),
+            (
+                "SELECT COUNT(*)::TEXT FROM blocks",
+                "SELECT COUNT(*)::TEXT FROM blocks",
+                vec![],
+            ),
+            (
+                "{from}{from}{unknown}",
+                "$1$1{unknown}",
+                vec![Placeholder::From],
+            ),
+            (
+                "SELECT date(timestamp + {utc_offset}) WHERE date >= {from}",
+                "SELECT date(timestamp + $1::interval) WHERE date >= $2",
+                vec![Placeholder::UtcOffset, Placeholder::From],
+            ),
+        ] {
+            let (query, params) = compile_template(template);
+            assert_eq!(expected_query, query);
+            assert_eq!(expected_params, params);
+        }
+    }
+
+    #[test]
+    fn line_requires_placeholders() {
+        assert_eq!(
+            SqlTemplateError::MissingPlaceholder("line".into()),
+            SqlChart::new("line".into(), ChartType::Line, "SELECT {from}").unwrap_err()
+        );
+        assert_eq!(
+            SqlTemplateError::Empty("counter".into()),
+            SqlChart::new("counter".into(), ChartType::Counter, "  ").unwrap_err()
+        );
+        assert_eq!(
+            SqlTemplateError::UnsupportedType("top".into()),
+            SqlChart::new("top".into(), ChartType::Leaderboard, "SELECT 1").unwrap_err()
+        );
+        SqlChart::new("counter".into(), ChartType::Counter, "SELECT 1").unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_sql_line() {
+        let chart = SqlChart::new(
+            "sqlNewBlocks".into(),
+            ChartType::Line,
+            r#"
+            SELECT date(timestamp) as date, COUNT(*)::TEXT as value
+            FROM blocks
+            WHERE
+                consensus = true AND
+                date(timestamp) >= {from} AND
+                date(timestamp) < {to}
+            GROUP BY date
+            "#,
+        )
+        .unwrap();
+        simple_test_chart(
+            "update_sql_line",
+            chart,
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "3"),
+                ("2022-11-11", "4"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1"),
+                ("2023-01-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_sql_counter() {
+        let counter = SqlChart::new(
+            "sqlTotalBlocks".into(),
+            ChartType::Counter,
+            r#"
+            SELECT MAX(date(timestamp)) as date, COUNT(*)::TEXT as value
+            FROM blocks
+            WHERE consensus = true AND date(timestamp) < {to}
+            "#,
+        )
+        .unwrap();
+        simple_test_counter("update_sql_counter", counter, "13").await;
+    }
+}
@@ -0,0 +1,177 @@
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use std::{fmt::Display, future::Future, str::FromStr};
+use thiserror::Error;
+
+tokio::task_local! {
+    static CURRENT: Timezone;
+}
+
+/// Fixed UTC offset that defines day boundaries of chart points.
+///
+/// Blockscout stores block timestamps in UTC, charts bucket them by dates
+/// of the timezone. Default is UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timezone(FixedOffset);
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid timezone '{0}': expected 'UTC' or offset like '+03:00'")]
+pub struct ParseTimezoneError(String);
+
+impl Timezone {
+    pub fn utc() -> Self {
+        Self(FixedOffset::east_opt(0).expect("zero offset is valid"))
+    }
+
+    pub fn offset_seconds(&self) -> i32 {
+        self.0.local_minus_utc()
+    }
+
+    pub fn is_utc(&self) -> bool {
+        self.offset_seconds() == 0
+    }
+
+    /// Converts utc time to the local time of the timezone
+    pub fn local_time(&self, utc: NaiveDateTime) -> NaiveDateTime {
+        utc + Duration::seconds(self.offset_seconds().into())
+    }
+
+    /// Converts local time of the timezone to utc time
+    pub fn utc_time(&self, local: NaiveDateTime) -> NaiveDateTime {
+        local - Duration::seconds(self.offset_seconds().into())
+    }
+
+    pub fn now(&self) -> NaiveDateTime {
+        self.local_time(Utc::now().naive_utc())
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// Sql expression of local time for utc timestamp `column`
+    pub fn local_sql(&self, column: &str) -> String {
+        if self.is_utc() {
+            column.to_owned()
+        } else {
+            format!("({column} + interval '{} seconds')", self.offset_seconds())
+        }
+    }
+
+    /// Offset in format of postgres `interval`
+    pub fn sql_interval(&self) -> String {
+        format!("{} seconds", self.offset_seconds())
+    }
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::utc()
+    }
+}
+
+impl Display for Timezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_utc() {
+            f.write_str("UTC")
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = ParseTimezoneError;
+
+    /// Accepts `UTC` and offsets `+HH`, `+HH:MM` and `+HHMM` (or with `-` sign)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseTimezoneError(s.to_owned());
+        let trimmed = s.trim();
+        if trimmed.eq_ignore_ascii_case("utc") || trimmed == "Z" {
+            return Ok(Self::utc());
+        }
+        let (sign, rest) = if let Some(rest) = trimmed.strip_prefix('+') {
+            (1, rest)
+        } else if let Some(rest) = trimmed.strip_prefix('-') {
+            (-1, rest)
+        } else {
+            return Err(err());
+        };
+        if !rest.is_ascii() {
+            return Err(err());
+        }
+        let (hours, minutes) = match (rest.len(), rest.split_once(':')) {
+            (_, Some((hours, minutes))) => (hours, minutes),
+            (1 | 2, None) => (rest, "00"),
+            (4, None) => rest.split_at(2),
+            _ => return Err(err()),
+        };
+        if hours.is_empty() || hours.len() > 2 || minutes.len() != 2 {
+            return Err(err());
+        }
+        let hours: i32 = hours.parse().map_err(|_| err())?;
+        let minutes: i32 = minutes.parse().map_err(|_| err())?;
+        if minutes >= 60 {
+            return Err(err());
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Self)
+            .ok_or_else(err)
+    }
+}
+
+/// Runs chart update in the timezone, see [`current`]
+pub async fn scope<F: Future>(timezone: Timezone, f: F) -> F::Output {
+    CURRENT.scope(timezone, f).await
+}
+
+/// Timezone of the running chart update, UTC outside of [`scope`]
+pub fn current() -> Timezone {
+    CURRENT.try_with(|timezone| *timezone).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_timezone_works() {
+        for (s, offset) in [
+            ("UTC", 0),
+            ("utc", 0),
+            ("+00:00", 0),
+            ("+03:00", 3 * 3600),
+            ("+3", 3 * 3600),
+            ("-05:30", -(5 * 3600 + 30 * 60)),
+            ("+0545", 5 * 3600 + 45 * 60),
+        ] {
+            assert_eq!(
+                Ok(offset),
+                Timezone::from_str(s).map(|timezone| timezone.offset_seconds()),
+                "{s}"
+            );
+        }
+        for s in ["", "03:00", "+3:60", "+25:00", "Europe/Berlin", "+123"] {
+            assert_eq!(
+                Err(ParseTimezoneError(s.into())),
+                Timezone::from_str(s),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn display_roundtrip_works() {
+        for s in ["UTC", "+03:00", "-05:30"] {
+            assert_eq!(s, Timezone::from_str(s).unwrap().to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn scope_sets_current_timezone() {
+        let timezone = Timezone::from_str("+03:00").unwrap();
+        assert_eq!(Timezone::utc(), current());
+        assert_eq!(timezone, scope(timezone, async { current() }).await);
+        assert_eq!(Timezone::utc(), current());
+    }
+}
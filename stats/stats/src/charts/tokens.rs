@@ -0,0 +1,324 @@
+use crate::{
+    charts::{
+        insert::DateValue,
+        timezone,
+        updater::{ChartFullUpdater, ChartPartialUpdater},
+    },
+    UpdateError,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ParseTokenError {
+    #[error("unknown token chart '{0}'")]
+    UnknownKind(String),
+    #[error("invalid token address '{0}'")]
+    InvalidAddress(String),
+}
+
+/// Kind of the chart calculated for a single token contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenChartKind {
+    /// Number of token transfers per day
+    Transfers,
+    /// Number of distinct senders of token transfers per day
+    UniqueSenders,
+    /// Number of addresses with positive balance.
+    /// History is collected as snapshots, one point per update
+    Holders,
+}
+
+impl TokenChartKind {
+    pub fn all() -> [TokenChartKind; 3] {
+        [
+            TokenChartKind::Transfers,
+            TokenChartKind::UniqueSenders,
+            TokenChartKind::Holders,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            TokenChartKind::Transfers => "tokenTransfers",
+            TokenChartKind::UniqueSenders => "tokenUniqueSenders",
+            TokenChartKind::Holders => "tokenHolders",
+        }
+    }
+}
+
+impl Display for TokenChartKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for TokenChartKind {
+    type Err = ParseTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .into_iter()
+            .find(|kind| kind.id() == s)
+            .ok_or_else(|| ParseTokenError::UnknownKind(s.to_owned()))
+    }
+}
+
+/// Parses `0x`-prefixed hex string into 20 bytes of address
+pub fn parse_token_address(s: &str) -> Result<Vec<u8>, ParseTokenError> {
+    let invalid = || ParseTokenError::InvalidAddress(s.to_owned());
+    let hex = s.strip_prefix("0x").ok_or_else(invalid)?;
+    if hex.len() != 40 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Line chart parameterized by token contract address.
+///
+/// Every (kind, address) pair is stored as a separate chart named `{kind}_0x{address}`,
+/// so that charts of every configured token are created and updated independently.
+#[derive(Debug, Clone)]
+pub struct TokenLine {
+    kind: TokenChartKind,
+    address: Vec<u8>,
+    name: String,
+}
+
+impl TokenLine {
+    pub fn new(kind: TokenChartKind, address: Vec<u8>) -> Self {
+        let hex: String = address.iter().map(|byte| format!("{byte:02x}")).collect();
+        let name = format!("{}_0x{}", kind.id(), hex);
+        Self {
+            kind,
+            address,
+            name,
+        }
+    }
+
+    pub fn kind(&self) -> TokenChartKind {
+        self.kind
+    }
+
+    fn transfers_statement(&self, value_expr: &str, last_row: Option<DateValue>) -> Statement {
+        let timestamp = timezone::current().local_sql("b.timestamp");
+        match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                    SELECT
+                        date({timestamp}) as date,
+                        {value_expr}::TEXT as value
+                    FROM token_transfers tt
+                    JOIN blocks          b ON tt.block_hash = b.hash
+                    WHERE
+                        tt.token_contract_address_hash = $1 AND
+                        date({timestamp}) > $2 AND
+                        b.consensus = true
+                    GROUP BY date;
+                    "#
+                ),
+                vec![self.address.clone().into(), row.date.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &format!(
+                    r#"
+                    SELECT
+                        date({timestamp}) as date,
+                        {value_expr}::TEXT as value
+                    FROM token_transfers tt
+                    JOIN blocks          b ON tt.block_hash = b.hash
+                    WHERE
+                        tt.token_contract_address_hash = $1 AND
+                        b.consensus = true
+                    GROUP BY date;
+                    "#
+                ),
+                vec![self.address.clone().into()],
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl ChartPartialUpdater for TokenLine {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let value_expr = match self.kind {
+            TokenChartKind::Transfers => "COUNT(*)",
+            TokenChartKind::UniqueSenders => "COUNT(DISTINCT tt.from_address_hash)",
+            TokenChartKind::Holders => {
+                return Err(UpdateError::Internal(format!(
+                    "chart {} cannot be updated partially",
+                    self.name
+                )))
+            }
+        };
+        let data = DateValue::find_by_statement(self.transfers_statement(value_expr, last_row))
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl ChartFullUpdater for TokenLine {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        // current balances have no history, so the value is saved
+        // as of the date of the last indexed block
+        let timezone = timezone::current();
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &format!(
+                r#"
+            SELECT
+                COALESCE(
+                    (SELECT MAX(date({})) FROM blocks WHERE consensus = true),
+                    $2
+                ) as date,
+                COUNT(*)::TEXT as value
+            FROM address_current_token_balances
+            WHERE
+                token_contract_address_hash = $1 AND
+                value > 0;
+            "#,
+                timezone.local_sql("timestamp")
+            ),
+            vec![self.address.clone().into(), timezone.today().into()],
+        );
+        let data = DateValue::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for TokenLine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        match self.kind {
+            TokenChartKind::Transfers | TokenChartKind::UniqueSenders => {
+                ChartPartialUpdater::update_with_values(self, db, blockscout, force_full).await
+            }
+            TokenChartKind::Holders => {
+                ChartFullUpdater::update_with_values(self, db, blockscout, force_full).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tests::{mock_blockscout::mock_token_address, simple_test::simple_test_chart},
+        Chart,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_works() {
+        assert_eq!(
+            Ok(TokenChartKind::UniqueSenders),
+            TokenChartKind::from_str("tokenUniqueSenders")
+        );
+        assert_eq!(
+            Err(ParseTokenError::UnknownKind("newTxns".into())),
+            TokenChartKind::from_str("newTxns")
+        );
+
+        let address = "0x00000000000000000000000000000000000000ff";
+        let parsed = parse_token_address(address).unwrap();
+        assert_eq!(20, parsed.len());
+        assert_eq!(Some(&0xff), parsed.last());
+        assert_eq!(
+            format!("tokenHolders_{address}"),
+            TokenLine::new(TokenChartKind::Holders, parsed).name()
+        );
+
+        for invalid in [
+            "00000000000000000000000000000000000000ff",
+            "0x00ff",
+            "0x0000000000000000000000000000000000000zff",
+        ] {
+            assert_eq!(
+                Err(ParseTokenError::InvalidAddress(invalid.into())),
+                parse_token_address(invalid)
+            );
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_token_transfers() {
+        let chart = TokenLine::new(TokenChartKind::Transfers, mock_token_address());
+        simple_test_chart(
+            "update_token_transfers",
+            chart,
+            vec![
+                ("2022-11-09", "2"),
+                ("2022-11-10", "4"),
+                ("2022-11-11", "4"),
+                ("2022-11-12", "2"),
+                ("2022-12-01", "2"),
+                ("2023-02-01", "2"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_token_unique_senders() {
+        let chart = TokenLine::new(TokenChartKind::UniqueSenders, mock_token_address());
+        simple_test_chart(
+            "update_token_unique_senders",
+            chart,
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "2"),
+                ("2022-11-11", "2"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_token_holders() {
+        let chart = TokenLine::new(TokenChartKind::Holders, mock_token_address());
+        simple_test_chart("update_token_holders", chart, vec![("2023-03-01", "6")]).await;
+    }
+}
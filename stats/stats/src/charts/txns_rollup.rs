@@ -0,0 +1,423 @@
+use super::{
+    chain,
+    reorg::get_invalidated_from,
+    resolution::start_of_day,
+    timezone::{self, Timezone},
+    updater::get_min_block_blockscout,
+};
+use crate::{
+    charts::insert::{DateValue, TimespanValue, TimespanValueDouble},
+    metrics, Chart, Resolution, UpdateError,
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{
+    prelude::*, DbBackend, FromQueryResult, Statement, TransactionTrait, Value as SqlValue,
+};
+
+const INSERT_CHUNK_ROWS: usize = 1000;
+
+/// Aggregates of blockscout transactions per hour, stored in `txns_rollup` table.
+///
+/// This is the only chart that scans blockscout transactions,
+/// line charts of transactions depend on it and read the rollup instead.
+/// Every update recalculates hours starting from the last saved day.
+/// The rollup is not served, so it's registered as [`ChartType::Internal`].
+#[derive(Default, Debug)]
+pub struct TxnsRollup {}
+
+#[derive(FromQueryResult, Debug)]
+struct RollupRow {
+    hour: NaiveDateTime,
+    txns: i64,
+    finished_txns: i64,
+    succeeded_txns: i64,
+    // numeric sums are passed as text, so they are not limited by decimal precision
+    fee_sum: String,
+    fee_count: i64,
+    gas_price_sum: String,
+    gas_price_count: i64,
+    value_sum: String,
+}
+
+/// Rows of the rollup calculated from blockscout, `filter` is applied to blocks `b`
+fn rows_sql(filter: &str) -> String {
+    format!(
+        r#"
+            SELECT
+                date_trunc('hour', b.timestamp) as hour,
+                COUNT(*) as txns,
+                COUNT(*) FILTER (
+                    WHERE t.error IS NULL OR t.error::text != 'dropped/replaced'
+                ) as finished_txns,
+                COUNT(*) FILTER (WHERE t.error IS NULL) as succeeded_txns,
+                COALESCE(SUM(t.gas_used * t.gas_price), 0)::TEXT as fee_sum,
+                COUNT(t.gas_used * t.gas_price) as fee_count,
+                COALESCE(SUM(t.gas_price), 0)::TEXT as gas_price_sum,
+                COUNT(t.gas_price) as gas_price_count,
+                COALESCE(SUM(t.value) FILTER (WHERE t.error IS NULL), 0)::TEXT as value_sum
+            FROM transactions t
+            JOIN blocks       b ON t.block_hash = b.hash
+            WHERE b.consensus = true {filter}
+            GROUP BY hour
+        "#
+    )
+}
+
+#[derive(FromQueryResult, Debug)]
+struct LastHour {
+    hour: NaiveDateTime,
+    min_blockscout_block: i64,
+}
+
+impl TxnsRollup {
+    /// Utc hours of the rollup make up whole days only in timezones with whole hour offsets,
+    /// so charts of the rollup can't use other timezones
+    pub fn supports_timezone(timezone: Timezone) -> bool {
+        timezone.offset_seconds() % 3600 == 0
+    }
+
+    /// Start of the hours to recalculate, `None` means all hours
+    async fn recalculate_from(
+        &self,
+        db: &DatabaseConnection,
+        min_blockscout_block: i64,
+        force_full: bool,
+    ) -> Result<Option<NaiveDateTime>, UpdateError> {
+        if force_full {
+            tracing::info!(
+                chart = self.name(),
+                "running full update due to force override"
+            );
+            return Ok(None);
+        }
+        let last = LastHour::find_by_statement(Statement::from_string(
+            DbBackend::Postgres,
+            r#"
+            SELECT hour, min_blockscout_block
+            FROM txns_rollup
+            ORDER BY hour DESC
+            LIMIT 1;
+            "#
+            .into(),
+        ))
+        .one(db)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+        let from = match last {
+            Some(last) if last.min_blockscout_block == min_blockscout_block => {
+                start_of_day(last.hour.date())
+            }
+            Some(last) => {
+                tracing::info!(
+                    min_blockscout_block = min_blockscout_block,
+                    min_chart_block = last.min_blockscout_block,
+                    chart = self.name(),
+                    "running full update due to min blocks mismatch"
+                );
+                return Ok(None);
+            }
+            None => {
+                tracing::info!(
+                    chart = self.name(),
+                    "running full update due to lack of history data"
+                );
+                return Ok(None);
+            }
+        };
+        let invalidated_from = get_invalidated_from(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        // invalidated date is a local date of the timezone of the update,
+        // while hours of the rollup are in utc
+        let timezone = timezone::current();
+        Ok(Some(match invalidated_from {
+            Some(date) => from.min(timezone.utc_time(start_of_day(date))),
+            None => from,
+        }))
+    }
+
+    async fn get_rows(
+        &self,
+        blockscout: &DatabaseConnection,
+        from: Option<NaiveDateTime>,
+    ) -> Result<Vec<RollupRow>, UpdateError> {
+        let (filter, values): (_, Vec<SqlValue>) = match from {
+            Some(from) => ("AND b.timestamp >= $1", vec![from.into()]),
+            None => ("", vec![]),
+        };
+        let sql = format!("{};", rows_sql(filter));
+        let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+            .with_label_values(&[&chain::current(), self.name()])
+            .start_timer();
+        RollupRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            values,
+        ))
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::BlockscoutDB)
+    }
+
+    async fn save_rows(
+        &self,
+        db: &DatabaseConnection,
+        from: Option<NaiveDateTime>,
+        rows: Vec<RollupRow>,
+        min_blockscout_block: i64,
+    ) -> Result<(), DbErr> {
+        let txn = db.begin().await?;
+        let delete = match from {
+            Some(from) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "DELETE FROM txns_rollup WHERE hour >= $1;",
+                vec![from.into()],
+            ),
+            None => Statement::from_string(DbBackend::Postgres, "DELETE FROM txns_rollup;".into()),
+        };
+        txn.execute(delete).await?;
+        for chunk in rows.chunks(INSERT_CHUNK_ROWS) {
+            let mut placeholders = Vec::with_capacity(chunk.len());
+            let mut values: Vec<SqlValue> = Vec::with_capacity(chunk.len() * 10);
+            for row in chunk {
+                let n = values.len();
+                placeholders.push(format!(
+                    "(${}, ${}, ${}, ${}, ${}::numeric, ${}, ${}::numeric, ${}, ${}::numeric, ${})",
+                    n + 1,
+                    n + 2,
+                    n + 3,
+                    n + 4,
+                    n + 5,
+                    n + 6,
+                    n + 7,
+                    n + 8,
+                    n + 9,
+                    n + 10
+                ));
+                let row_values: [SqlValue; 10] = [
+                    row.hour.into(),
+                    row.txns.into(),
+                    row.finished_txns.into(),
+                    row.succeeded_txns.into(),
+                    row.fee_sum.clone().into(),
+                    row.fee_count.into(),
+                    row.gas_price_sum.clone().into(),
+                    row.gas_price_count.into(),
+                    row.value_sum.clone().into(),
+                    min_blockscout_block.into(),
+                ];
+                values.extend(row_values);
+            }
+            let sql = format!(
+                r#"
+                INSERT INTO txns_rollup (
+                    hour, txns, finished_txns, succeeded_txns, fee_sum,
+                    fee_count, gas_price_sum, gas_price_count, value_sum, min_blockscout_block
+                )
+                VALUES {};
+                "#,
+                placeholders.join(", ")
+            );
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                &sql,
+                values,
+            ))
+            .await?;
+        }
+        txn.commit().await
+    }
+}
+
+#[async_trait]
+impl Chart for TxnsRollup {
+    fn name(&self) -> &str {
+        "txnsRollup"
+    }
+
+    /// The rollup is not served, chart type is used only to register it in `charts` table
+    fn chart_type(&self) -> ChartType {
+        ChartType::Internal
+    }
+
+    /// Hours are stored in utc, charts of the rollup shift them to their timezone
+    fn uses_timezone(&self) -> bool {
+        false
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let from = self
+            .recalculate_from(db, min_blockscout_block, force_full)
+            .await?;
+        let rows = self.get_rows(blockscout, from).await?;
+        self.save_rows(db, from, rows, min_blockscout_block)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
+}
+
+/// Points of the chart calculated from the rollup.
+///
+/// `value_sql` is an aggregate of `txns_rollup` columns with `FLOAT` type,
+/// periods where it is `NULL` are skipped.
+/// Hours are shifted to the current timezone, which must be supported by
+/// [`TxnsRollup::supports_timezone`].
+pub async fn get_rollup_values(
+    db: &DatabaseConnection,
+    value_sql: &str,
+    resolution: Resolution,
+    last_row: Option<NaiveDateTime>,
+) -> Result<Vec<TimespanValueDouble>, UpdateError> {
+    let timezone = timezone::current();
+    let hour = timezone.local_sql("hour");
+    // filter by raw hour, so that the index on it could be used
+    let (filter, mut values): (_, Vec<SqlValue>) = match last_row {
+        Some(timespan) => (
+            "WHERE hour >= $2",
+            vec![timezone.utc_time(resolution.period_end(timespan)).into()],
+        ),
+        None => ("", vec![]),
+    };
+    values.insert(0, resolution.sql_precision().into());
+    let sql = format!(
+        r#"
+        SELECT timespan, value
+        FROM (
+            SELECT
+                date_trunc($1, {hour}) as timespan,
+                ({value_sql})::FLOAT as value
+            FROM txns_rollup
+            {filter}
+            GROUP BY timespan
+        ) points
+        WHERE value IS NOT NULL;
+        "#
+    );
+    TimespanValueDouble::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        &sql,
+        values,
+    ))
+    .all(db)
+    .await
+    .map_err(UpdateError::StatsDB)
+}
+
+/// Daily points of the dates calculated like [`get_rollup_values`],
+/// but from rollup rows of blockscout instead of `txns_rollup` table.
+/// Every date is queried separately, so that only its blocks are read
+pub async fn recalculate_rollup_values(
+    blockscout: &DatabaseConnection,
+    value_sql: &str,
+    dates: &[NaiveDate],
+) -> Result<Vec<DateValue>, UpdateError> {
+    let timezone = timezone::current();
+    let rows = rows_sql("AND b.timestamp >= $1 AND b.timestamp < $2");
+    let hour = timezone.local_sql("hour");
+    let sql = format!(
+        r#"
+        WITH hourly AS ({rows}),
+        txns_rollup AS (
+            SELECT
+                hour, txns, finished_txns, succeeded_txns, fee_sum::numeric as fee_sum,
+                fee_count, gas_price_sum::numeric as gas_price_sum, gas_price_count,
+                value_sum::numeric as value_sum
+            FROM hourly
+        )
+        SELECT timespan, value
+        FROM (
+            SELECT
+                date_trunc($3, {hour}) as timespan,
+                ({value_sql})::FLOAT as value
+            FROM txns_rollup
+            GROUP BY timespan
+        ) points
+        WHERE value IS NOT NULL;
+        "#
+    );
+    let mut values = Vec::with_capacity(dates.len());
+    for date in dates {
+        let from = timezone.utc_time(start_of_day(*date));
+        let to = timezone.utc_time(start_of_day(*date + Duration::days(1)));
+        let found = TimespanValueDouble::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            vec![
+                from.into(),
+                to.into(),
+                Resolution::Day.sql_precision().into(),
+            ],
+        ))
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::BlockscoutDB)?
+        .into_iter()
+        .map(|value| DateValue::from(TimespanValue::from(value)))
+        .filter(|value| value.date == *date);
+        values.extend(found);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
+    use pretty_assertions::assert_eq;
+
+    #[derive(FromQueryResult, Debug, PartialEq, Eq)]
+    struct Totals {
+        hours: i64,
+        txns: i64,
+        succeeded_txns: i64,
+    }
+
+    async fn totals(db: &DatabaseConnection) -> Totals {
+        Totals::find_by_statement(Statement::from_string(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                COUNT(*) as hours,
+                SUM(txns)::BIGINT as txns,
+                SUM(succeeded_txns)::BIGINT as succeeded_txns
+            FROM txns_rollup;
+            "#
+            .into(),
+        ))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_txns_rollup() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_txns_rollup", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        let rollup = TxnsRollup::default();
+        rollup.create(&db).await.unwrap();
+
+        let expected = Totals {
+            hours: 13,
+            txns: 47,
+            succeeded_txns: 46,
+        };
+        rollup.update(&db, &blockscout, true).await.unwrap();
+        assert_eq!(expected, totals(&db).await);
+
+        rollup.update(&db, &blockscout, false).await.unwrap();
+        assert_eq!(expected, totals(&db).await);
+    }
+}
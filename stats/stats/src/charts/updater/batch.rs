@@ -1,10 +1,14 @@
-use super::{get_last_row, get_min_block_blockscout, get_min_date_blockscout};
+use super::{
+    get_last_row, get_min_block_blockscout, get_min_date_blockscout,
+    progress::{clear_batch_progress, get_batch_progress, save_batch_progress, BatchProgress},
+    query_dates,
+};
 use crate::{
-    charts::{find_chart, insert::insert_data_many},
+    charts::{chain, find_chart, insert::insert_data_many, timezone},
     metrics, Chart, DateValue, UpdateError,
 };
 use async_trait::async_trait;
-use chrono::{Duration, NaiveDate, Utc};
+use chrono::{Duration, NaiveDate};
 use sea_orm::{DatabaseConnection, FromQueryResult, Statement, TransactionTrait};
 use std::time::Instant;
 
@@ -15,6 +19,14 @@ pub trait ChartBatchUpdater: Chart {
         chrono::Duration::days(30)
     }
 
+    async fn recalculate_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        query_dates(blockscout, dates, |from, to| Ok(self.get_query(from, to))).await
+    }
+
     async fn update_with_values(
         &self,
         db: &DatabaseConnection,
@@ -29,9 +41,15 @@ pub trait ChartBatchUpdater: Chart {
             .await
             .map_err(UpdateError::BlockscoutDB)?;
         let last_row = get_last_row(self, chart_id, min_blockscout_block, db, force_full).await?;
+        // full update starts from the beginning, not from interrupted update
+        if force_full {
+            clear_batch_progress(db, self.name())
+                .await
+                .map_err(UpdateError::StatsDB)?;
+        }
 
         let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
-            .with_label_values(&[self.name()])
+            .with_label_values(&[&chain::current(), self.name()])
             .start_timer();
         tracing::info!(last_row =? last_row, "start batch update");
         self.batch_update(db, blockscout, last_row, chart_id, min_blockscout_block)
@@ -50,14 +68,38 @@ pub trait ChartBatchUpdater: Chart {
             .begin()
             .await
             .map_err(UpdateError::BlockscoutDB)?;
-        let first_date = match last_row {
-            Some(last_row) => last_row.date,
-            None => get_min_date_blockscout(&txn)
-                .await
-                .map(|time| time.date())
-                .map_err(UpdateError::BlockscoutDB)?,
+        let timezone = timezone::current();
+        let last_date = timezone.today();
+        // progress is valid only if blockscout wasn't reindexed since it was saved
+        let saved_progress = get_batch_progress(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .filter(|progress| progress.min_blockscout_block == min_blockscout_block);
+        let (range_start, first_date) = match saved_progress {
+            Some(progress) => {
+                tracing::info!(progress =? progress, "resume interrupted batch update");
+                (progress.range_start, progress.completed_to)
+            }
+            None => {
+                let first_date = match last_row {
+                    Some(last_row) => last_row.date,
+                    None => get_min_date_blockscout(&txn)
+                        .await
+                        .map(|time| timezone.local_time(time).date())
+                        .map_err(UpdateError::BlockscoutDB)?,
+                };
+                (first_date, first_date)
+            }
+        };
+        let mut progress = BatchProgress {
+            range_start,
+            range_end: last_date,
+            completed_to: first_date,
+            min_blockscout_block,
         };
-        let last_date = Utc::now().date_naive();
+        let progress_gauge =
+            metrics::BATCH_UPDATE_PROGRESS.with_label_values(&[&chain::current(), self.name()]);
+        progress_gauge.set(progress.ratio());
 
         let steps = generate_date_ranges(first_date, last_date, self.step_duration());
         let n = steps.len();
@@ -75,10 +117,22 @@ pub trait ChartBatchUpdater: Chart {
             let elapsed = now.elapsed();
             let found = values.len();
             tracing::info!(found =? found, elapsed =? elapsed, "{}/{} step of batch done", i + 1, n);
-            insert_data_many(db, values)
+
+            progress.completed_to = to.min(last_date);
+            let stats_txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+            insert_data_many(&stats_txn, values)
+                .await
+                .map_err(UpdateError::StatsDB)?;
+            save_batch_progress(&stats_txn, self.name(), &progress)
                 .await
                 .map_err(UpdateError::StatsDB)?;
+            stats_txn.commit().await.map_err(UpdateError::StatsDB)?;
+            progress_gauge.set(progress.ratio());
         }
+        clear_batch_progress(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        progress_gauge.set(1.0);
         Ok(())
     }
 }
@@ -103,6 +157,11 @@ fn generate_date_ranges(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        get_chart_data,
+        lines::NewContracts,
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+    };
     use chrono::NaiveDate;
     use pretty_assertions::assert_eq;
     use std::str::FromStr;
@@ -138,4 +197,74 @@ mod tests {
             assert_eq!(expected, actual);
         }
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_resumes_from_saved_progress() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_resumes_from_saved_progress", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        let chart = NewContracts::default();
+        chart.create(&db).await.unwrap();
+
+        let min_blockscout_block = get_min_block_blockscout(&blockscout).await.unwrap();
+        let progress = BatchProgress {
+            range_start: d("2022-11-09"),
+            range_end: d("2023-03-01"),
+            completed_to: d("2022-12-01"),
+            min_blockscout_block,
+        };
+        save_batch_progress(&db, chart.name(), &progress)
+            .await
+            .unwrap();
+
+        chart.update(&db, &blockscout, false).await.unwrap();
+        let data: Vec<_> = get_chart_data(&db, chart.name(), None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|point| (point.date.to_string(), point.value))
+            .collect();
+        let expected: Vec<_> = [
+            ("2022-12-01", "2"),
+            ("2023-01-01", "1"),
+            ("2023-02-01", "1"),
+        ]
+        .into_iter()
+        .map(|(date, value)| (date.to_string(), value.to_string()))
+        .collect();
+        assert_eq!(expected, data);
+        assert_eq!(None, get_batch_progress(&db, chart.name()).await.unwrap());
+
+        // without saved progress the whole range is calculated
+        chart.update(&db, &blockscout, true).await.unwrap();
+        let data = get_chart_data(&db, chart.name(), None, None).await.unwrap();
+        assert_eq!(7, data.len());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn force_full_update_ignores_saved_progress() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("force_full_update_ignores_saved_progress", None).await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        let chart = NewContracts::default();
+        chart.create(&db).await.unwrap();
+
+        let min_blockscout_block = get_min_block_blockscout(&blockscout).await.unwrap();
+        let progress = BatchProgress {
+            range_start: d("2022-11-09"),
+            range_end: d("2023-03-01"),
+            completed_to: d("2022-12-01"),
+            min_blockscout_block,
+        };
+        save_batch_progress(&db, chart.name(), &progress)
+            .await
+            .unwrap();
+
+        chart.update(&db, &blockscout, true).await.unwrap();
+        let data = get_chart_data(&db, chart.name(), None, None).await.unwrap();
+        assert_eq!(7, data.len());
+        assert_eq!(None, get_batch_progress(&db, chart.name()).await.unwrap());
+    }
 }
@@ -1,4 +1,4 @@
-use super::get_min_block_blockscout;
+use super::{get_last_row, get_min_block_blockscout};
 use crate::{
     charts::{
         find_chart,
@@ -7,9 +7,15 @@ use crate::{
     get_chart_data, Chart, UpdateError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use sea_orm::prelude::*;
-use std::{fmt::Display, iter::Sum, ops::AddAssign, str::FromStr, sync::Arc};
+use std::{fmt::Display, ops::AddAssign, str::FromStr, sync::Arc};
 
+/// Chart calculated from data of another chart.
+///
+/// The parent is not updated here, it must be updated before the chart
+/// (see [`Chart::dependencies`]). Only points of the parent starting from
+/// the last saved point of the chart are read.
 #[async_trait]
 pub trait ChartDependentUpdater<P>: Chart
 where
@@ -17,22 +23,20 @@ where
 {
     fn parent(&self) -> Arc<P>;
 
-    async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError>;
+    /// `parent_data` starts from the date of `last_row`,
+    /// or contains the whole parent chart if `last_row` is `None`
+    async fn get_values(
+        &self,
+        last_row: Option<DateValue>,
+        parent_data: Vec<DateValue>,
+    ) -> Result<Vec<DateValue>, UpdateError>;
 
     async fn get_parent_data(
         &self,
         db: &DatabaseConnection,
-        blockscout: &DatabaseConnection,
-        force_full: bool,
+        from: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
-        let parent = self.parent();
-        tracing::info!(
-            chart_name = self.name(),
-            parent_chart_name = parent.name(),
-            "updating parent"
-        );
-        parent.update_with_mutex(db, blockscout, force_full).await?;
-        let data = get_chart_data(db, parent.name(), None, None).await?;
+        let data = get_chart_data(db, self.parent().name(), from, None).await?;
         Ok(data)
     }
 
@@ -49,9 +53,12 @@ where
         let min_blockscout_block = get_min_block_blockscout(blockscout)
             .await
             .map_err(UpdateError::BlockscoutDB)?;
-        let parent_data = self.get_parent_data(db, blockscout, force_full).await?;
+        let last_row = get_last_row(self, chart_id, min_blockscout_block, db, force_full).await?;
+        let parent_data = self
+            .get_parent_data(db, last_row.as_ref().map(|row| row.date))
+            .await?;
         let values = self
-            .get_values(parent_data)
+            .get_values(last_row, parent_data)
             .await?
             .into_iter()
             .map(|v| v.active_model(chart_id, Some(min_blockscout_block)));
@@ -61,63 +68,56 @@ where
     }
 }
 
-pub fn parse_and_growth<T>(
-    mut data: Vec<DateValue>,
-    parent_name: &str,
-) -> Result<Vec<DateValue>, UpdateError>
+/// Parses saved value of the chart, that the parent data is added to
+fn parse_last_value<T>(last_row: Option<DateValue>, chart_name: &str) -> Result<T, UpdateError>
 where
-    T: AddAssign + FromStr + Default + Display,
+    T: FromStr + Default,
     T::Err: Display,
 {
-    let mut prev_sum = T::default();
-    for item in data.iter_mut() {
-        let value = item.value.parse::<T>().map_err(|e| {
+    match last_row {
+        Some(row) => row.value.parse::<T>().map_err(|e| {
             UpdateError::Internal(format!(
-                "failed to parse values in chart '{parent_name}': {e}",
+                "failed to parse values in chart '{chart_name}': {e}",
             ))
-        })?;
-        prev_sum += value;
-        item.value = prev_sum.to_string();
+        }),
+        None => Ok(T::default()),
     }
-    Ok(data)
 }
 
-pub fn parse_and_sum<T>(
+/// Points of the parent after `last_row`, the point of `last_row` date is already counted
+fn points_after(data: Vec<DateValue>, last_row: Option<&DateValue>) -> Vec<DateValue> {
+    match last_row {
+        Some(row) => data.into_iter().filter(|p| p.date > row.date).collect(),
+        None => data,
+    }
+}
+
+/// Running total of the parent values.
+///
+/// Counters that sum the parent use it as well: the total is saved for every date,
+/// so the next update continues from a total that doesn't depend on partial days.
+pub fn parse_and_growth<T>(
     data: Vec<DateValue>,
+    last_row: Option<DateValue>,
     chart_name: &str,
     parent_name: &str,
-) -> Result<Option<DateValue>, UpdateError>
+) -> Result<Vec<DateValue>, UpdateError>
 where
-    T: Sum + FromStr + Default + Display,
+    T: AddAssign + FromStr + Default + Display,
     T::Err: Display,
 {
-    let max_date = match data.iter().max() {
-        Some(max_date) => max_date.date,
-        None => {
-            tracing::warn!(
-                chart_name = chart_name,
-                parent_chart_name = parent_name,
-                "parent doesn't have any data after update"
-            );
-            return Ok(None);
-        }
-    };
-    let total: T = data
-        .into_iter()
-        .map(|p| p.value.parse::<T>())
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| {
+    let mut data = points_after(data, last_row.as_ref());
+    let mut prev_sum = parse_last_value::<T>(last_row, chart_name)?;
+    for item in data.iter_mut() {
+        let value = item.value.parse::<T>().map_err(|e| {
             UpdateError::Internal(format!(
                 "failed to parse values in chart '{parent_name}': {e}",
             ))
-        })?
-        .into_iter()
-        .sum();
-    let point = DateValue {
-        date: max_date,
-        value: total.to_string(),
-    };
-    Ok(Some(point))
+        })?;
+        prev_sum += value;
+        item.value = prev_sum.to_string();
+    }
+    Ok(data)
 }
 
 pub fn last_point(data: Vec<DateValue>) -> Option<DateValue> {
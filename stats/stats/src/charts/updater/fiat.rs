@@ -0,0 +1,53 @@
+use super::get_min_block_blockscout;
+use crate::{
+    charts::{
+        fiat::{to_fiat, MissingPrice},
+        find_chart,
+        insert::insert_data_many,
+        ArcChart,
+    },
+    get_chart_data,
+    lines::NativeCoinPrice,
+    Chart, UpdateError,
+};
+use async_trait::async_trait;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Line chart of the parent values multiplied by [`NativeCoinPrice`] of the same day.
+///
+/// Both charts are dependencies and must be updated before it.
+/// Prices of past days can appear in `market_history` later than the parent points,
+/// so the whole chart is recalculated on every update.
+#[async_trait]
+pub trait ChartFiatUpdater: Chart {
+    /// Chart with values in native coins
+    fn parent(&self) -> ArcChart;
+
+    fn price(&self) -> Arc<NativeCoinPrice>;
+
+    fn missing_price(&self) -> MissingPrice;
+
+    async fn update_with_values(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        _force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let data = get_chart_data(db, self.parent().name(), None, None).await?;
+        let prices = get_chart_data(db, self.price().name(), None, None).await?;
+        let values = to_fiat(data, prices, self.missing_price(), self.name())?
+            .into_iter()
+            .map(|v| v.active_model(chart_id, Some(min_blockscout_block)));
+        insert_data_many(db, values)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
+}
@@ -1,12 +1,14 @@
+use super::query_dates;
 use crate::{
     charts::{
-        find_chart,
+        chain, find_chart,
         insert::{insert_data_many, DateValue},
     },
     metrics, Chart, UpdateError,
 };
 use async_trait::async_trait;
-use sea_orm::prelude::*;
+use chrono::NaiveDate;
+use sea_orm::{prelude::*, Statement};
 
 #[async_trait]
 pub trait ChartFullUpdater: Chart {
@@ -15,6 +17,24 @@ pub trait ChartFullUpdater: Chart {
         blockscout: &DatabaseConnection,
     ) -> Result<Vec<DateValue>, UpdateError>;
 
+    /// Query of daily values from `from` inclusive to `to` exclusive.
+    /// Has to be implemented to recalculate separate dates, see [`Chart::recalculate`].
+    fn get_dates_query(&self, _from: NaiveDate, _to: NaiveDate) -> Result<Statement, UpdateError> {
+        Err(UpdateError::Internal(format!(
+            "chart {} doesn't support recalculation of dates",
+            self.name()
+        )))
+    }
+
+    /// Every date is recalculated by its own query instead of the whole chart
+    async fn recalculate_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        query_dates(blockscout, dates, |from, to| self.get_dates_query(from, to)).await
+    }
+
     async fn update_with_values(
         &self,
         db: &DatabaseConnection,
@@ -27,7 +47,7 @@ pub trait ChartFullUpdater: Chart {
             .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
         let values = {
             let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
-                .with_label_values(&[self.name()])
+                .with_label_values(&[&chain::current(), self.name()])
                 .start_timer();
             self.get_values(blockscout)
                 .await?
@@ -0,0 +1,173 @@
+use super::{get_max_date_blockscout, get_min_block_blockscout, get_min_date_blockscout};
+use crate::{
+    charts::{chain, find_chart, reorg::get_invalidated_from, resolution::start_of_day, timezone},
+    metrics, Chart, UpdateError,
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use entity::leaderboard_data;
+use sea_orm::{
+    prelude::*, DbBackend, FromQueryResult, QueryOrder, QuerySelect, Set, Statement,
+    TransactionTrait,
+};
+
+/// Number of entries saved for every date and period
+pub const LEADERBOARD_SIZE: u32 = 100;
+/// Numbers of days that entries are ranked over, periods end with the date of entries
+pub const LEADERBOARD_PERIODS: [u32; 3] = [1, 7, 30];
+/// Dates calculated by the first update, counting back from the date of the last block
+const INITIAL_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct LeaderboardEntry {
+    pub address: Vec<u8>,
+    pub value: String,
+}
+
+#[derive(FromQueryResult)]
+struct LastDate {
+    date: NaiveDate,
+    min_blockscout_block: Option<i64>,
+}
+
+/// Chart of addresses ranked by value over the last days.
+///
+/// Entries are saved for every date up to the date of the last block and every period
+/// of [`LEADERBOARD_PERIODS`]. The last saved date is recalculated on every update,
+/// as its day could be partial. The first update calculates [`INITIAL_DAYS`] dates only.
+#[async_trait]
+pub trait ChartLeaderboardUpdater: Chart {
+    /// Query of entries over blocks with timestamps in `[$1, $2)`, ordered by rank and
+    /// limited by `$3`. Has to return `address` and `value::TEXT` columns
+    fn query(&self) -> &'static str;
+
+    async fn get_entries(
+        &self,
+        blockscout: &DatabaseConnection,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Result<Vec<LeaderboardEntry>, UpdateError> {
+        LeaderboardEntry::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            self.query(),
+            vec![from.into(), to.into(), i64::from(LEADERBOARD_SIZE).into()],
+        ))
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::BlockscoutDB)
+    }
+
+    async fn update_with_values(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let timezone = timezone::current();
+        let first_date = get_min_date_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let first_date = timezone.local_time(first_date).date();
+        let last_date = get_max_date_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let last_date = timezone.local_time(last_date).date();
+
+        let saved_date = if force_full {
+            None
+        } else {
+            get_last_date(db, chart_id, min_blockscout_block).await?
+        };
+        let from = match saved_date {
+            Some(date) => {
+                let invalidated_from = get_invalidated_from(db, self.name())
+                    .await
+                    .map_err(UpdateError::StatsDB)?;
+                invalidated_from.map_or(date, |invalidated| invalidated.min(date))
+            }
+            None => last_date - Duration::days(INITIAL_DAYS - 1),
+        };
+
+        let mut date = from.max(first_date);
+        while date <= last_date {
+            let to = timezone.utc_time(start_of_day(date + Duration::days(1)));
+            let mut models = Vec::new();
+            for period in LEADERBOARD_PERIODS {
+                let from = date - Duration::days(i64::from(period) - 1);
+                let from = timezone.utc_time(start_of_day(from));
+                let entries = {
+                    let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+                        .with_label_values(&[&chain::current(), self.name()])
+                        .start_timer();
+                    self.get_entries(blockscout, from, to).await?
+                };
+                models.extend(entries.into_iter().enumerate().map(|(i, entry)| {
+                    leaderboard_data::ActiveModel {
+                        chart_id: Set(chart_id),
+                        date: Set(date),
+                        period_days: Set(period as i32),
+                        rank: Set(i as i32 + 1),
+                        address: Set(entry.address),
+                        value: Set(entry.value),
+                        min_blockscout_block: Set(Some(min_blockscout_block)),
+                        ..Default::default()
+                    }
+                }));
+            }
+            save_date(db, chart_id, date, models).await?;
+            date += Duration::days(1);
+        }
+        Ok(())
+    }
+}
+
+/// Last saved date, `None` if it was calculated for another first block of blockscout
+async fn get_last_date(
+    db: &DatabaseConnection,
+    chart_id: i32,
+    min_blockscout_block: i64,
+) -> Result<Option<NaiveDate>, UpdateError> {
+    let last = leaderboard_data::Entity::find()
+        .select_only()
+        .column(leaderboard_data::Column::Date)
+        .column(leaderboard_data::Column::MinBlockscoutBlock)
+        .filter(leaderboard_data::Column::ChartId.eq(chart_id))
+        .order_by_desc(leaderboard_data::Column::Date)
+        .into_model::<LastDate>()
+        .one(db)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    Ok(last
+        .filter(|last| last.min_blockscout_block == Some(min_blockscout_block))
+        .map(|last| last.date))
+}
+
+/// Replaces entries of the date, so that ranks that are gone are not left
+async fn save_date(
+    db: &DatabaseConnection,
+    chart_id: i32,
+    date: NaiveDate,
+    models: Vec<leaderboard_data::ActiveModel>,
+) -> Result<(), UpdateError> {
+    let txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+    leaderboard_data::Entity::delete_many()
+        .filter(leaderboard_data::Column::ChartId.eq(chart_id))
+        .filter(leaderboard_data::Column::Date.eq(date))
+        .exec(&txn)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    if !models.is_empty() {
+        leaderboard_data::Entity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+    }
+    txn.commit().await.map_err(UpdateError::StatsDB)
+}
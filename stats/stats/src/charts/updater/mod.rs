@@ -1,18 +1,65 @@
 use blockscout_db::entity::blocks;
-use chrono::{NaiveDate, NaiveDateTime};
-use entity::chart_data;
-use sea_orm::{prelude::*, sea_query, ConnectionTrait, FromQueryResult, QueryOrder, QuerySelect};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use entity::{chart_data, sea_orm_active_enums::ChartResolution};
+use sea_orm::{
+    prelude::*, sea_query, ConnectionTrait, FromQueryResult, QueryOrder, QuerySelect, Statement,
+};
 mod batch;
 mod dependent;
+mod distribution;
+mod fiat;
 mod full;
+mod leaderboard;
 mod partial;
+mod progress;
+mod rollup;
 
 pub use batch::ChartBatchUpdater;
-pub use dependent::{last_point, parse_and_growth, parse_and_sum, ChartDependentUpdater};
+pub use dependent::{last_point, parse_and_growth, ChartDependentUpdater};
+pub use distribution::ChartDistributionUpdater;
+pub use fiat::ChartFiatUpdater;
 pub use full::ChartFullUpdater;
+pub use leaderboard::{
+    ChartLeaderboardUpdater, LeaderboardEntry, LEADERBOARD_PERIODS, LEADERBOARD_SIZE,
+};
 pub use partial::ChartPartialUpdater;
+pub use progress::{clear_batch_progress, get_batch_progress, BatchProgress};
+pub use rollup::ChartRollupUpdater;
 
-use crate::{Chart, DateValue, UpdateError};
+use super::{reorg::get_invalidated_from, resolution::start_of_day, timezone};
+use crate::{Chart, DateValue, Resolution, TimespanValue, UpdateError};
+
+/// Values of the dates, every date is queried separately,
+/// so that only rows of the dates are read
+pub async fn query_dates<F>(
+    blockscout: &DatabaseConnection,
+    dates: &[NaiveDate],
+    get_query: F,
+) -> Result<Vec<DateValue>, UpdateError>
+where
+    F: Fn(NaiveDate, NaiveDate) -> Result<Statement, UpdateError> + Send + Sync,
+{
+    let mut values = Vec::with_capacity(dates.len());
+    for date in dates {
+        let query = get_query(*date, *date + Duration::days(1))?;
+        let found = DateValue::find_by_statement(query)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        values.extend(found);
+    }
+    Ok(values)
+}
+
+/// Utc times of the starts of local days, raw timestamps are filtered by them,
+/// so that the index on them could be used
+pub fn utc_day_bounds(from: NaiveDate, to: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    let timezone = timezone::current();
+    (
+        timezone.utc_time(start_of_day(from)),
+        timezone.utc_time(start_of_day(to)),
+    )
+}
 
 #[derive(FromQueryResult)]
 struct MinBlock {
@@ -41,6 +88,7 @@ struct MinDate {
     timestamp: NaiveDateTime,
 }
 
+/// Timestamp of the first block in utc
 pub async fn get_min_date_blockscout<C>(blockscout: &C) -> Result<NaiveDateTime, DbErr>
 where
     C: ConnectionTrait,
@@ -63,9 +111,36 @@ where
         .ok_or_else(|| DbErr::RecordNotFound("no blocks found in blockscout database".into()))
 }
 
+#[derive(FromQueryResult)]
+struct MaxDate {
+    timestamp: Option<NaiveDateTime>,
+}
+
+/// Timestamp of the last block in utc
+pub async fn get_max_date_blockscout<C>(blockscout: &C) -> Result<NaiveDateTime, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let max_date = blocks::Entity::find()
+        .select_only()
+        .column_as(
+            sea_query::Expr::col(blocks::Column::Timestamp).max(),
+            "timestamp",
+        )
+        .filter(blocks::Column::Consensus.eq(true))
+        .into_model::<MaxDate>()
+        .one(blockscout)
+        .await?;
+
+    max_date
+        .and_then(|r| r.timestamp)
+        .ok_or_else(|| DbErr::RecordNotFound("no blocks found in blockscout database".into()))
+}
+
 #[derive(Debug, FromQueryResult)]
 struct SyncInfo {
     pub date: NaiveDate,
+    pub time: NaiveTime,
     pub value: String,
     pub min_blockscout_block: Option<i64>,
 }
@@ -77,6 +152,32 @@ pub async fn get_last_row<C>(
     db: &DatabaseConnection,
     force_full: bool,
 ) -> Result<Option<DateValue>, UpdateError>
+where
+    C: Chart + ?Sized,
+{
+    let last_row = get_last_row_with_resolution(
+        chart,
+        chart_id,
+        Resolution::Day,
+        min_blockscout_block,
+        db,
+        force_full,
+    )
+    .await?;
+    Ok(last_row.map(|row| DateValue {
+        date: row.timespan.date(),
+        value: row.value,
+    }))
+}
+
+pub async fn get_last_row_with_resolution<C>(
+    chart: &C,
+    chart_id: i32,
+    resolution: Resolution,
+    min_blockscout_block: i64,
+    db: &DatabaseConnection,
+    force_full: bool,
+) -> Result<Option<TimespanValue>, UpdateError>
 where
     C: Chart + ?Sized,
 {
@@ -84,17 +185,40 @@ where
         tracing::info!(
             min_blockscout_block = min_blockscout_block,
             chart = chart.name(),
+            resolution = %resolution,
             "running full update due to force override"
         );
         None
     } else {
-        let last_row: Option<SyncInfo> = chart_data::Entity::find()
+        let invalidated_from = get_invalidated_from(db, chart.name())
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let mut query = chart_data::Entity::find()
             .column(chart_data::Column::Date)
+            .column(chart_data::Column::Time)
             .column(chart_data::Column::Value)
             .column(chart_data::Column::MinBlockscoutBlock)
             .filter(chart_data::Column::ChartId.eq(chart_id))
+            .filter(chart_data::Column::Resolution.eq(ChartResolution::from(resolution)))
+            .filter(chart_data::Column::Series.eq(""))
             .order_by_desc(chart_data::Column::Date)
-            .offset(1)
+            .order_by_desc(chart_data::Column::Time);
+        query = match invalidated_from {
+            // points before the reorged period were not changed,
+            // so the update continues right after the last of them
+            Some(date) => {
+                let period_start = resolution.period_start(start_of_day(date)).date();
+                tracing::info!(
+                    chart = chart.name(),
+                    resolution = %resolution,
+                    invalidated_from = %date,
+                    "recalculating points invalidated by reorg"
+                );
+                query.filter(chart_data::Column::Date.lt(period_start))
+            }
+            None => query.offset(1),
+        };
+        let last_row: Option<SyncInfo> = query
             .into_model()
             .one(db)
             .await
@@ -108,10 +232,11 @@ where
                             min_blockscout_block = min_blockscout_block,
                             min_chart_block = block,
                             chart = chart.name(),
+                            resolution = %resolution,
                             "running partial update"
                         );
-                        Some(DateValue {
-                            date: row.date,
+                        Some(TimespanValue {
+                            timespan: row.date.and_time(row.time),
                             value: row.value,
                         })
                     } else {
@@ -119,6 +244,7 @@ where
                             min_blockscout_block = min_blockscout_block,
                             min_chart_block = block,
                             chart = chart.name(),
+                            resolution = %resolution,
                             "running full update due to min blocks mismatch"
                         );
                         None
@@ -127,6 +253,7 @@ where
                     tracing::info!(
                         min_blockscout_block = min_blockscout_block,
                         chart = chart.name(),
+                        resolution = %resolution,
                         "running full update due to lack of saved min block"
                     );
                     None
@@ -136,6 +263,7 @@ where
                 tracing::info!(
                     min_blockscout_block = min_blockscout_block,
                     chart = chart.name(),
+                    resolution = %resolution,
                     "running full update due to lack of history data"
                 );
                 None
@@ -1,13 +1,14 @@
-use super::{get_last_row, get_min_block_blockscout};
+use super::{get_last_row, get_last_row_with_resolution, get_min_block_blockscout, query_dates};
 use crate::{
     charts::{
-        find_chart,
-        insert::{insert_data_many, DateValue},
+        chain, find_chart,
+        insert::{insert_data_many, DateValue, TimespanValue},
     },
-    metrics, Chart, UpdateError,
+    metrics, Chart, Resolution, UpdateError,
 };
 use async_trait::async_trait;
-use sea_orm::prelude::*;
+use chrono::NaiveDate;
+use sea_orm::{prelude::*, Statement};
 
 #[async_trait]
 pub trait ChartPartialUpdater: Chart {
@@ -17,6 +18,40 @@ pub trait ChartPartialUpdater: Chart {
         last_row: Option<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError>;
 
+    /// Values for resolutions other than [`Resolution::Day`].
+    /// Has to be implemented for every resolution returned by [`Chart::resolutions`].
+    async fn get_values_with_resolution(
+        &self,
+        _blockscout: &DatabaseConnection,
+        _last_row: Option<TimespanValue>,
+        resolution: Resolution,
+    ) -> Result<Vec<TimespanValue>, UpdateError> {
+        Err(UpdateError::Internal(format!(
+            "chart {} doesn't support {} resolution",
+            self.name(),
+            resolution
+        )))
+    }
+
+    /// Query of daily values from `from` inclusive to `to` exclusive.
+    /// Has to be implemented to recalculate separate dates, see [`Chart::recalculate`].
+    fn get_dates_query(&self, _from: NaiveDate, _to: NaiveDate) -> Result<Statement, UpdateError> {
+        Err(UpdateError::Internal(format!(
+            "chart {} doesn't support recalculation of dates",
+            self.name()
+        )))
+    }
+
+    /// Partial values may depend on the last saved point,
+    /// so every date is recalculated by its own query instead
+    async fn recalculate_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        query_dates(blockscout, dates, |from, to| self.get_dates_query(from, to)).await
+    }
+
     async fn update_with_values(
         &self,
         db: &DatabaseConnection,
@@ -33,13 +68,62 @@ pub trait ChartPartialUpdater: Chart {
         let last_row = get_last_row(self, chart_id, min_blockscout_block, db, force_full).await?;
         let values = {
             let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
-                .with_label_values(&[self.name()])
+                .with_label_values(&[&chain::current(), self.name()])
                 .start_timer();
             self.get_values(blockscout, last_row)
                 .await?
                 .into_iter()
                 .map(|value| value.active_model(chart_id, Some(min_blockscout_block)))
         };
+        insert_data_many(db, values)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+
+        for resolution in self
+            .resolutions()
+            .iter()
+            .filter(|resolution| **resolution != Resolution::Day)
+        {
+            self.update_resolution(
+                db,
+                blockscout,
+                chart_id,
+                *resolution,
+                min_blockscout_block,
+                force_full,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_resolution(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        chart_id: i32,
+        resolution: Resolution,
+        min_blockscout_block: i64,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let last_row = get_last_row_with_resolution(
+            self,
+            chart_id,
+            resolution,
+            min_blockscout_block,
+            db,
+            force_full,
+        )
+        .await?;
+        let values = {
+            let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+                .with_label_values(&[&chain::current(), self.name()])
+                .start_timer();
+            self.get_values_with_resolution(blockscout, last_row, resolution)
+                .await?
+                .into_iter()
+                .map(|value| value.active_model(chart_id, resolution, Some(min_blockscout_block)))
+        };
         insert_data_many(db, values)
             .await
             .map_err(UpdateError::StatsDB)?;
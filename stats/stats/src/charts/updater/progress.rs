@@ -0,0 +1,152 @@
+use chrono::NaiveDate;
+use entity::kv_storage;
+use sea_orm::{prelude::*, sea_query, ConnectionTrait, Set};
+use std::str::FromStr;
+
+const KEY_PREFIX: &str = "batch_progress:";
+
+/// Progress of batch update that is not finished yet.
+///
+/// Is saved after every step, so the update can be resumed
+/// from `completed_to` instead of the start of the range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchProgress {
+    pub range_start: NaiveDate,
+    pub range_end: NaiveDate,
+    pub completed_to: NaiveDate,
+    pub min_blockscout_block: i64,
+}
+
+impl BatchProgress {
+    /// Part of the range that is done, from `0.0` to `1.0`
+    pub fn ratio(&self) -> f64 {
+        let total = (self.range_end - self.range_start).num_days();
+        if total <= 0 {
+            return 1.0;
+        }
+        let done = (self.completed_to - self.range_start).num_days();
+        (done as f64 / total as f64).clamp(0.0, 1.0)
+    }
+
+    fn encode(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.range_start, self.range_end, self.completed_to, self.min_blockscout_block
+        )
+    }
+
+    fn decode(value: &str) -> Option<Self> {
+        let mut parts = value.split('|');
+        let progress = Self {
+            range_start: NaiveDate::from_str(parts.next()?).ok()?,
+            range_end: NaiveDate::from_str(parts.next()?).ok()?,
+            completed_to: NaiveDate::from_str(parts.next()?).ok()?,
+            min_blockscout_block: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(progress)
+    }
+}
+
+fn key(chart_name: &str) -> String {
+    format!("{KEY_PREFIX}{chart_name}")
+}
+
+pub async fn get_batch_progress<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+) -> Result<Option<BatchProgress>, DbErr> {
+    let row = kv_storage::Entity::find_by_id(key(chart_name))
+        .one(db)
+        .await?;
+    let progress = row.and_then(|row| {
+        let progress = BatchProgress::decode(&row.value);
+        if progress.is_none() {
+            tracing::warn!(
+                chart = chart_name,
+                value = %row.value,
+                "ignoring invalid batch progress"
+            );
+        }
+        progress
+    });
+    Ok(progress)
+}
+
+pub async fn save_batch_progress<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+    progress: &BatchProgress,
+) -> Result<(), DbErr> {
+    kv_storage::Entity::insert(kv_storage::ActiveModel {
+        key: Set(key(chart_name)),
+        value: Set(progress.encode()),
+    })
+    .on_conflict(
+        sea_query::OnConflict::column(kv_storage::Column::Key)
+            .update_column(kv_storage::Column::Value)
+            .to_owned(),
+    )
+    .exec(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn clear_batch_progress<C: ConnectionTrait>(
+    db: &C,
+    chart_name: &str,
+) -> Result<(), DbErr> {
+    kv_storage::Entity::delete_by_id(key(chart_name))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn d(s: &str) -> NaiveDate {
+        NaiveDate::from_str(s).expect("cannot parse date")
+    }
+
+    #[test]
+    fn encode_decode_works() {
+        let progress = BatchProgress {
+            range_start: d("2022-01-01"),
+            range_end: d("2022-04-01"),
+            completed_to: d("2022-01-31"),
+            min_blockscout_block: 0,
+        };
+        let encoded = progress.encode();
+        assert_eq!("2022-01-01|2022-04-01|2022-01-31|0", encoded);
+        assert_eq!(Some(progress), BatchProgress::decode(&encoded));
+
+        for invalid in [
+            "",
+            "2022-01-01|2022-04-01|2022-01-31",
+            "a|b|c|d",
+            "2022-01-01|2022-04-01|2022-01-31|0|1",
+        ] {
+            assert_eq!(None, BatchProgress::decode(invalid));
+        }
+    }
+
+    #[test]
+    fn ratio_works() {
+        for (start, end, completed, expected) in [
+            ("2022-01-01", "2022-01-11", "2022-01-01", 0.0),
+            ("2022-01-01", "2022-01-11", "2022-01-06", 0.5),
+            ("2022-01-01", "2022-01-11", "2022-01-21", 1.0),
+            ("2022-01-01", "2022-01-01", "2022-01-01", 1.0),
+        ] {
+            let progress = BatchProgress {
+                range_start: d(start),
+                range_end: d(end),
+                completed_to: d(completed),
+                min_blockscout_block: 0,
+            };
+            assert_eq!(expected, progress.ratio());
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::{get_last_row_with_resolution, get_min_block_blockscout};
+use crate::{
+    charts::{
+        chain, find_chart,
+        insert::{insert_data_many, TimespanValue},
+        txns_rollup::{get_rollup_values, recalculate_rollup_values, TxnsRollup},
+    },
+    metrics, Chart, DateValue, Resolution, UpdateError,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+
+/// Line chart calculated from [`TxnsRollup`] instead of blockscout transactions.
+///
+/// The rollup is a dependency of the chart and must be updated before it.
+#[async_trait]
+pub trait ChartRollupUpdater: Chart {
+    fn rollup(&self) -> Arc<TxnsRollup>;
+
+    /// Aggregate of `txns_rollup` columns over the period, must have `FLOAT` type
+    fn value_sql(&self) -> &'static str;
+
+    /// Values are calculated from blockscout instead of the rollup
+    async fn recalculate_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        dates: &[NaiveDate],
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        recalculate_rollup_values(blockscout, self.value_sql(), dates).await
+    }
+
+    async fn update_with_values(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        for resolution in self.resolutions() {
+            self.update_resolution(db, chart_id, *resolution, min_blockscout_block, force_full)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_resolution(
+        &self,
+        db: &DatabaseConnection,
+        chart_id: i32,
+        resolution: Resolution,
+        min_blockscout_block: i64,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        let last_row = get_last_row_with_resolution(
+            self,
+            chart_id,
+            resolution,
+            min_blockscout_block,
+            db,
+            force_full,
+        )
+        .await?;
+        let values = {
+            let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+                .with_label_values(&[&chain::current(), self.name()])
+                .start_timer();
+            get_rollup_values(
+                db,
+                self.value_sql(),
+                resolution,
+                last_row.map(|row| row.timespan),
+            )
+            .await?
+            .into_iter()
+            .map(|value| {
+                TimespanValue::from(value).active_model(
+                    chart_id,
+                    resolution,
+                    Some(min_blockscout_block),
+                )
+            })
+        };
+        insert_data_many(db, values)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        Ok(())
+    }
+}
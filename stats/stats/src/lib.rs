@@ -7,5 +7,27 @@ pub mod tests;
 pub use entity;
 pub use migration;
 
-pub use charts::{cache, counters, insert::DateValue, lines, Chart, UpdateError};
-pub use read::{get_chart_data, get_counters, ReadError};
+pub use charts::{
+    alert_state::{get_alert_state, save_alert_state, AlertState},
+    audit, cache, chain, counter_history, counters,
+    expression::{ExpressionChart, ExpressionError},
+    fiat::{MissingPrice, ParseMissingPriceError},
+    insert::{DateValue, TimespanValue},
+    is_update_mutex_locked, leaderboards, lines, reorg,
+    resolution::Resolution,
+    set_update_failed, set_update_succeeded,
+    sql_chart::{SqlChart, SqlTemplateError},
+    timezone,
+    timezone::{ParseTimezoneError, Timezone},
+    tokens::{parse_token_address, ParseTokenError, TokenChartKind, TokenLine},
+    txns_rollup,
+    updater::{
+        get_batch_progress, BatchProgress, LeaderboardEntry, LEADERBOARD_PERIODS, LEADERBOARD_SIZE,
+    },
+    ArcChart, Chart, UpdateError,
+};
+pub use read::{
+    get_chart_data, get_chart_data_with_resolution, get_chart_series_data, get_chart_start_date,
+    get_counters, get_last_chart_data, get_leaderboard, get_update_statuses, ChartUpdateStatus,
+    DateSeriesValue, Leaderboard, ReadError,
+};
@@ -1,6 +1,15 @@
-use crate::charts::insert::DateValue;
-use chrono::NaiveDate;
-use entity::{chart_data, charts};
+use crate::{
+    charts::{
+        insert::{DateValue, TimespanValue},
+        updater::LeaderboardEntry,
+    },
+    Resolution,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use entity::{
+    chart_data, charts, leaderboard_data,
+    sea_orm_active_enums::{ChartResolution, ChartType},
+};
 use sea_orm::{
     ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, FromQueryResult, QueryFilter,
     QueryOrder, QuerySelect, Statement,
@@ -23,6 +32,31 @@ struct CounterData {
     value: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct ChartUpdateStatus {
+    pub name: String,
+    pub last_updated_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<NaiveDateTime>,
+}
+
+pub async fn get_update_statuses(
+    db: &DatabaseConnection,
+) -> Result<Vec<ChartUpdateStatus>, ReadError> {
+    let statuses = charts::Entity::find()
+        .select_only()
+        .column(charts::Column::Name)
+        .column(charts::Column::LastUpdatedAt)
+        .column(charts::Column::LastError)
+        .column(charts::Column::LastErrorAt)
+        .filter(charts::Column::ChartType.ne(ChartType::Internal))
+        .order_by_asc(charts::Column::Name)
+        .into_model::<ChartUpdateStatus>()
+        .all(db)
+        .await?;
+    Ok(statuses)
+}
+
 pub async fn get_counters(
     db: &DatabaseConnection,
 ) -> Result<HashMap<String, DateValue>, ReadError> {
@@ -33,7 +67,7 @@ pub async fn get_counters(
             FROM "chart_data" "data"
             INNER JOIN "charts"
                 ON data.chart_id = charts.id
-            WHERE charts.chart_type = 'COUNTER'
+            WHERE charts.chart_type = 'COUNTER' AND data.resolution = 'DAY' AND data.series = ''
             ORDER BY charts.id, data.id DESC;
         "#
         .into(),
@@ -70,21 +104,235 @@ pub async fn get_chart_data(
         .await?
         .ok_or_else(|| ReadError::NotFound(name.into()))?;
 
-    let chart = get_chart(db, chart.id, from, to).await?;
+    let chart = get_chart(db, chart.id, Resolution::Day, from, to)
+        .await?
+        .into_iter()
+        .map(|point| DateValue {
+            date: point.date,
+            value: point.value,
+        })
+        .collect();
+    Ok(chart)
+}
+
+/// Returns the last `limit` daily points of the chart in ascending order of dates
+pub async fn get_last_chart_data(
+    db: &DatabaseConnection,
+    name: &str,
+    limit: u64,
+) -> Result<Vec<DateValue>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+
+    let mut data: Vec<DateValue> = chart_data::Entity::find()
+        .select_only()
+        .column(chart_data::Column::Date)
+        .column(chart_data::Column::Value)
+        .filter(chart_data::Column::ChartId.eq(chart.id))
+        .filter(chart_data::Column::Resolution.eq(ChartResolution::Day))
+        .filter(chart_data::Column::Series.eq(""))
+        .order_by_desc(chart_data::Column::Date)
+        .limit(limit)
+        .into_model()
+        .all(db)
+        .await?;
+    data.reverse();
+    Ok(data)
+}
+
+/// Returns points of the chart materialized with `resolution`.
+///
+/// `from` and `to` are compared with the first day of the period.
+pub async fn get_chart_data_with_resolution(
+    db: &DatabaseConnection,
+    name: &str,
+    resolution: Resolution,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<TimespanValue>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+
+    let chart = get_chart(db, chart.id, resolution, from, to)
+        .await?
+        .into_iter()
+        .map(|point| TimespanValue {
+            timespan: point.date.and_time(point.time),
+            value: point.value,
+        })
+        .collect();
     Ok(chart)
 }
 
+#[derive(Debug, FromQueryResult)]
+struct ChartDate {
+    date: NaiveDate,
+}
+
+/// Returns the first day of the first point of the chart materialized with `resolution`,
+/// `None` if the chart has no points
+pub async fn get_chart_start_date(
+    db: &DatabaseConnection,
+    name: &str,
+    resolution: Resolution,
+) -> Result<Option<NaiveDate>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+
+    let start = chart_data::Entity::find()
+        .select_only()
+        .column(chart_data::Column::Date)
+        .filter(chart_data::Column::ChartId.eq(chart.id))
+        .filter(chart_data::Column::Resolution.eq(ChartResolution::from(resolution)))
+        .filter(chart_data::Column::Series.eq(""))
+        .order_by_asc(chart_data::Column::Date)
+        .into_model::<ChartDate>()
+        .one(db)
+        .await?;
+    Ok(start.map(|start| start.date))
+}
+
+/// Value of the named series of the chart at the date
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct DateSeriesValue {
+    pub date: NaiveDate,
+    pub series: String,
+    pub value: String,
+}
+
+/// Returns additional series of the chart, ordered by date.
+/// Main values are returned by [`get_chart_data`]
+pub async fn get_chart_series_data(
+    db: &DatabaseConnection,
+    name: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<DateSeriesValue>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+
+    let data_request = chart_data::Entity::find()
+        .select_only()
+        .column(chart_data::Column::Date)
+        .column(chart_data::Column::Series)
+        .column(chart_data::Column::Value)
+        .filter(chart_data::Column::ChartId.eq(chart.id))
+        .filter(chart_data::Column::Resolution.eq(ChartResolution::Day))
+        .filter(chart_data::Column::Series.ne(""))
+        .order_by_asc(chart_data::Column::Date)
+        .order_by_asc(chart_data::Column::Series);
+    let data_request = if let Some(from) = from {
+        data_request.filter(chart_data::Column::Date.gte(from))
+    } else {
+        data_request
+    };
+    let data_request = if let Some(to) = to {
+        data_request.filter(chart_data::Column::Date.lte(to))
+    } else {
+        data_request
+    };
+    let data = data_request.into_model().all(db).await?;
+    Ok(data)
+}
+
+/// Entries of leaderboard chart ordered by rank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub date: NaiveDate,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct LeaderboardDate {
+    date: NaiveDate,
+}
+
+/// Returns top `limit` entries ranked over `period_days` ending with the date.
+/// Default date is the last calculated one, `None` if the leaderboard has no entries
+pub async fn get_leaderboard(
+    db: &DatabaseConnection,
+    name: &str,
+    period_days: u32,
+    date: Option<NaiveDate>,
+    limit: u32,
+) -> Result<Option<Leaderboard>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+
+    let date_request = leaderboard_data::Entity::find()
+        .select_only()
+        .column(leaderboard_data::Column::Date)
+        .filter(leaderboard_data::Column::ChartId.eq(chart.id))
+        .filter(leaderboard_data::Column::PeriodDays.eq(period_days as i32))
+        .order_by_desc(leaderboard_data::Column::Date);
+    let date_request = if let Some(date) = date {
+        date_request.filter(leaderboard_data::Column::Date.eq(date))
+    } else {
+        date_request
+    };
+    let date = match date_request.into_model::<LeaderboardDate>().one(db).await? {
+        Some(row) => row.date,
+        None => return Ok(None),
+    };
+
+    let entries = leaderboard_data::Entity::find()
+        .select_only()
+        .column(leaderboard_data::Column::Address)
+        .column(leaderboard_data::Column::Value)
+        .filter(leaderboard_data::Column::ChartId.eq(chart.id))
+        .filter(leaderboard_data::Column::PeriodDays.eq(period_days as i32))
+        .filter(leaderboard_data::Column::Date.eq(date))
+        .order_by_asc(leaderboard_data::Column::Rank)
+        .limit(u64::from(limit))
+        .into_model::<LeaderboardEntry>()
+        .all(db)
+        .await?;
+    Ok(Some(Leaderboard { date, entries }))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ChartPoint {
+    date: NaiveDate,
+    time: NaiveTime,
+    value: String,
+}
+
 async fn get_chart(
     db: &DatabaseConnection,
     chart_id: i32,
+    resolution: Resolution,
     from: Option<NaiveDate>,
     to: Option<NaiveDate>,
-) -> Result<Vec<DateValue>, DbErr> {
+) -> Result<Vec<ChartPoint>, DbErr> {
     let data_request = chart_data::Entity::find()
         .column(chart_data::Column::Date)
+        .column(chart_data::Column::Time)
         .column(chart_data::Column::Value)
         .filter(chart_data::Column::ChartId.eq(chart_id))
-        .order_by_asc(chart_data::Column::Date);
+        .filter(chart_data::Column::Resolution.eq(ChartResolution::from(resolution)))
+        .filter(chart_data::Column::Series.eq(""))
+        .order_by_asc(chart_data::Column::Date)
+        .order_by_asc(chart_data::Column::Time);
 
     let data_request = if let Some(from) = from {
         data_request.filter(chart_data::Column::Date.gte(from))
@@ -103,7 +351,6 @@ async fn get_chart(
 mod tests {
     use super::*;
     use crate::{counters::TotalBlocks, tests::init_db::init_db, Chart};
-    use entity::{chart_data, charts, sea_orm_active_enums::ChartType};
     use pretty_assertions::assert_eq;
     use sea_orm::{EntityTrait, Set};
     use std::str::FromStr;
@@ -195,4 +442,60 @@ mod tests {
             chart
         );
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_last_chart_data_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db::<migration::Migrator>("get_last_chart_data_mock", None).await;
+        insert_mock_data(&db).await;
+        let chart = get_last_chart_data(&db, "newBlocksPerDay", 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![value("2022-11-11", "150"), value("2022-11-12", "200")],
+            chart
+        );
+        assert!(matches!(
+            get_last_chart_data(&db, "unknown", 2).await,
+            Err(ReadError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_update_statuses_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db::<migration::Migrator>("get_update_statuses_mock", None).await;
+        insert_mock_data(&db).await;
+        crate::set_update_failed(&db, "newBlocksPerDay", "some error")
+            .await
+            .unwrap();
+        crate::set_update_succeeded(&db, "totalBlocks")
+            .await
+            .unwrap();
+        // internal charts are not listed
+        crate::charts::create_chart(&db, "txnsRollup".into(), ChartType::Internal)
+            .await
+            .unwrap();
+
+        let statuses = get_update_statuses(&db).await.unwrap();
+        assert_eq!(2, statuses.len());
+        assert_eq!("newBlocksPerDay", statuses[0].name);
+        assert_eq!(None, statuses[0].last_updated_at);
+        assert_eq!(Some("some error".into()), statuses[0].last_error);
+        assert!(statuses[0].last_error_at.is_some());
+        assert_eq!("totalBlocks", statuses[1].name);
+        assert!(statuses[1].last_updated_at.is_some());
+        assert_eq!(None, statuses[1].last_error);
+
+        crate::set_update_succeeded(&db, "newBlocksPerDay")
+            .await
+            .unwrap();
+        let statuses = get_update_statuses(&db).await.unwrap();
+        assert_eq!(None, statuses[0].last_error);
+        assert_eq!(None, statuses[0].last_error_at);
+    }
 }
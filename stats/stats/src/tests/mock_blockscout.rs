@@ -1,6 +1,6 @@
 use blockscout_db::entity::{
-    address_coin_balances_daily, addresses, block_rewards, blocks, internal_transactions,
-    smart_contracts, tokens, transactions,
+    address_coin_balances_daily, address_current_token_balances, addresses, block_rewards, blocks,
+    internal_transactions, market_history, smart_contracts, token_transfers, tokens, transactions,
 };
 use chrono::{NaiveDate, NaiveDateTime};
 use sea_orm::{prelude::Decimal, ActiveValue::NotSet, DatabaseConnection, EntityTrait, Set};
@@ -125,6 +125,32 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
         .await
         .unwrap();
 
+    // two transfers of the first token in every contract call
+    let token_transfers = blocks[0..blocks.len() - 1]
+        .iter()
+        .filter(|b| b.number.as_ref() % 3 != 1)
+        .flat_map(|b| {
+            [
+                mock_token_transfer(b, &accounts, 2, 0),
+                mock_token_transfer(b, &accounts, 2, 1),
+            ]
+        });
+    token_transfers::Entity::insert_many(token_transfers)
+        .exec(blockscout)
+        .await
+        .unwrap();
+    let token_balances = accounts.iter().enumerate().skip(1).map(|(i, account)| {
+        mock_token_balance(
+            account.hash.as_ref().clone(),
+            mock_token_address(),
+            Decimal::from(i % 4),
+        )
+    });
+    address_current_token_balances::Entity::insert_many(token_balances)
+        .exec(blockscout)
+        .await
+        .unwrap();
+
     let contract_creation_txns = contracts
         .iter()
         .chain(verified_contracts.iter())
@@ -271,6 +297,24 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
         .exec(blockscout)
         .await
         .unwrap();
+
+    // no price for 2022-11-11 and after 2022-11-12
+    let prices = [
+        ("2022-11-09", Some(Decimal::new(15, 1))),
+        ("2022-11-10", Some(Decimal::new(2, 0))),
+        ("2022-11-11", None),
+        ("2022-11-12", Some(Decimal::new(3, 0))),
+    ]
+    .into_iter()
+    .map(|(date, price)| (NaiveDate::from_str(date).unwrap(), price))
+    .filter(|(date, _)| *date <= NaiveDate::from_str(max_date).unwrap())
+    .enumerate()
+    .map(|(id, (date, price))| mock_market_history(id as i64, date, price))
+    .collect::<Vec<_>>();
+    market_history::Entity::insert_many(prices)
+        .exec(blockscout)
+        .await
+        .unwrap();
 }
 
 fn mock_block(index: i64, ts: &str, consensus: bool) -> blocks::ActiveModel {
@@ -419,6 +463,56 @@ fn mock_address_coin_balance_daily(
     }
 }
 
+/// Hash of the mock address with the seed, accounts have seeds `1..9`
+pub fn mock_address_hash(seed: i64) -> Vec<u8> {
+    mock_address(seed, false, false).hash.as_ref().clone()
+}
+
+/// Address of the token that has transfers and holders in mock data
+pub fn mock_token_address() -> Vec<u8> {
+    mock_address_hash(1)
+}
+
+fn mock_token_transfer(
+    block: &blocks::ActiveModel,
+    address_list: &Vec<addresses::ActiveModel>,
+    tx_index: u8,
+    log_index: i32,
+) -> token_transfers::ActiveModel {
+    let block_number = block.number.as_ref().to_owned() as i32;
+    let from_address_index = (block_number as usize) % address_list.len();
+    let to_address_index = (block_number as usize + 1) % address_list.len();
+    token_transfers::ActiveModel {
+        transaction_hash: Set(vec![0, 0, 0, 0, block_number as u8, tx_index]),
+        log_index: Set(log_index),
+        from_address_hash: Set(address_list[from_address_index].hash.as_ref().to_vec()),
+        to_address_hash: Set(address_list[to_address_index].hash.as_ref().to_vec()),
+        amount: Set(Some(Decimal::new(1_000, 0))),
+        token_contract_address_hash: Set(mock_token_address()),
+        inserted_at: Set(Default::default()),
+        updated_at: Set(Default::default()),
+        block_number: Set(Some(block_number)),
+        block_hash: Set(block.hash.as_ref().to_vec()),
+        ..Default::default()
+    }
+}
+
+fn mock_token_balance(
+    address: Vec<u8>,
+    token: Vec<u8>,
+    value: Decimal,
+) -> address_current_token_balances::ActiveModel {
+    address_current_token_balances::ActiveModel {
+        address_hash: Set(address),
+        block_number: Set(Default::default()),
+        token_contract_address_hash: Set(token),
+        value: Set(Some(value)),
+        inserted_at: Set(Default::default()),
+        updated_at: Set(Default::default()),
+        ..Default::default()
+    }
+}
+
 fn mock_token(hash: Vec<u8>) -> tokens::ActiveModel {
     tokens::ActiveModel {
         r#type: Set(Default::default()),
@@ -486,3 +580,16 @@ fn mock_internal_transaction(
         ..Default::default()
     }
 }
+
+fn mock_market_history(
+    id: i64,
+    date: NaiveDate,
+    closing_price: Option<Decimal>,
+) -> market_history::ActiveModel {
+    market_history::ActiveModel {
+        id: Set(id),
+        date: Set(Some(date)),
+        closing_price: Set(closing_price),
+        opening_price: Set(closing_price),
+    }
+}
@@ -1,7 +1,12 @@
 use super::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
-use crate::{get_chart_data, get_counters, Chart};
+use crate::{
+    get_chart_data, get_chart_data_with_resolution, get_chart_series_data, get_counters,
+    get_leaderboard, ArcChart, Chart, Resolution, LEADERBOARD_SIZE,
+};
+use chrono::NaiveDate;
 use pretty_assertions::assert_eq;
 use sea_orm::DatabaseConnection;
+use std::str::FromStr;
 
 pub async fn simple_test_chart(test_name: &str, chart: impl Chart, expected: Vec<(&str, &str)>) {
     let _ = tracing_subscriber::fmt::try_init();
@@ -9,13 +14,38 @@ pub async fn simple_test_chart(test_name: &str, chart: impl Chart, expected: Vec
     chart.create(&db).await.unwrap();
     fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
 
+    update_dependencies(&chart, &db, &blockscout, true).await;
     chart.update(&db, &blockscout, true).await.unwrap();
     get_chart_and_assert_eq(&db, &chart, &expected).await;
 
+    update_dependencies(&chart, &db, &blockscout, false).await;
     chart.update(&db, &blockscout, false).await.unwrap();
     get_chart_and_assert_eq(&db, &chart, &expected).await;
 }
 
+/// Updates dependencies of the chart in the order the server does
+async fn update_dependencies(
+    chart: &impl Chart,
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    force_full: bool,
+) {
+    let mut order = Vec::new();
+    collect_dependencies(chart.dependencies(), &mut order);
+    for dependency in order {
+        dependency.update(db, blockscout, force_full).await.unwrap();
+    }
+}
+
+fn collect_dependencies(dependencies: Vec<ArcChart>, order: &mut Vec<ArcChart>) {
+    for dependency in dependencies {
+        collect_dependencies(dependency.dependencies(), order);
+        if !order.iter().any(|chart| chart.name() == dependency.name()) {
+            order.push(dependency);
+        }
+    }
+}
+
 async fn get_chart_and_assert_eq(
     db: &DatabaseConnection,
     chart: &impl Chart,
@@ -33,15 +63,113 @@ async fn get_chart_and_assert_eq(
     assert_eq!(expected, &data);
 }
 
+pub async fn simple_test_chart_with_resolution(
+    test_name: &str,
+    chart: impl Chart,
+    resolution: Resolution,
+    expected: Vec<(&str, &str)>,
+) {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name, None).await;
+    chart.create(&db).await.unwrap();
+    fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+    update_dependencies(&chart, &db, &blockscout, true).await;
+    chart.update(&db, &blockscout, true).await.unwrap();
+    get_chart_with_resolution_and_assert_eq(&db, &chart, resolution, &expected).await;
+
+    update_dependencies(&chart, &db, &blockscout, false).await;
+    chart.update(&db, &blockscout, false).await.unwrap();
+    get_chart_with_resolution_and_assert_eq(&db, &chart, resolution, &expected).await;
+}
+
+async fn get_chart_with_resolution_and_assert_eq(
+    db: &DatabaseConnection,
+    chart: &impl Chart,
+    resolution: Resolution,
+    expected: &Vec<(&str, &str)>,
+) {
+    let data = get_chart_data_with_resolution(db, chart.name(), resolution, None, None)
+        .await
+        .unwrap();
+    let data: Vec<_> = data
+        .into_iter()
+        .map(|p| (resolution.format_timespan(p.timespan), p.value))
+        .collect();
+    let data: Vec<(&str, &str)> = data
+        .iter()
+        .map(|(date, value)| (date.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(expected, &data);
+}
+
+/// Checks all series of the chart in order of [`Chart::series`],
+/// the main value is expected to be the second series (median)
+pub async fn simple_test_distribution_chart<const N: usize>(
+    test_name: &str,
+    chart: impl Chart,
+    expected: Vec<(&str, [&str; N])>,
+) {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name, None).await;
+    chart.create(&db).await.unwrap();
+    fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+    let expected_main: Vec<_> = expected
+        .iter()
+        .map(|(date, values)| (*date, values[1]))
+        .collect();
+    for force_full in [true, false] {
+        chart.update(&db, &blockscout, force_full).await.unwrap();
+        get_chart_and_assert_eq(&db, &chart, &expected_main).await;
+        get_series_and_assert_eq(&db, &chart, &expected).await;
+    }
+}
+
+async fn get_series_and_assert_eq<const N: usize>(
+    db: &DatabaseConnection,
+    chart: &impl Chart,
+    expected: &[(&str, [&str; N])],
+) {
+    let data = get_chart_series_data(db, chart.name(), None, None)
+        .await
+        .unwrap();
+    let mut dates: Vec<String> = data.iter().map(|p| p.date.to_string()).collect();
+    dates.dedup();
+    let data: Vec<(String, Vec<&str>)> = dates
+        .into_iter()
+        .map(|date| {
+            let values = chart
+                .series()
+                .iter()
+                .map(|series| {
+                    data.iter()
+                        .find(|p| p.date.to_string() == date && p.series == *series)
+                        .map(|p| p.value.as_str())
+                        .unwrap_or_default()
+                })
+                .collect();
+            (date, values)
+        })
+        .collect();
+    let expected: Vec<(String, Vec<&str>)> = expected
+        .iter()
+        .map(|(date, values)| (date.to_string(), values.to_vec()))
+        .collect();
+    assert_eq!(expected, data);
+}
+
 pub async fn simple_test_counter(test_name: &str, counter: impl Chart, expected: &str) {
     let _ = tracing_subscriber::fmt::try_init();
     let (db, blockscout) = init_db_all(test_name, None).await;
     counter.create(&db).await.unwrap();
     fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
 
+    update_dependencies(&counter, &db, &blockscout, true).await;
     counter.update(&db, &blockscout, true).await.unwrap();
     get_counter_and_assert_eq(&db, &counter, expected).await;
 
+    update_dependencies(&counter, &db, &blockscout, false).await;
     counter.update(&db, &blockscout, false).await.unwrap();
     get_counter_and_assert_eq(&db, &counter, expected).await;
 }
@@ -52,3 +180,47 @@ async fn get_counter_and_assert_eq(db: &DatabaseConnection, counter: &impl Chart
     let value = &data.value;
     assert_eq!(expected, value);
 }
+
+/// Date, period in days and addresses with values ordered by rank
+pub type LeaderboardExpectation<'a> = (&'a str, u32, Vec<(Vec<u8>, &'a str)>);
+
+pub async fn simple_test_leaderboard(
+    test_name: &str,
+    chart: impl Chart,
+    expected: Vec<LeaderboardExpectation<'_>>,
+) {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name, None).await;
+    chart.create(&db).await.unwrap();
+    fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+    chart.update(&db, &blockscout, true).await.unwrap();
+    get_leaderboard_and_assert_eq(&db, &chart, &expected).await;
+
+    chart.update(&db, &blockscout, false).await.unwrap();
+    get_leaderboard_and_assert_eq(&db, &chart, &expected).await;
+}
+
+async fn get_leaderboard_and_assert_eq(
+    db: &DatabaseConnection,
+    chart: &impl Chart,
+    expected: &[LeaderboardExpectation<'_>],
+) {
+    for (date, period_days, expected) in expected {
+        let date = NaiveDate::from_str(date).unwrap();
+        let entries: Vec<_> =
+            get_leaderboard(db, chart.name(), *period_days, Some(date), LEADERBOARD_SIZE)
+                .await
+                .unwrap()
+                .map(|leaderboard| leaderboard.entries)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| (entry.address, entry.value))
+                .collect();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(address, value)| (address.clone(), value.to_string()))
+            .collect();
+        assert_eq!(expected, entries, "{date}, {period_days} days");
+    }
+}